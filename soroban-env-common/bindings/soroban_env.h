@@ -0,0 +1,732 @@
+// Generated from env.json by soroban_env_common::bindings, do not edit.
+
+#ifndef SOROBAN_ENV_H
+#define SOROBAN_ENV_H
+
+#include <stdint.h>
+
+#define SOROBAN_INTERFACE_VERSION 85899345920ULL
+
+// Val encoding.
+
+typedef uint64_t SorobanVal;
+typedef SorobanVal SorobanAddressObject;
+typedef SorobanVal SorobanBool;
+typedef SorobanVal SorobanBytesObject;
+typedef SorobanVal SorobanDurationObject;
+typedef SorobanVal SorobanError;
+typedef SorobanVal SorobanI128Object;
+typedef SorobanVal SorobanI256Object;
+typedef SorobanVal SorobanI256Val;
+typedef SorobanVal SorobanI64Object;
+typedef SorobanVal SorobanMapObject;
+typedef SorobanVal SorobanStorageType;
+typedef SorobanVal SorobanStringObject;
+typedef SorobanVal SorobanSymbol;
+typedef SorobanVal SorobanSymbolObject;
+typedef SorobanVal SorobanTimepointObject;
+typedef SorobanVal SorobanU128Object;
+typedef SorobanVal SorobanU256Object;
+typedef SorobanVal SorobanU256Val;
+typedef SorobanVal SorobanU32Val;
+typedef SorobanVal SorobanU64Object;
+typedef SorobanVal SorobanU64Val;
+typedef SorobanVal SorobanVecObject;
+typedef SorobanVal SorobanVoid;
+
+#define SOROBAN_TAG_BITS 8
+#define SOROBAN_MINOR_BITS 24
+#define SOROBAN_TAG_FALSE 0
+#define SOROBAN_TAG_TRUE 1
+#define SOROBAN_TAG_VOID 2
+#define SOROBAN_TAG_ERROR 3
+#define SOROBAN_TAG_U32_VAL 4
+#define SOROBAN_TAG_I32_VAL 5
+#define SOROBAN_TAG_U64_SMALL 6
+#define SOROBAN_TAG_I64_SMALL 7
+#define SOROBAN_TAG_TIMEPOINT_SMALL 8
+#define SOROBAN_TAG_DURATION_SMALL 9
+#define SOROBAN_TAG_U128_SMALL 10
+#define SOROBAN_TAG_I128_SMALL 11
+#define SOROBAN_TAG_U256_SMALL 12
+#define SOROBAN_TAG_I256_SMALL 13
+#define SOROBAN_TAG_SYMBOL_SMALL 14
+#define SOROBAN_TAG_U64_OBJECT 64
+#define SOROBAN_TAG_I64_OBJECT 65
+#define SOROBAN_TAG_TIMEPOINT_OBJECT 66
+#define SOROBAN_TAG_DURATION_OBJECT 67
+#define SOROBAN_TAG_U128_OBJECT 68
+#define SOROBAN_TAG_I128_OBJECT 69
+#define SOROBAN_TAG_U256_OBJECT 70
+#define SOROBAN_TAG_I256_OBJECT 71
+#define SOROBAN_TAG_BYTES_OBJECT 72
+#define SOROBAN_TAG_STRING_OBJECT 73
+#define SOROBAN_TAG_SYMBOL_OBJECT 74
+#define SOROBAN_TAG_VEC_OBJECT 75
+#define SOROBAN_TAG_MAP_OBJECT 76
+#define SOROBAN_TAG_ADDRESS_OBJECT 77
+#define SOROBAN_TAG_BAD 127
+
+#define SOROBAN_FALSE ((SorobanBool)SOROBAN_TAG_FALSE)
+#define SOROBAN_TRUE ((SorobanBool)SOROBAN_TAG_TRUE)
+#define SOROBAN_VOID ((SorobanVoid)SOROBAN_TAG_VOID)
+
+static inline uint8_t soroban_val_tag(SorobanVal v) {
+    return (uint8_t)v;
+}
+
+static inline SorobanVal soroban_val_from_major_minor(uint32_t major, uint32_t minor, uint8_t tag) {
+    return ((((uint64_t)major << SOROBAN_MINOR_BITS) | minor) << SOROBAN_TAG_BITS) | tag;
+}
+
+static inline uint32_t soroban_val_major(SorobanVal v) {
+    return (uint32_t)(v >> (SOROBAN_MINOR_BITS + SOROBAN_TAG_BITS));
+}
+
+static inline uint32_t soroban_val_minor(SorobanVal v) {
+    return (uint32_t)(v >> SOROBAN_TAG_BITS) & ((1u << SOROBAN_MINOR_BITS) - 1);
+}
+
+static inline SorobanBool soroban_bool(int b) {
+    return b ? SOROBAN_TRUE : SOROBAN_FALSE;
+}
+
+static inline SorobanU32Val soroban_u32_val(uint32_t x) {
+    return soroban_val_from_major_minor(x, 0, SOROBAN_TAG_U32_VAL);
+}
+
+static inline uint32_t soroban_u32_from_val(SorobanU32Val v) {
+    return soroban_val_major(v);
+}
+
+static inline SorobanVal soroban_i32_val(int32_t x) {
+    return soroban_val_from_major_minor((uint32_t)x, 0, SOROBAN_TAG_I32_VAL);
+}
+
+static inline int32_t soroban_i32_from_val(SorobanVal v) {
+    return (int32_t)soroban_val_major(v);
+}
+
+// Only valid for values that fit into 56 bits, larger ones need to be
+// converted to objects by the host.
+static inline SorobanVal soroban_u64_small_val(uint64_t x) {
+    return (x << SOROBAN_TAG_BITS) | SOROBAN_TAG_U64_SMALL;
+}
+
+static inline uint64_t soroban_u64_from_small_val(SorobanVal v) {
+    return v >> SOROBAN_TAG_BITS;
+}
+
+// Only valid for values that fit into 56 bits, larger ones need to be
+// converted to objects by the host.
+static inline SorobanVal soroban_i64_small_val(int64_t x) {
+    return ((uint64_t)x << SOROBAN_TAG_BITS) | SOROBAN_TAG_I64_SMALL;
+}
+
+static inline int64_t soroban_i64_from_small_val(SorobanVal v) {
+    return (int64_t)v >> SOROBAN_TAG_BITS;
+}
+
+static inline SorobanError soroban_error(uint32_t type, uint32_t code) {
+    return soroban_val_from_major_minor(code, type, SOROBAN_TAG_ERROR);
+}
+
+static inline uint32_t soroban_object_handle(SorobanVal v) {
+    return soroban_val_major(v);
+}
+
+// Encodes a symbol of up to 9 characters from [_0-9A-Za-z] as a small symbol.
+// Returns a value with the SOROBAN_TAG_BAD tag for any other string.
+static inline SorobanSymbol soroban_symbol_small(const char *s) {
+    uint64_t body = 0;
+    int n = 0;
+    for (; s[n] != '\0'; ++n) {
+        char ch = s[n];
+        uint64_t code;
+        if (n >= 9) {
+            return SOROBAN_TAG_BAD;
+        } else if (ch == '_') {
+            code = 1;
+        } else if (ch >= '0' && ch <= '9') {
+            code = 2 + (uint64_t)(ch - '0');
+        } else if (ch >= 'A' && ch <= 'Z') {
+            code = 12 + (uint64_t)(ch - 'A');
+        } else if (ch >= 'a' && ch <= 'z') {
+            code = 38 + (uint64_t)(ch - 'a');
+        } else {
+            return SOROBAN_TAG_BAD;
+        }
+        body = (body << 6) | code;
+    }
+    return (body << SOROBAN_TAG_BITS) | SOROBAN_TAG_SYMBOL_SMALL;
+}
+
+// Module 'context' (imported as "x").
+
+// Emit a diagnostic event containing a message and sequence of `Val`s.
+__attribute__((import_module("x"), import_name("_")))
+SorobanVoid log_from_linear_memory(SorobanU32Val msg_pos, SorobanU32Val msg_len, SorobanU32Val vals_pos, SorobanU32Val vals_len);
+
+// Compare two objects, or at least one object to a non-object, structurally. Returns -1 if a<b, 1 if a>b, or 0 if a==b.
+__attribute__((import_module("x"), import_name("0")))
+int64_t obj_cmp(SorobanVal a, SorobanVal b);
+
+// Records a contract event. `topics` is expected to be a `SCVec` with length <= 4 that cannot contain `Vec`, `Map`, or `Bytes` with length > 32.
+__attribute__((import_module("x"), import_name("1")))
+SorobanVoid contract_event(SorobanVecObject topics, SorobanVal data);
+
+// Return the protocol version of the current ledger as a u32.
+__attribute__((import_module("x"), import_name("2")))
+SorobanU32Val get_ledger_version(void);
+
+// Return the sequence number of the current ledger as a u32.
+__attribute__((import_module("x"), import_name("3")))
+SorobanU32Val get_ledger_sequence(void);
+
+// Return the timestamp number of the current ledger as a u64.
+__attribute__((import_module("x"), import_name("4")))
+SorobanU64Val get_ledger_timestamp(void);
+
+// Causes the currently executing contract to fail immediately with a provided error code, which must be of error-type `ScErrorType::Contract`. Does not actually return.
+__attribute__((import_module("x"), import_name("5")))
+SorobanVoid fail_with_error(SorobanError error);
+
+// Return the network id (sha256 hash of network passphrase) of the current ledger as `Bytes`. The value is always 32 bytes in length.
+__attribute__((import_module("x"), import_name("6")))
+SorobanBytesObject get_ledger_network_id(void);
+
+// Get the Address object for the current contract.
+__attribute__((import_module("x"), import_name("7")))
+SorobanAddressObject get_current_contract_address(void);
+
+// Returns the max ledger sequence that an entry can live to (inclusive).
+__attribute__((import_module("x"), import_name("8")))
+SorobanU32Val get_max_live_until_ledger(void);
+
+// Module 'int' (imported as "i").
+
+// Convert a `u64` to an object containing a `u64`.
+__attribute__((import_module("i"), import_name("_")))
+SorobanU64Object obj_from_u64(uint64_t v);
+
+// Convert an object containing a `u64` to a `u64`.
+__attribute__((import_module("i"), import_name("0")))
+uint64_t obj_to_u64(SorobanU64Object obj);
+
+// Convert an `i64` to an object containing an `i64`.
+__attribute__((import_module("i"), import_name("1")))
+SorobanI64Object obj_from_i64(int64_t v);
+
+// Convert an object containing an `i64` to an `i64`.
+__attribute__((import_module("i"), import_name("2")))
+int64_t obj_to_i64(SorobanI64Object obj);
+
+// Convert the high and low 64-bit words of a u128 to an object containing a u128.
+__attribute__((import_module("i"), import_name("3")))
+SorobanU128Object obj_from_u128_pieces(uint64_t hi, uint64_t lo);
+
+// Extract the low 64 bits from an object containing a u128.
+__attribute__((import_module("i"), import_name("4")))
+uint64_t obj_to_u128_lo64(SorobanU128Object obj);
+
+// Extract the high 64 bits from an object containing a u128.
+__attribute__((import_module("i"), import_name("5")))
+uint64_t obj_to_u128_hi64(SorobanU128Object obj);
+
+// Convert the high and low 64-bit words of an i128 to an object containing an i128.
+__attribute__((import_module("i"), import_name("6")))
+SorobanI128Object obj_from_i128_pieces(int64_t hi, uint64_t lo);
+
+// Extract the low 64 bits from an object containing an i128.
+__attribute__((import_module("i"), import_name("7")))
+uint64_t obj_to_i128_lo64(SorobanI128Object obj);
+
+// Extract the high 64 bits from an object containing an i128.
+__attribute__((import_module("i"), import_name("8")))
+int64_t obj_to_i128_hi64(SorobanI128Object obj);
+
+// Convert the four 64-bit words of a u256 (big-endian) to an object containing a u256.
+__attribute__((import_module("i"), import_name("9")))
+SorobanU256Object obj_from_u256_pieces(uint64_t hi_hi, uint64_t hi_lo, uint64_t lo_hi, uint64_t lo_lo);
+
+// Create a U256 `Val` from its representation as a byte array in big endian.
+__attribute__((import_module("i"), import_name("a")))
+SorobanU256Val u256_val_from_be_bytes(SorobanBytesObject bytes);
+
+// Return the memory representation of this U256 `Val` as a byte array in big endian byte order.
+__attribute__((import_module("i"), import_name("b")))
+SorobanBytesObject u256_val_to_be_bytes(SorobanU256Val val);
+
+// Extract the highest 64-bits (bits 192-255) from an object containing a u256.
+__attribute__((import_module("i"), import_name("c")))
+uint64_t obj_to_u256_hi_hi(SorobanU256Object obj);
+
+// Extract bits 128-191 from an object containing a u256.
+__attribute__((import_module("i"), import_name("d")))
+uint64_t obj_to_u256_hi_lo(SorobanU256Object obj);
+
+// Extract bits 64-127 from an object containing a u256.
+__attribute__((import_module("i"), import_name("e")))
+uint64_t obj_to_u256_lo_hi(SorobanU256Object obj);
+
+// Extract the lowest 64-bits (bits 0-63) from an object containing a u256.
+__attribute__((import_module("i"), import_name("f")))
+uint64_t obj_to_u256_lo_lo(SorobanU256Object obj);
+
+// Convert the four 64-bit words of an i256 (big-endian) to an object containing an i256.
+__attribute__((import_module("i"), import_name("g")))
+SorobanI256Object obj_from_i256_pieces(int64_t hi_hi, uint64_t hi_lo, uint64_t lo_hi, uint64_t lo_lo);
+
+// Create a I256 `Val` from its representation as a byte array in big endian.
+__attribute__((import_module("i"), import_name("h")))
+SorobanI256Val i256_val_from_be_bytes(SorobanBytesObject bytes);
+
+// Return the memory representation of this I256 `Val` as a byte array in big endian byte order.
+__attribute__((import_module("i"), import_name("i")))
+SorobanBytesObject i256_val_to_be_bytes(SorobanI256Val val);
+
+// Extract the highest 64-bits (bits 192-255) from an object containing an i256.
+__attribute__((import_module("i"), import_name("j")))
+int64_t obj_to_i256_hi_hi(SorobanI256Object obj);
+
+// Extract bits 128-191 from an object containing an i256.
+__attribute__((import_module("i"), import_name("k")))
+uint64_t obj_to_i256_hi_lo(SorobanI256Object obj);
+
+// Extract bits 64-127 from an object containing an i256.
+__attribute__((import_module("i"), import_name("l")))
+uint64_t obj_to_i256_lo_hi(SorobanI256Object obj);
+
+// Extract the lowest 64-bits (bits 0-63) from an object containing an i256.
+__attribute__((import_module("i"), import_name("m")))
+uint64_t obj_to_i256_lo_lo(SorobanI256Object obj);
+
+// Performs checked integer addition. Computes `lhs + rhs`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("n")))
+SorobanU256Val u256_add(SorobanU256Val lhs, SorobanU256Val rhs);
+
+// Performs checked integer subtraction. Computes `lhs - rhs`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("o")))
+SorobanU256Val u256_sub(SorobanU256Val lhs, SorobanU256Val rhs);
+
+// Performs checked integer multiplication. Computes `lhs * rhs`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("p")))
+SorobanU256Val u256_mul(SorobanU256Val lhs, SorobanU256Val rhs);
+
+// Performs checked integer division. Computes `lhs / rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+__attribute__((import_module("i"), import_name("q")))
+SorobanU256Val u256_div(SorobanU256Val lhs, SorobanU256Val rhs);
+
+// Performs checked Euclidean modulo. Computes `lhs % rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+__attribute__((import_module("i"), import_name("r")))
+SorobanU256Val u256_rem_euclid(SorobanU256Val lhs, SorobanU256Val rhs);
+
+// Performs checked exponentiation. Computes `lhs.exp(rhs)`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("s")))
+SorobanU256Val u256_pow(SorobanU256Val lhs, SorobanU32Val rhs);
+
+// Performs checked shift left. Computes `lhs << rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+__attribute__((import_module("i"), import_name("t")))
+SorobanU256Val u256_shl(SorobanU256Val lhs, SorobanU32Val rhs);
+
+// Performs checked shift right. Computes `lhs >> rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+__attribute__((import_module("i"), import_name("u")))
+SorobanU256Val u256_shr(SorobanU256Val lhs, SorobanU32Val rhs);
+
+// Performs checked integer addition. Computes `lhs + rhs`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("v")))
+SorobanI256Val i256_add(SorobanI256Val lhs, SorobanI256Val rhs);
+
+// Performs checked integer subtraction. Computes `lhs - rhs`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("w")))
+SorobanI256Val i256_sub(SorobanI256Val lhs, SorobanI256Val rhs);
+
+// Performs checked integer multiplication. Computes `lhs * rhs`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("x")))
+SorobanI256Val i256_mul(SorobanI256Val lhs, SorobanI256Val rhs);
+
+// Performs checked integer division. Computes `lhs / rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+__attribute__((import_module("i"), import_name("y")))
+SorobanI256Val i256_div(SorobanI256Val lhs, SorobanI256Val rhs);
+
+// Performs checked Euclidean modulo. Computes `lhs % rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+__attribute__((import_module("i"), import_name("z")))
+SorobanI256Val i256_rem_euclid(SorobanI256Val lhs, SorobanI256Val rhs);
+
+// Performs checked exponentiation. Computes `lhs.exp(rhs)`, returning `ScError` if overflow occurred.
+__attribute__((import_module("i"), import_name("A")))
+SorobanI256Val i256_pow(SorobanI256Val lhs, SorobanU32Val rhs);
+
+// Performs checked shift left. Computes `lhs << rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+__attribute__((import_module("i"), import_name("B")))
+SorobanI256Val i256_shl(SorobanI256Val lhs, SorobanU32Val rhs);
+
+// Performs checked shift right. Computes `lhs >> rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+__attribute__((import_module("i"), import_name("C")))
+SorobanI256Val i256_shr(SorobanI256Val lhs, SorobanU32Val rhs);
+
+// Convert a `u64` to a `Timepoint` object.
+__attribute__((import_module("i"), import_name("D")))
+SorobanTimepointObject timepoint_obj_from_u64(uint64_t v);
+
+// Convert a `Timepoint` object to a `u64`.
+__attribute__((import_module("i"), import_name("E")))
+uint64_t timepoint_obj_to_u64(SorobanTimepointObject obj);
+
+// Convert a `u64` to a `Duration` object.
+__attribute__((import_module("i"), import_name("F")))
+SorobanDurationObject duration_obj_from_u64(uint64_t v);
+
+// Convert a `Duration` object a `u64`.
+__attribute__((import_module("i"), import_name("G")))
+uint64_t duration_obj_to_u64(SorobanDurationObject obj);
+
+// Module 'map' (imported as "m").
+
+// Create an empty new map.
+__attribute__((import_module("m"), import_name("_")))
+SorobanMapObject map_new(void);
+
+// Insert a key/value mapping into an existing map, and return the map object handle. If the map already has a mapping for the given key, the previous value is overwritten.
+__attribute__((import_module("m"), import_name("0")))
+SorobanMapObject map_put(SorobanMapObject m, SorobanVal k, SorobanVal v);
+
+// Get the value for a key from a map. Traps if key is not found.
+__attribute__((import_module("m"), import_name("1")))
+SorobanVal map_get(SorobanMapObject m, SorobanVal k);
+
+// Remove a key/value mapping from a map if it exists, traps if doesn't.
+__attribute__((import_module("m"), import_name("2")))
+SorobanMapObject map_del(SorobanMapObject m, SorobanVal k);
+
+// Get the size of a map.
+__attribute__((import_module("m"), import_name("3")))
+SorobanU32Val map_len(SorobanMapObject m);
+
+// Test for the presence of a key in a map. Returns Bool.
+__attribute__((import_module("m"), import_name("4")))
+SorobanBool map_has(SorobanMapObject m, SorobanVal k);
+
+// Get the key from a map at position `i`. If `i` is an invalid position, return ScError.
+__attribute__((import_module("m"), import_name("5")))
+SorobanVal map_key_by_pos(SorobanMapObject m, SorobanU32Val i);
+
+// Get the value from a map at position `i`. If `i` is an invalid position, return ScError.
+__attribute__((import_module("m"), import_name("6")))
+SorobanVal map_val_by_pos(SorobanMapObject m, SorobanU32Val i);
+
+// Return a new vector containing all the keys in a map. The new vector is ordered in the original map's key-sorted order.
+__attribute__((import_module("m"), import_name("7")))
+SorobanVecObject map_keys(SorobanMapObject m);
+
+// Return a new vector containing all the values in a map. The new vector is ordered in the original map's key-sorted order.
+__attribute__((import_module("m"), import_name("8")))
+SorobanVecObject map_values(SorobanMapObject m);
+
+// Return a new map initialized from a pair of equal-length arrays, one for keys and one for values, given by a pair of linear-memory addresses and a length in Vals.
+__attribute__((import_module("m"), import_name("9")))
+SorobanMapObject map_new_from_linear_memory(SorobanU32Val keys_pos, SorobanU32Val vals_pos, SorobanU32Val len);
+
+// Copy Vals from `map` to the array `vals_pos`, selecting only the keys identified by the array `keys_pos`. Both arrays have `len` elements and are identified by linear-memory addresses.
+__attribute__((import_module("m"), import_name("a")))
+SorobanVoid map_unpack_to_linear_memory(SorobanMapObject map, SorobanU32Val keys_pos, SorobanU32Val vals_pos, SorobanU32Val len);
+
+// Module 'vec' (imported as "v").
+
+// Creates an empty new vector.
+__attribute__((import_module("v"), import_name("_")))
+SorobanVecObject vec_new(void);
+
+// Update the value at index `i` in the vector. Return the new vector. Trap if the index is out of bounds.
+__attribute__((import_module("v"), import_name("0")))
+SorobanVecObject vec_put(SorobanVecObject v, SorobanU32Val i, SorobanVal x);
+
+// Returns the element at index `i` of the vector. Traps if the index is out of bound.
+__attribute__((import_module("v"), import_name("1")))
+SorobanVal vec_get(SorobanVecObject v, SorobanU32Val i);
+
+// Delete an element in a vector at index `i`, shifting all elements after it to the left. Return the new vector. Traps if the index is out of bound.
+__attribute__((import_module("v"), import_name("2")))
+SorobanVecObject vec_del(SorobanVecObject v, SorobanU32Val i);
+
+// Returns length of the vector.
+__attribute__((import_module("v"), import_name("3")))
+SorobanU32Val vec_len(SorobanVecObject v);
+
+// Push a value to the front of a vector.
+__attribute__((import_module("v"), import_name("4")))
+SorobanVecObject vec_push_front(SorobanVecObject v, SorobanVal x);
+
+// Removes the first element from the vector and returns the new vector. Traps if original vector is empty.
+__attribute__((import_module("v"), import_name("5")))
+SorobanVecObject vec_pop_front(SorobanVecObject v);
+
+// Appends an element to the back of the vector.
+__attribute__((import_module("v"), import_name("6")))
+SorobanVecObject vec_push_back(SorobanVecObject v, SorobanVal x);
+
+// Removes the last element from the vector and returns the new vector. Traps if original vector is empty.
+__attribute__((import_module("v"), import_name("7")))
+SorobanVecObject vec_pop_back(SorobanVecObject v);
+
+// Return the first element in the vector. Traps if the vector is empty
+__attribute__((import_module("v"), import_name("8")))
+SorobanVal vec_front(SorobanVecObject v);
+
+// Return the last element in the vector. Traps if the vector is empty
+__attribute__((import_module("v"), import_name("9")))
+SorobanVal vec_back(SorobanVecObject v);
+
+// Inserts an element at index `i` within the vector, shifting all elements after it to the right. Traps if the index is out of bound
+__attribute__((import_module("v"), import_name("a")))
+SorobanVecObject vec_insert(SorobanVecObject v, SorobanU32Val i, SorobanVal x);
+
+// Clone the vector `v1`, then moves all the elements of vector `v2` into it. Return the new vector. Traps if number of elements in the vector overflows a u32.
+__attribute__((import_module("v"), import_name("b")))
+SorobanVecObject vec_append(SorobanVecObject v1, SorobanVecObject v2);
+
+// Copy the elements from `start` index until `end` index, exclusive, in the vector and create a new vector from it. Return the new vector. Traps if the index is out of bound.
+__attribute__((import_module("v"), import_name("c")))
+SorobanVecObject vec_slice(SorobanVecObject v, SorobanU32Val start, SorobanU32Val end);
+
+// Get the index of the first occurrence of a given element in the vector. Returns the u32 index of the value if it's there. Otherwise, it returns `Void`.
+__attribute__((import_module("v"), import_name("d")))
+SorobanVal vec_first_index_of(SorobanVecObject v, SorobanVal x);
+
+// Get the index of the last occurrence of a given element in the vector. Returns the u32 index of the value if it's there. Otherwise, it returns `Void`.
+__attribute__((import_module("v"), import_name("e")))
+SorobanVal vec_last_index_of(SorobanVecObject v, SorobanVal x);
+
+// Binary search a sorted vector for a given element. If it exists, the high 32 bits of the return value is 0x0000_0001 and the low 32 bits contain the u32 index of the element. If it does not exist, the high 32 bits of the return value is 0x0000_0000 and the low-32 bits contain the u32 index at which the element would need to be inserted into the vector to maintain sorted order.
+__attribute__((import_module("v"), import_name("f")))
+uint64_t vec_binary_search(SorobanVecObject v, SorobanVal x);
+
+// Return a new vec initialized from an input slice of Vals given by a linear-memory address and length in Vals.
+__attribute__((import_module("v"), import_name("g")))
+SorobanVecObject vec_new_from_linear_memory(SorobanU32Val vals_pos, SorobanU32Val len);
+
+// Copy the Vals of a vec into an array at a given linear-memory address and length in Vals.
+__attribute__((import_module("v"), import_name("h")))
+SorobanVoid vec_unpack_to_linear_memory(SorobanVecObject vec, SorobanU32Val vals_pos, SorobanU32Val len);
+
+// Module 'ledger' (imported as "l").
+
+__attribute__((import_module("l"), import_name("_")))
+SorobanVoid put_contract_data(SorobanVal k, SorobanVal v, SorobanStorageType t);
+
+__attribute__((import_module("l"), import_name("0")))
+SorobanBool has_contract_data(SorobanVal k, SorobanStorageType t);
+
+__attribute__((import_module("l"), import_name("1")))
+SorobanVal get_contract_data(SorobanVal k, SorobanStorageType t);
+
+__attribute__((import_module("l"), import_name("2")))
+SorobanVoid del_contract_data(SorobanVal k, SorobanStorageType t);
+
+// Creates the contract instance on behalf of `deployer`. `deployer` must authorize this call via Soroban auth framework, i.e. this calls `deployer.require_auth` with respective arguments. `wasm_hash` must be a hash of the contract code that has already been uploaded on this network. `salt` is used to create a unique contract id. Returns the address of the created contract.
+__attribute__((import_module("l"), import_name("3")))
+SorobanAddressObject create_contract(SorobanAddressObject deployer, SorobanBytesObject wasm_hash, SorobanBytesObject salt);
+
+// Creates the instance of Stellar Asset contract corresponding to the provided asset. `serialized_asset` is `stellar::Asset` XDR serialized to bytes format. Returns the address of the created contract.
+__attribute__((import_module("l"), import_name("4")))
+SorobanAddressObject create_asset_contract(SorobanBytesObject serialized_asset);
+
+// Uploads provided `wasm` bytecode to the network and returns its identifier (SHA-256 hash). No-op in case if the same Wasm object already exists.
+__attribute__((import_module("l"), import_name("5")))
+SorobanBytesObject upload_wasm(SorobanBytesObject wasm);
+
+// Replaces the executable of the current contract with the provided Wasm code identified by a hash. Wasm entry corresponding to the hash has to already be present in the ledger. The update happens only after the current contract invocation has successfully finished, so this can be safely called in the middle of a function.
+__attribute__((import_module("l"), import_name("6")))
+SorobanVoid update_current_contract_wasm(SorobanBytesObject hash);
+
+// If the entry's TTL is below `threshold` ledgers, extend `live_until_ledger_seq` such that TTL == `extend_to`, where TTL is defined as live_until_ledger_seq - current ledger
+__attribute__((import_module("l"), import_name("7")))
+SorobanVoid extend_contract_data_ttl(SorobanVal k, SorobanStorageType t, SorobanU32Val threshold, SorobanU32Val extend_to);
+
+// If the TTL for the current contract instance and code (if applicable) is below `threshold` ledgers, extend `live_until_ledger_seq` such that TTL == `extend_to`, where TTL is defined as live_until_ledger_seq - current ledger
+__attribute__((import_module("l"), import_name("8")))
+SorobanVoid extend_current_contract_instance_and_code_ttl(SorobanU32Val threshold, SorobanU32Val extend_to);
+
+// If the TTL for the provided contract instance and code (if applicable) is below `threshold` ledgers, extend `live_until_ledger_seq` such that TTL == `extend_to`, where TTL is defined as live_until_ledger_seq - current ledger
+__attribute__((import_module("l"), import_name("9")))
+SorobanVoid extend_contract_instance_and_code_ttl(SorobanAddressObject contract, SorobanU32Val threshold, SorobanU32Val extend_to);
+
+// Get the id of a contract without creating it. `deployer` is address of the contract deployer. `salt` is used to create a unique contract id. Returns the address of the would-be contract.
+__attribute__((import_module("l"), import_name("a")))
+SorobanAddressObject get_contract_id(SorobanAddressObject deployer, SorobanBytesObject salt);
+
+// Get the id of the Stellar Asset contract corresponding to the provided asset without creating the instance. `serialized_asset` is `stellar::Asset` XDR serialized to bytes format. Returns the address of the would-be asset contract.
+__attribute__((import_module("l"), import_name("b")))
+SorobanAddressObject get_asset_contract_id(SorobanBytesObject serialized_asset);
+
+// Module 'call' (imported as "d").
+
+// Calls a function in another contract with arguments contained in vector `args`. If the call is successful, returns the result of the called function. Traps otherwise.
+__attribute__((import_module("d"), import_name("_")))
+SorobanVal call(SorobanAddressObject contract, SorobanSymbol func, SorobanVecObject args);
+
+// Calls a function in another contract with arguments contained in vector `args`, returning either the result of the called function or an `Error` if the called function failed. The returned error is either a custom `ContractError` that the called contract returns explicitly, or an error with type `Context` and code `InvalidAction` in case of any other error in the called contract (such as a host function failure that caused a trap). `try_call` might trap in a few scenarios where the error can't be meaningfully recovered from, such as running out of budget.
+__attribute__((import_module("d"), import_name("0")))
+SorobanVal try_call(SorobanAddressObject contract, SorobanSymbol func, SorobanVecObject args);
+
+// Module 'buf' (imported as "b").
+
+// Serializes an (SC)Val into XDR opaque `Bytes` object.
+__attribute__((import_module("b"), import_name("_")))
+SorobanBytesObject serialize_to_bytes(SorobanVal v);
+
+// Deserialize a `Bytes` object to get back the (SC)Val.
+__attribute__((import_module("b"), import_name("0")))
+SorobanVal deserialize_from_bytes(SorobanBytesObject b);
+
+// Copies a slice of bytes from a `Bytes` object specified at offset `b_pos` with length `len` into the linear memory at position `lm_pos`. Traps if either the `Bytes` object or the linear memory doesn't have enough bytes.
+__attribute__((import_module("b"), import_name("1")))
+SorobanVoid bytes_copy_to_linear_memory(SorobanBytesObject b, SorobanU32Val b_pos, SorobanU32Val lm_pos, SorobanU32Val len);
+
+// Copies a segment of the linear memory specified at position `lm_pos` with length `len`, into a `Bytes` object at offset `b_pos`. The `Bytes` object may grow in size to accommodate the new bytes. Traps if the linear memory doesn't have enough bytes.
+__attribute__((import_module("b"), import_name("2")))
+SorobanBytesObject bytes_copy_from_linear_memory(SorobanBytesObject b, SorobanU32Val b_pos, SorobanU32Val lm_pos, SorobanU32Val len);
+
+// Constructs a new `Bytes` object initialized with bytes copied from a linear memory slice specified at position `lm_pos` with length `len`.
+__attribute__((import_module("b"), import_name("3")))
+SorobanBytesObject bytes_new_from_linear_memory(SorobanU32Val lm_pos, SorobanU32Val len);
+
+// Create an empty new `Bytes` object.
+__attribute__((import_module("b"), import_name("4")))
+SorobanBytesObject bytes_new(void);
+
+// Update the value at index `i` in the `Bytes` object. Return the new `Bytes`. Trap if the index is out of bounds.
+__attribute__((import_module("b"), import_name("5")))
+SorobanBytesObject bytes_put(SorobanBytesObject b, SorobanU32Val i, SorobanU32Val u);
+
+// Returns the element at index `i` of the `Bytes` object. Traps if the index is out of bound.
+__attribute__((import_module("b"), import_name("6")))
+SorobanU32Val bytes_get(SorobanBytesObject b, SorobanU32Val i);
+
+// Delete an element in a `Bytes` object at index `i`, shifting all elements after it to the left. Return the new `Bytes`. Traps if the index is out of bound.
+__attribute__((import_module("b"), import_name("7")))
+SorobanBytesObject bytes_del(SorobanBytesObject b, SorobanU32Val i);
+
+// Returns length of the `Bytes` object.
+__attribute__((import_module("b"), import_name("8")))
+SorobanU32Val bytes_len(SorobanBytesObject b);
+
+// Appends an element to the back of the `Bytes` object.
+__attribute__((import_module("b"), import_name("9")))
+SorobanBytesObject bytes_push(SorobanBytesObject b, SorobanU32Val u);
+
+// Removes the last element from the `Bytes` object and returns the new `Bytes`. Traps if original `Bytes` is empty.
+__attribute__((import_module("b"), import_name("a")))
+SorobanBytesObject bytes_pop(SorobanBytesObject b);
+
+// Return the first element in the `Bytes` object. Traps if the `Bytes` is empty
+__attribute__((import_module("b"), import_name("b")))
+SorobanU32Val bytes_front(SorobanBytesObject b);
+
+// Return the last element in the `Bytes` object. Traps if the `Bytes` is empty
+__attribute__((import_module("b"), import_name("c")))
+SorobanU32Val bytes_back(SorobanBytesObject b);
+
+// Inserts an element at index `i` within the `Bytes` object, shifting all elements after it to the right. Traps if the index is out of bound
+__attribute__((import_module("b"), import_name("d")))
+SorobanBytesObject bytes_insert(SorobanBytesObject b, SorobanU32Val i, SorobanU32Val u);
+
+// Clone the `Bytes` object `b1`, then moves all the elements of `Bytes` object `b2` into it. Return the new `Bytes`. Traps if its length overflows a u32.
+__attribute__((import_module("b"), import_name("e")))
+SorobanBytesObject bytes_append(SorobanBytesObject b1, SorobanBytesObject b2);
+
+// Copies the elements from `start` index until `end` index, exclusive, in the `Bytes` object and creates a new `Bytes` from it. Returns the new `Bytes`. Traps if the index is out of bound.
+__attribute__((import_module("b"), import_name("f")))
+SorobanBytesObject bytes_slice(SorobanBytesObject b, SorobanU32Val start, SorobanU32Val end);
+
+// Copies a slice of bytes from a `String` object specified at offset `s_pos` with length `len` into the linear memory at position `lm_pos`. Traps if either the `String` object or the linear memory doesn't have enough bytes.
+__attribute__((import_module("b"), import_name("g")))
+SorobanVoid string_copy_to_linear_memory(SorobanStringObject s, SorobanU32Val s_pos, SorobanU32Val lm_pos, SorobanU32Val len);
+
+// Copies a slice of bytes from a `Symbol` object specified at offset `s_pos` with length `len` into the linear memory at position `lm_pos`. Traps if either the `String` object or the linear memory doesn't have enough bytes.
+__attribute__((import_module("b"), import_name("h")))
+SorobanVoid symbol_copy_to_linear_memory(SorobanSymbolObject s, SorobanU32Val s_pos, SorobanU32Val lm_pos, SorobanU32Val len);
+
+// Constructs a new `String` object initialized with bytes copied from a linear memory slice specified at position `lm_pos` with length `len`.
+__attribute__((import_module("b"), import_name("i")))
+SorobanStringObject string_new_from_linear_memory(SorobanU32Val lm_pos, SorobanU32Val len);
+
+// Constructs a new `Symbol` object initialized with bytes copied from a linear memory slice specified at position `lm_pos` with length `len`.
+__attribute__((import_module("b"), import_name("j")))
+SorobanSymbolObject symbol_new_from_linear_memory(SorobanU32Val lm_pos, SorobanU32Val len);
+
+// Returns length of the `String` object.
+__attribute__((import_module("b"), import_name("k")))
+SorobanU32Val string_len(SorobanStringObject s);
+
+// Returns length of the `Symbol` object.
+__attribute__((import_module("b"), import_name("l")))
+SorobanU32Val symbol_len(SorobanSymbolObject s);
+
+// Return the index of a Symbol in an array of linear-memory byte-slices, or trap if not found.
+__attribute__((import_module("b"), import_name("m")))
+SorobanU32Val symbol_index_in_linear_memory(SorobanSymbol sym, SorobanU32Val slices_pos, SorobanU32Val len);
+
+// Module 'crypto' (imported as "c").
+
+__attribute__((import_module("c"), import_name("_")))
+SorobanBytesObject compute_hash_sha256(SorobanBytesObject x);
+
+__attribute__((import_module("c"), import_name("0")))
+SorobanVoid verify_sig_ed25519(SorobanBytesObject k, SorobanBytesObject x, SorobanBytesObject s);
+
+// Returns the keccak256 hash of given input bytes.
+__attribute__((import_module("c"), import_name("1")))
+SorobanBytesObject compute_hash_keccak256(SorobanBytesObject x);
+
+// Recovers the SEC-1-encoded ECDSA secp256k1 public key that produced a given 64-byte signature over a given 32-byte message digest, for a given recovery_id byte.
+__attribute__((import_module("c"), import_name("2")))
+SorobanBytesObject recover_key_ecdsa_secp256k1(SorobanBytesObject msg_digest, SorobanBytesObject signature, SorobanU32Val recovery_id);
+
+// Module 'address' (imported as "a").
+
+// Checks if the address has authorized the invocation of the current contract function with the provided arguments. Traps if the invocation hasn't been authorized.
+__attribute__((import_module("a"), import_name("_")))
+SorobanVoid require_auth_for_args(SorobanAddressObject address, SorobanVecObject args);
+
+// Checks if the address has authorized the invocation of the current contract function with all the arguments of the invocation. Traps if the invocation hasn't been authorized.
+__attribute__((import_module("a"), import_name("0")))
+SorobanVoid require_auth(SorobanAddressObject address);
+
+// Converts a provided Stellar strkey address of an account or a contract ('G...' or 'C...' respectively) to an address object. `strkey` can be either `BytesObject` or `StringObject` (the contents should represent the `G.../C...` string in both cases). Any other valid or invalid strkey (e.g. 'S...') will trigger an error. Prefer directly using the Address objects whenever possible. This is only useful in the context of custom messaging protocols (e.g. cross-chain).
+__attribute__((import_module("a"), import_name("1")))
+SorobanAddressObject strkey_to_address(SorobanVal strkey);
+
+// Converts a provided address to Stellar strkey format ('G...' for account or 'C...' for contract). Prefer directly using the Address objects whenever possible. This is only useful in the context of custom messaging protocols (e.g. cross-chain).
+__attribute__((import_module("a"), import_name("2")))
+SorobanStringObject address_to_strkey(SorobanAddressObject address);
+
+// Authorizes sub-contract calls for the next contract call on behalf of the current contract. Every entry in the argument vector corresponds to `InvokerContractAuthEntry` contract type that authorizes a tree of `require_auth` calls on behalf of the current contract. The entries must not contain any authorizations for the direct contract call, i.e. if current contract needs to call contract function F1 that calls function F2 both of which require auth, only F2 should be present in `auth_entries`.
+__attribute__((import_module("a"), import_name("3")))
+SorobanVoid authorize_as_curr_contract(SorobanVecObject auth_entires);
+
+// Module 'test' (imported as "t").
+
+// A dummy function taking 0 arguments and performs no-op. This function is for test purpose only, for measuring the roundtrip cost of invoking a host function, i.e. host->Vm->host.
+__attribute__((import_module("t"), import_name("_")))
+SorobanVal dummy0(void);
+
+// Module 'prng' (imported as "p").
+
+// Reseed the frame-local PRNG with a given BytesObject, which should be 32 bytes long.
+__attribute__((import_module("p"), import_name("_")))
+SorobanVoid prng_reseed(SorobanBytesObject seed);
+
+// Construct a new BytesObject of the given length filled with bytes drawn from the frame-local PRNG.
+__attribute__((import_module("p"), import_name("0")))
+SorobanBytesObject prng_bytes_new(SorobanU32Val length);
+
+// Return a u64 uniformly sampled from the inclusive range [lo,hi] by the frame-local PRNG.
+__attribute__((import_module("p"), import_name("1")))
+uint64_t prng_u64_in_inclusive_range(uint64_t lo, uint64_t hi);
+
+// Return a (Fisher-Yates) shuffled clone of a given vector, using the frame-local PRNG.
+__attribute__((import_module("p"), import_name("2")))
+SorobanVecObject prng_vec_shuffle(SorobanVecObject vec);
+
+#endif // SOROBAN_ENV_H
@@ -0,0 +1,725 @@
+// Generated from env.json by soroban_env_common::bindings, do not edit.
+
+export const INTERFACE_VERSION: u64 = 85899345920;
+
+// Val encoding.
+
+export type Val = u64;
+export type AddressObject = Val;
+export type Bool = Val;
+export type BytesObject = Val;
+export type DurationObject = Val;
+export type ErrorVal = Val;
+export type I128Object = Val;
+export type I256Object = Val;
+export type I256Val = Val;
+export type I64Object = Val;
+export type MapObject = Val;
+export type StorageType = Val;
+export type StringObject = Val;
+export type SymbolVal = Val;
+export type SymbolObject = Val;
+export type TimepointObject = Val;
+export type U128Object = Val;
+export type U256Object = Val;
+export type U256Val = Val;
+export type U32Val = Val;
+export type U64Object = Val;
+export type U64Val = Val;
+export type VecObject = Val;
+export type Void = Val;
+
+export const TAG_BITS: u64 = 8;
+export const MINOR_BITS: u64 = 24;
+export const TAG_FALSE: u64 = 0;
+export const TAG_TRUE: u64 = 1;
+export const TAG_VOID: u64 = 2;
+export const TAG_ERROR: u64 = 3;
+export const TAG_U32_VAL: u64 = 4;
+export const TAG_I32_VAL: u64 = 5;
+export const TAG_U64_SMALL: u64 = 6;
+export const TAG_I64_SMALL: u64 = 7;
+export const TAG_TIMEPOINT_SMALL: u64 = 8;
+export const TAG_DURATION_SMALL: u64 = 9;
+export const TAG_U128_SMALL: u64 = 10;
+export const TAG_I128_SMALL: u64 = 11;
+export const TAG_U256_SMALL: u64 = 12;
+export const TAG_I256_SMALL: u64 = 13;
+export const TAG_SYMBOL_SMALL: u64 = 14;
+export const TAG_U64_OBJECT: u64 = 64;
+export const TAG_I64_OBJECT: u64 = 65;
+export const TAG_TIMEPOINT_OBJECT: u64 = 66;
+export const TAG_DURATION_OBJECT: u64 = 67;
+export const TAG_U128_OBJECT: u64 = 68;
+export const TAG_I128_OBJECT: u64 = 69;
+export const TAG_U256_OBJECT: u64 = 70;
+export const TAG_I256_OBJECT: u64 = 71;
+export const TAG_BYTES_OBJECT: u64 = 72;
+export const TAG_STRING_OBJECT: u64 = 73;
+export const TAG_SYMBOL_OBJECT: u64 = 74;
+export const TAG_VEC_OBJECT: u64 = 75;
+export const TAG_MAP_OBJECT: u64 = 76;
+export const TAG_ADDRESS_OBJECT: u64 = 77;
+export const TAG_BAD: u64 = 127;
+
+export const FALSE: Bool = TAG_FALSE;
+export const TRUE: Bool = TAG_TRUE;
+export const VOID: Void = TAG_VOID;
+
+export function valTag(v: Val): u8 {
+  return <u8>v;
+}
+
+export function valFromMajorMinor(major: u32, minor: u32, tag: u64): Val {
+  return ((((<u64>major) << MINOR_BITS) | <u64>minor) << TAG_BITS) | tag;
+}
+
+export function valMajor(v: Val): u32 {
+  return <u32>(v >> (MINOR_BITS + TAG_BITS));
+}
+
+export function valMinor(v: Val): u32 {
+  return <u32>(v >> TAG_BITS) & ((1 << <u32>MINOR_BITS) - 1);
+}
+
+export function boolVal(b: bool): Bool {
+  return b ? TRUE : FALSE;
+}
+
+export function u32Val(x: u32): U32Val {
+  return valFromMajorMinor(x, 0, TAG_U32_VAL);
+}
+
+export function u32FromVal(v: U32Val): u32 {
+  return valMajor(v);
+}
+
+export function i32Val(x: i32): Val {
+  return valFromMajorMinor(<u32>x, 0, TAG_I32_VAL);
+}
+
+export function i32FromVal(v: Val): i32 {
+  return <i32>valMajor(v);
+}
+
+// Only valid for values that fit into 56 bits, larger ones need to be
+// converted to objects by the host.
+export function u64SmallVal(x: u64): Val {
+  return (x << TAG_BITS) | TAG_U64_SMALL;
+}
+
+export function u64FromSmallVal(v: Val): u64 {
+  return v >> TAG_BITS;
+}
+
+// Only valid for values that fit into 56 bits, larger ones need to be
+// converted to objects by the host.
+export function i64SmallVal(x: i64): Val {
+  return (<u64>x << TAG_BITS) | TAG_I64_SMALL;
+}
+
+export function i64FromSmallVal(v: Val): i64 {
+  return (<i64>v) >> <i64>TAG_BITS;
+}
+
+export function errorVal(type: u32, code: u32): ErrorVal {
+  return valFromMajorMinor(code, type, TAG_ERROR);
+}
+
+export function objectHandle(v: Val): u32 {
+  return valMajor(v);
+}
+
+// Encodes a symbol of up to 9 characters from [_0-9A-Za-z] as a small symbol.
+// Returns a value with the TAG_BAD tag for any other string.
+export function symbolSmall(s: string): SymbolVal {
+  if (s.length > 9) {
+    return TAG_BAD;
+  }
+  let body: u64 = 0;
+  for (let i = 0; i < s.length; ++i) {
+    const ch = s.charCodeAt(i);
+    let code: u64;
+    if (ch == 0x5f) {
+      code = 1;
+    } else if (ch >= 0x30 && ch <= 0x39) {
+      code = 2 + <u64>(ch - 0x30);
+    } else if (ch >= 0x41 && ch <= 0x5a) {
+      code = 12 + <u64>(ch - 0x41);
+    } else if (ch >= 0x61 && ch <= 0x7a) {
+      code = 38 + <u64>(ch - 0x61);
+    } else {
+      return TAG_BAD;
+    }
+    body = (body << 6) | code;
+  }
+  return (body << TAG_BITS) | TAG_SYMBOL_SMALL;
+}
+
+// Module 'context' (imported as "x").
+
+// Emit a diagnostic event containing a message and sequence of `Val`s.
+@external("x", "_")
+export declare function log_from_linear_memory(msg_pos: U32Val, msg_len: U32Val, vals_pos: U32Val, vals_len: U32Val): Void;
+
+// Compare two objects, or at least one object to a non-object, structurally. Returns -1 if a<b, 1 if a>b, or 0 if a==b.
+@external("x", "0")
+export declare function obj_cmp(a: Val, b: Val): i64;
+
+// Records a contract event. `topics` is expected to be a `SCVec` with length <= 4 that cannot contain `Vec`, `Map`, or `Bytes` with length > 32.
+@external("x", "1")
+export declare function contract_event(topics: VecObject, data: Val): Void;
+
+// Return the protocol version of the current ledger as a u32.
+@external("x", "2")
+export declare function get_ledger_version(): U32Val;
+
+// Return the sequence number of the current ledger as a u32.
+@external("x", "3")
+export declare function get_ledger_sequence(): U32Val;
+
+// Return the timestamp number of the current ledger as a u64.
+@external("x", "4")
+export declare function get_ledger_timestamp(): U64Val;
+
+// Causes the currently executing contract to fail immediately with a provided error code, which must be of error-type `ScErrorType::Contract`. Does not actually return.
+@external("x", "5")
+export declare function fail_with_error(error: ErrorVal): Void;
+
+// Return the network id (sha256 hash of network passphrase) of the current ledger as `Bytes`. The value is always 32 bytes in length.
+@external("x", "6")
+export declare function get_ledger_network_id(): BytesObject;
+
+// Get the Address object for the current contract.
+@external("x", "7")
+export declare function get_current_contract_address(): AddressObject;
+
+// Returns the max ledger sequence that an entry can live to (inclusive).
+@external("x", "8")
+export declare function get_max_live_until_ledger(): U32Val;
+
+// Module 'int' (imported as "i").
+
+// Convert a `u64` to an object containing a `u64`.
+@external("i", "_")
+export declare function obj_from_u64(v: u64): U64Object;
+
+// Convert an object containing a `u64` to a `u64`.
+@external("i", "0")
+export declare function obj_to_u64(obj: U64Object): u64;
+
+// Convert an `i64` to an object containing an `i64`.
+@external("i", "1")
+export declare function obj_from_i64(v: i64): I64Object;
+
+// Convert an object containing an `i64` to an `i64`.
+@external("i", "2")
+export declare function obj_to_i64(obj: I64Object): i64;
+
+// Convert the high and low 64-bit words of a u128 to an object containing a u128.
+@external("i", "3")
+export declare function obj_from_u128_pieces(hi: u64, lo: u64): U128Object;
+
+// Extract the low 64 bits from an object containing a u128.
+@external("i", "4")
+export declare function obj_to_u128_lo64(obj: U128Object): u64;
+
+// Extract the high 64 bits from an object containing a u128.
+@external("i", "5")
+export declare function obj_to_u128_hi64(obj: U128Object): u64;
+
+// Convert the high and low 64-bit words of an i128 to an object containing an i128.
+@external("i", "6")
+export declare function obj_from_i128_pieces(hi: i64, lo: u64): I128Object;
+
+// Extract the low 64 bits from an object containing an i128.
+@external("i", "7")
+export declare function obj_to_i128_lo64(obj: I128Object): u64;
+
+// Extract the high 64 bits from an object containing an i128.
+@external("i", "8")
+export declare function obj_to_i128_hi64(obj: I128Object): i64;
+
+// Convert the four 64-bit words of a u256 (big-endian) to an object containing a u256.
+@external("i", "9")
+export declare function obj_from_u256_pieces(hi_hi: u64, hi_lo: u64, lo_hi: u64, lo_lo: u64): U256Object;
+
+// Create a U256 `Val` from its representation as a byte array in big endian.
+@external("i", "a")
+export declare function u256_val_from_be_bytes(bytes: BytesObject): U256Val;
+
+// Return the memory representation of this U256 `Val` as a byte array in big endian byte order.
+@external("i", "b")
+export declare function u256_val_to_be_bytes(val: U256Val): BytesObject;
+
+// Extract the highest 64-bits (bits 192-255) from an object containing a u256.
+@external("i", "c")
+export declare function obj_to_u256_hi_hi(obj: U256Object): u64;
+
+// Extract bits 128-191 from an object containing a u256.
+@external("i", "d")
+export declare function obj_to_u256_hi_lo(obj: U256Object): u64;
+
+// Extract bits 64-127 from an object containing a u256.
+@external("i", "e")
+export declare function obj_to_u256_lo_hi(obj: U256Object): u64;
+
+// Extract the lowest 64-bits (bits 0-63) from an object containing a u256.
+@external("i", "f")
+export declare function obj_to_u256_lo_lo(obj: U256Object): u64;
+
+// Convert the four 64-bit words of an i256 (big-endian) to an object containing an i256.
+@external("i", "g")
+export declare function obj_from_i256_pieces(hi_hi: i64, hi_lo: u64, lo_hi: u64, lo_lo: u64): I256Object;
+
+// Create a I256 `Val` from its representation as a byte array in big endian.
+@external("i", "h")
+export declare function i256_val_from_be_bytes(bytes: BytesObject): I256Val;
+
+// Return the memory representation of this I256 `Val` as a byte array in big endian byte order.
+@external("i", "i")
+export declare function i256_val_to_be_bytes(val: I256Val): BytesObject;
+
+// Extract the highest 64-bits (bits 192-255) from an object containing an i256.
+@external("i", "j")
+export declare function obj_to_i256_hi_hi(obj: I256Object): i64;
+
+// Extract bits 128-191 from an object containing an i256.
+@external("i", "k")
+export declare function obj_to_i256_hi_lo(obj: I256Object): u64;
+
+// Extract bits 64-127 from an object containing an i256.
+@external("i", "l")
+export declare function obj_to_i256_lo_hi(obj: I256Object): u64;
+
+// Extract the lowest 64-bits (bits 0-63) from an object containing an i256.
+@external("i", "m")
+export declare function obj_to_i256_lo_lo(obj: I256Object): u64;
+
+// Performs checked integer addition. Computes `lhs + rhs`, returning `ScError` if overflow occurred.
+@external("i", "n")
+export declare function u256_add(lhs: U256Val, rhs: U256Val): U256Val;
+
+// Performs checked integer subtraction. Computes `lhs - rhs`, returning `ScError` if overflow occurred.
+@external("i", "o")
+export declare function u256_sub(lhs: U256Val, rhs: U256Val): U256Val;
+
+// Performs checked integer multiplication. Computes `lhs * rhs`, returning `ScError` if overflow occurred.
+@external("i", "p")
+export declare function u256_mul(lhs: U256Val, rhs: U256Val): U256Val;
+
+// Performs checked integer division. Computes `lhs / rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+@external("i", "q")
+export declare function u256_div(lhs: U256Val, rhs: U256Val): U256Val;
+
+// Performs checked Euclidean modulo. Computes `lhs % rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+@external("i", "r")
+export declare function u256_rem_euclid(lhs: U256Val, rhs: U256Val): U256Val;
+
+// Performs checked exponentiation. Computes `lhs.exp(rhs)`, returning `ScError` if overflow occurred.
+@external("i", "s")
+export declare function u256_pow(lhs: U256Val, rhs: U32Val): U256Val;
+
+// Performs checked shift left. Computes `lhs << rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+@external("i", "t")
+export declare function u256_shl(lhs: U256Val, rhs: U32Val): U256Val;
+
+// Performs checked shift right. Computes `lhs >> rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+@external("i", "u")
+export declare function u256_shr(lhs: U256Val, rhs: U32Val): U256Val;
+
+// Performs checked integer addition. Computes `lhs + rhs`, returning `ScError` if overflow occurred.
+@external("i", "v")
+export declare function i256_add(lhs: I256Val, rhs: I256Val): I256Val;
+
+// Performs checked integer subtraction. Computes `lhs - rhs`, returning `ScError` if overflow occurred.
+@external("i", "w")
+export declare function i256_sub(lhs: I256Val, rhs: I256Val): I256Val;
+
+// Performs checked integer multiplication. Computes `lhs * rhs`, returning `ScError` if overflow occurred.
+@external("i", "x")
+export declare function i256_mul(lhs: I256Val, rhs: I256Val): I256Val;
+
+// Performs checked integer division. Computes `lhs / rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+@external("i", "y")
+export declare function i256_div(lhs: I256Val, rhs: I256Val): I256Val;
+
+// Performs checked Euclidean modulo. Computes `lhs % rhs`, returning `ScError` if `rhs == 0` or overflow occurred.
+@external("i", "z")
+export declare function i256_rem_euclid(lhs: I256Val, rhs: I256Val): I256Val;
+
+// Performs checked exponentiation. Computes `lhs.exp(rhs)`, returning `ScError` if overflow occurred.
+@external("i", "A")
+export declare function i256_pow(lhs: I256Val, rhs: U32Val): I256Val;
+
+// Performs checked shift left. Computes `lhs << rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+@external("i", "B")
+export declare function i256_shl(lhs: I256Val, rhs: U32Val): I256Val;
+
+// Performs checked shift right. Computes `lhs >> rhs`, returning `ScError` if `rhs` is larger than or equal to the number of bits in `lhs`.
+@external("i", "C")
+export declare function i256_shr(lhs: I256Val, rhs: U32Val): I256Val;
+
+// Convert a `u64` to a `Timepoint` object.
+@external("i", "D")
+export declare function timepoint_obj_from_u64(v: u64): TimepointObject;
+
+// Convert a `Timepoint` object to a `u64`.
+@external("i", "E")
+export declare function timepoint_obj_to_u64(obj: TimepointObject): u64;
+
+// Convert a `u64` to a `Duration` object.
+@external("i", "F")
+export declare function duration_obj_from_u64(v: u64): DurationObject;
+
+// Convert a `Duration` object a `u64`.
+@external("i", "G")
+export declare function duration_obj_to_u64(obj: DurationObject): u64;
+
+// Module 'map' (imported as "m").
+
+// Create an empty new map.
+@external("m", "_")
+export declare function map_new(): MapObject;
+
+// Insert a key/value mapping into an existing map, and return the map object handle. If the map already has a mapping for the given key, the previous value is overwritten.
+@external("m", "0")
+export declare function map_put(m: MapObject, k: Val, v: Val): MapObject;
+
+// Get the value for a key from a map. Traps if key is not found.
+@external("m", "1")
+export declare function map_get(m: MapObject, k: Val): Val;
+
+// Remove a key/value mapping from a map if it exists, traps if doesn't.
+@external("m", "2")
+export declare function map_del(m: MapObject, k: Val): MapObject;
+
+// Get the size of a map.
+@external("m", "3")
+export declare function map_len(m: MapObject): U32Val;
+
+// Test for the presence of a key in a map. Returns Bool.
+@external("m", "4")
+export declare function map_has(m: MapObject, k: Val): Bool;
+
+// Get the key from a map at position `i`. If `i` is an invalid position, return ScError.
+@external("m", "5")
+export declare function map_key_by_pos(m: MapObject, i: U32Val): Val;
+
+// Get the value from a map at position `i`. If `i` is an invalid position, return ScError.
+@external("m", "6")
+export declare function map_val_by_pos(m: MapObject, i: U32Val): Val;
+
+// Return a new vector containing all the keys in a map. The new vector is ordered in the original map's key-sorted order.
+@external("m", "7")
+export declare function map_keys(m: MapObject): VecObject;
+
+// Return a new vector containing all the values in a map. The new vector is ordered in the original map's key-sorted order.
+@external("m", "8")
+export declare function map_values(m: MapObject): VecObject;
+
+// Return a new map initialized from a pair of equal-length arrays, one for keys and one for values, given by a pair of linear-memory addresses and a length in Vals.
+@external("m", "9")
+export declare function map_new_from_linear_memory(keys_pos: U32Val, vals_pos: U32Val, len: U32Val): MapObject;
+
+// Copy Vals from `map` to the array `vals_pos`, selecting only the keys identified by the array `keys_pos`. Both arrays have `len` elements and are identified by linear-memory addresses.
+@external("m", "a")
+export declare function map_unpack_to_linear_memory(map: MapObject, keys_pos: U32Val, vals_pos: U32Val, len: U32Val): Void;
+
+// Module 'vec' (imported as "v").
+
+// Creates an empty new vector.
+@external("v", "_")
+export declare function vec_new(): VecObject;
+
+// Update the value at index `i` in the vector. Return the new vector. Trap if the index is out of bounds.
+@external("v", "0")
+export declare function vec_put(v: VecObject, i: U32Val, x: Val): VecObject;
+
+// Returns the element at index `i` of the vector. Traps if the index is out of bound.
+@external("v", "1")
+export declare function vec_get(v: VecObject, i: U32Val): Val;
+
+// Delete an element in a vector at index `i`, shifting all elements after it to the left. Return the new vector. Traps if the index is out of bound.
+@external("v", "2")
+export declare function vec_del(v: VecObject, i: U32Val): VecObject;
+
+// Returns length of the vector.
+@external("v", "3")
+export declare function vec_len(v: VecObject): U32Val;
+
+// Push a value to the front of a vector.
+@external("v", "4")
+export declare function vec_push_front(v: VecObject, x: Val): VecObject;
+
+// Removes the first element from the vector and returns the new vector. Traps if original vector is empty.
+@external("v", "5")
+export declare function vec_pop_front(v: VecObject): VecObject;
+
+// Appends an element to the back of the vector.
+@external("v", "6")
+export declare function vec_push_back(v: VecObject, x: Val): VecObject;
+
+// Removes the last element from the vector and returns the new vector. Traps if original vector is empty.
+@external("v", "7")
+export declare function vec_pop_back(v: VecObject): VecObject;
+
+// Return the first element in the vector. Traps if the vector is empty
+@external("v", "8")
+export declare function vec_front(v: VecObject): Val;
+
+// Return the last element in the vector. Traps if the vector is empty
+@external("v", "9")
+export declare function vec_back(v: VecObject): Val;
+
+// Inserts an element at index `i` within the vector, shifting all elements after it to the right. Traps if the index is out of bound
+@external("v", "a")
+export declare function vec_insert(v: VecObject, i: U32Val, x: Val): VecObject;
+
+// Clone the vector `v1`, then moves all the elements of vector `v2` into it. Return the new vector. Traps if number of elements in the vector overflows a u32.
+@external("v", "b")
+export declare function vec_append(v1: VecObject, v2: VecObject): VecObject;
+
+// Copy the elements from `start` index until `end` index, exclusive, in the vector and create a new vector from it. Return the new vector. Traps if the index is out of bound.
+@external("v", "c")
+export declare function vec_slice(v: VecObject, start: U32Val, end: U32Val): VecObject;
+
+// Get the index of the first occurrence of a given element in the vector. Returns the u32 index of the value if it's there. Otherwise, it returns `Void`.
+@external("v", "d")
+export declare function vec_first_index_of(v: VecObject, x: Val): Val;
+
+// Get the index of the last occurrence of a given element in the vector. Returns the u32 index of the value if it's there. Otherwise, it returns `Void`.
+@external("v", "e")
+export declare function vec_last_index_of(v: VecObject, x: Val): Val;
+
+// Binary search a sorted vector for a given element. If it exists, the high 32 bits of the return value is 0x0000_0001 and the low 32 bits contain the u32 index of the element. If it does not exist, the high 32 bits of the return value is 0x0000_0000 and the low-32 bits contain the u32 index at which the element would need to be inserted into the vector to maintain sorted order.
+@external("v", "f")
+export declare function vec_binary_search(v: VecObject, x: Val): u64;
+
+// Return a new vec initialized from an input slice of Vals given by a linear-memory address and length in Vals.
+@external("v", "g")
+export declare function vec_new_from_linear_memory(vals_pos: U32Val, len: U32Val): VecObject;
+
+// Copy the Vals of a vec into an array at a given linear-memory address and length in Vals.
+@external("v", "h")
+export declare function vec_unpack_to_linear_memory(vec: VecObject, vals_pos: U32Val, len: U32Val): Void;
+
+// Module 'ledger' (imported as "l").
+
+@external("l", "_")
+export declare function put_contract_data(k: Val, v: Val, t: StorageType): Void;
+
+@external("l", "0")
+export declare function has_contract_data(k: Val, t: StorageType): Bool;
+
+@external("l", "1")
+export declare function get_contract_data(k: Val, t: StorageType): Val;
+
+@external("l", "2")
+export declare function del_contract_data(k: Val, t: StorageType): Void;
+
+// Creates the contract instance on behalf of `deployer`. `deployer` must authorize this call via Soroban auth framework, i.e. this calls `deployer.require_auth` with respective arguments. `wasm_hash` must be a hash of the contract code that has already been uploaded on this network. `salt` is used to create a unique contract id. Returns the address of the created contract.
+@external("l", "3")
+export declare function create_contract(deployer: AddressObject, wasm_hash: BytesObject, salt: BytesObject): AddressObject;
+
+// Creates the instance of Stellar Asset contract corresponding to the provided asset. `serialized_asset` is `stellar::Asset` XDR serialized to bytes format. Returns the address of the created contract.
+@external("l", "4")
+export declare function create_asset_contract(serialized_asset: BytesObject): AddressObject;
+
+// Uploads provided `wasm` bytecode to the network and returns its identifier (SHA-256 hash). No-op in case if the same Wasm object already exists.
+@external("l", "5")
+export declare function upload_wasm(wasm: BytesObject): BytesObject;
+
+// Replaces the executable of the current contract with the provided Wasm code identified by a hash. Wasm entry corresponding to the hash has to already be present in the ledger. The update happens only after the current contract invocation has successfully finished, so this can be safely called in the middle of a function.
+@external("l", "6")
+export declare function update_current_contract_wasm(hash: BytesObject): Void;
+
+// If the entry's TTL is below `threshold` ledgers, extend `live_until_ledger_seq` such that TTL == `extend_to`, where TTL is defined as live_until_ledger_seq - current ledger
+@external("l", "7")
+export declare function extend_contract_data_ttl(k: Val, t: StorageType, threshold: U32Val, extend_to: U32Val): Void;
+
+// If the TTL for the current contract instance and code (if applicable) is below `threshold` ledgers, extend `live_until_ledger_seq` such that TTL == `extend_to`, where TTL is defined as live_until_ledger_seq - current ledger
+@external("l", "8")
+export declare function extend_current_contract_instance_and_code_ttl(threshold: U32Val, extend_to: U32Val): Void;
+
+// If the TTL for the provided contract instance and code (if applicable) is below `threshold` ledgers, extend `live_until_ledger_seq` such that TTL == `extend_to`, where TTL is defined as live_until_ledger_seq - current ledger
+@external("l", "9")
+export declare function extend_contract_instance_and_code_ttl(contract: AddressObject, threshold: U32Val, extend_to: U32Val): Void;
+
+// Get the id of a contract without creating it. `deployer` is address of the contract deployer. `salt` is used to create a unique contract id. Returns the address of the would-be contract.
+@external("l", "a")
+export declare function get_contract_id(deployer: AddressObject, salt: BytesObject): AddressObject;
+
+// Get the id of the Stellar Asset contract corresponding to the provided asset without creating the instance. `serialized_asset` is `stellar::Asset` XDR serialized to bytes format. Returns the address of the would-be asset contract.
+@external("l", "b")
+export declare function get_asset_contract_id(serialized_asset: BytesObject): AddressObject;
+
+// Module 'call' (imported as "d").
+
+// Calls a function in another contract with arguments contained in vector `args`. If the call is successful, returns the result of the called function. Traps otherwise.
+@external("d", "_")
+export declare function call(contract: AddressObject, func: SymbolVal, args: VecObject): Val;
+
+// Calls a function in another contract with arguments contained in vector `args`, returning either the result of the called function or an `Error` if the called function failed. The returned error is either a custom `ContractError` that the called contract returns explicitly, or an error with type `Context` and code `InvalidAction` in case of any other error in the called contract (such as a host function failure that caused a trap). `try_call` might trap in a few scenarios where the error can't be meaningfully recovered from, such as running out of budget.
+@external("d", "0")
+export declare function try_call(contract: AddressObject, func: SymbolVal, args: VecObject): Val;
+
+// Module 'buf' (imported as "b").
+
+// Serializes an (SC)Val into XDR opaque `Bytes` object.
+@external("b", "_")
+export declare function serialize_to_bytes(v: Val): BytesObject;
+
+// Deserialize a `Bytes` object to get back the (SC)Val.
+@external("b", "0")
+export declare function deserialize_from_bytes(b: BytesObject): Val;
+
+// Copies a slice of bytes from a `Bytes` object specified at offset `b_pos` with length `len` into the linear memory at position `lm_pos`. Traps if either the `Bytes` object or the linear memory doesn't have enough bytes.
+@external("b", "1")
+export declare function bytes_copy_to_linear_memory(b: BytesObject, b_pos: U32Val, lm_pos: U32Val, len: U32Val): Void;
+
+// Copies a segment of the linear memory specified at position `lm_pos` with length `len`, into a `Bytes` object at offset `b_pos`. The `Bytes` object may grow in size to accommodate the new bytes. Traps if the linear memory doesn't have enough bytes.
+@external("b", "2")
+export declare function bytes_copy_from_linear_memory(b: BytesObject, b_pos: U32Val, lm_pos: U32Val, len: U32Val): BytesObject;
+
+// Constructs a new `Bytes` object initialized with bytes copied from a linear memory slice specified at position `lm_pos` with length `len`.
+@external("b", "3")
+export declare function bytes_new_from_linear_memory(lm_pos: U32Val, len: U32Val): BytesObject;
+
+// Create an empty new `Bytes` object.
+@external("b", "4")
+export declare function bytes_new(): BytesObject;
+
+// Update the value at index `i` in the `Bytes` object. Return the new `Bytes`. Trap if the index is out of bounds.
+@external("b", "5")
+export declare function bytes_put(b: BytesObject, i: U32Val, u: U32Val): BytesObject;
+
+// Returns the element at index `i` of the `Bytes` object. Traps if the index is out of bound.
+@external("b", "6")
+export declare function bytes_get(b: BytesObject, i: U32Val): U32Val;
+
+// Delete an element in a `Bytes` object at index `i`, shifting all elements after it to the left. Return the new `Bytes`. Traps if the index is out of bound.
+@external("b", "7")
+export declare function bytes_del(b: BytesObject, i: U32Val): BytesObject;
+
+// Returns length of the `Bytes` object.
+@external("b", "8")
+export declare function bytes_len(b: BytesObject): U32Val;
+
+// Appends an element to the back of the `Bytes` object.
+@external("b", "9")
+export declare function bytes_push(b: BytesObject, u: U32Val): BytesObject;
+
+// Removes the last element from the `Bytes` object and returns the new `Bytes`. Traps if original `Bytes` is empty.
+@external("b", "a")
+export declare function bytes_pop(b: BytesObject): BytesObject;
+
+// Return the first element in the `Bytes` object. Traps if the `Bytes` is empty
+@external("b", "b")
+export declare function bytes_front(b: BytesObject): U32Val;
+
+// Return the last element in the `Bytes` object. Traps if the `Bytes` is empty
+@external("b", "c")
+export declare function bytes_back(b: BytesObject): U32Val;
+
+// Inserts an element at index `i` within the `Bytes` object, shifting all elements after it to the right. Traps if the index is out of bound
+@external("b", "d")
+export declare function bytes_insert(b: BytesObject, i: U32Val, u: U32Val): BytesObject;
+
+// Clone the `Bytes` object `b1`, then moves all the elements of `Bytes` object `b2` into it. Return the new `Bytes`. Traps if its length overflows a u32.
+@external("b", "e")
+export declare function bytes_append(b1: BytesObject, b2: BytesObject): BytesObject;
+
+// Copies the elements from `start` index until `end` index, exclusive, in the `Bytes` object and creates a new `Bytes` from it. Returns the new `Bytes`. Traps if the index is out of bound.
+@external("b", "f")
+export declare function bytes_slice(b: BytesObject, start: U32Val, end: U32Val): BytesObject;
+
+// Copies a slice of bytes from a `String` object specified at offset `s_pos` with length `len` into the linear memory at position `lm_pos`. Traps if either the `String` object or the linear memory doesn't have enough bytes.
+@external("b", "g")
+export declare function string_copy_to_linear_memory(s: StringObject, s_pos: U32Val, lm_pos: U32Val, len: U32Val): Void;
+
+// Copies a slice of bytes from a `Symbol` object specified at offset `s_pos` with length `len` into the linear memory at position `lm_pos`. Traps if either the `String` object or the linear memory doesn't have enough bytes.
+@external("b", "h")
+export declare function symbol_copy_to_linear_memory(s: SymbolObject, s_pos: U32Val, lm_pos: U32Val, len: U32Val): Void;
+
+// Constructs a new `String` object initialized with bytes copied from a linear memory slice specified at position `lm_pos` with length `len`.
+@external("b", "i")
+export declare function string_new_from_linear_memory(lm_pos: U32Val, len: U32Val): StringObject;
+
+// Constructs a new `Symbol` object initialized with bytes copied from a linear memory slice specified at position `lm_pos` with length `len`.
+@external("b", "j")
+export declare function symbol_new_from_linear_memory(lm_pos: U32Val, len: U32Val): SymbolObject;
+
+// Returns length of the `String` object.
+@external("b", "k")
+export declare function string_len(s: StringObject): U32Val;
+
+// Returns length of the `Symbol` object.
+@external("b", "l")
+export declare function symbol_len(s: SymbolObject): U32Val;
+
+// Return the index of a Symbol in an array of linear-memory byte-slices, or trap if not found.
+@external("b", "m")
+export declare function symbol_index_in_linear_memory(sym: SymbolVal, slices_pos: U32Val, len: U32Val): U32Val;
+
+// Module 'crypto' (imported as "c").
+
+@external("c", "_")
+export declare function compute_hash_sha256(x: BytesObject): BytesObject;
+
+@external("c", "0")
+export declare function verify_sig_ed25519(k: BytesObject, x: BytesObject, s: BytesObject): Void;
+
+// Returns the keccak256 hash of given input bytes.
+@external("c", "1")
+export declare function compute_hash_keccak256(x: BytesObject): BytesObject;
+
+// Recovers the SEC-1-encoded ECDSA secp256k1 public key that produced a given 64-byte signature over a given 32-byte message digest, for a given recovery_id byte.
+@external("c", "2")
+export declare function recover_key_ecdsa_secp256k1(msg_digest: BytesObject, signature: BytesObject, recovery_id: U32Val): BytesObject;
+
+// Module 'address' (imported as "a").
+
+// Checks if the address has authorized the invocation of the current contract function with the provided arguments. Traps if the invocation hasn't been authorized.
+@external("a", "_")
+export declare function require_auth_for_args(address: AddressObject, args: VecObject): Void;
+
+// Checks if the address has authorized the invocation of the current contract function with all the arguments of the invocation. Traps if the invocation hasn't been authorized.
+@external("a", "0")
+export declare function require_auth(address: AddressObject): Void;
+
+// Converts a provided Stellar strkey address of an account or a contract ('G...' or 'C...' respectively) to an address object. `strkey` can be either `BytesObject` or `StringObject` (the contents should represent the `G.../C...` string in both cases). Any other valid or invalid strkey (e.g. 'S...') will trigger an error. Prefer directly using the Address objects whenever possible. This is only useful in the context of custom messaging protocols (e.g. cross-chain).
+@external("a", "1")
+export declare function strkey_to_address(strkey: Val): AddressObject;
+
+// Converts a provided address to Stellar strkey format ('G...' for account or 'C...' for contract). Prefer directly using the Address objects whenever possible. This is only useful in the context of custom messaging protocols (e.g. cross-chain).
+@external("a", "2")
+export declare function address_to_strkey(address: AddressObject): StringObject;
+
+// Authorizes sub-contract calls for the next contract call on behalf of the current contract. Every entry in the argument vector corresponds to `InvokerContractAuthEntry` contract type that authorizes a tree of `require_auth` calls on behalf of the current contract. The entries must not contain any authorizations for the direct contract call, i.e. if current contract needs to call contract function F1 that calls function F2 both of which require auth, only F2 should be present in `auth_entries`.
+@external("a", "3")
+export declare function authorize_as_curr_contract(auth_entires: VecObject): Void;
+
+// Module 'test' (imported as "t").
+
+// A dummy function taking 0 arguments and performs no-op. This function is for test purpose only, for measuring the roundtrip cost of invoking a host function, i.e. host->Vm->host.
+@external("t", "_")
+export declare function dummy0(): Val;
+
+// Module 'prng' (imported as "p").
+
+// Reseed the frame-local PRNG with a given BytesObject, which should be 32 bytes long.
+@external("p", "_")
+export declare function prng_reseed(seed: BytesObject): Void;
+
+// Construct a new BytesObject of the given length filled with bytes drawn from the frame-local PRNG.
+@external("p", "0")
+export declare function prng_bytes_new(length: U32Val): BytesObject;
+
+// Return a u64 uniformly sampled from the inclusive range [lo,hi] by the frame-local PRNG.
+@external("p", "1")
+export declare function prng_u64_in_inclusive_range(lo: u64, hi: u64): u64;
+
+// Return a (Fisher-Yates) shuffled clone of a given vector, using the frame-local PRNG.
+@external("p", "2")
+export declare function prng_vec_shuffle(vec: VecObject): VecObject;
@@ -0,0 +1,588 @@
+//! This module generates import bindings of the host functions for contracts
+//! written in languages other than Rust: a C header and an AssemblyScript
+//! module. Both declare every host function with its Wasm module and function
+//! export names, and define the [Val] tags along with helpers for encoding
+//! and decoding the small [Val] forms.
+//!
+//! The bindings are produced from the same x-macro as the [Env](crate::Env)
+//! trait, so they always match the interface of this crate. Checked-in copies
+//! of the generated files live in the `bindings` directory of the crate and
+//! are kept in sync by its tests (run them with `UPDATE_BINDINGS=1` to
+//! regenerate the files).
+
+use crate::{
+    call_macro_with_all_host_functions, meta,
+    symbol::{CODE_BITS, MAX_SMALL_CHARS},
+    val::{MINOR_BITS, TAG_BITS},
+    Tag,
+};
+use std::fmt::Write;
+
+/// Description of a single host function, as exposed to the contracts.
+pub struct HostFunctionBinding {
+    /// Export name of the Wasm module the function is importable from.
+    pub module_export: &'static str,
+    /// Name of the module the function belongs to.
+    pub module_name: &'static str,
+    /// Export name of the Wasm function.
+    pub export: &'static str,
+    /// Name of the function.
+    pub name: &'static str,
+    /// Names and types of the arguments.
+    pub args: &'static [(&'static str, &'static str)],
+    /// Return type.
+    pub ret: &'static str,
+    /// Documentation of the function.
+    pub docs: &'static str,
+    /// First ledger protocol in which the function is available, if bounded.
+    pub min_proto: Option<u32>,
+    /// Last ledger protocol in which the function is available, if bounded.
+    pub max_proto: Option<u32>,
+}
+
+macro_rules! optional_proto {
+    () => {
+        None
+    };
+    ($proto:literal) => {
+        Some($proto)
+    };
+}
+
+// This is a callback macro that pattern-matches the token-tree passed by the
+// x-macro (call_macro_with_all_host_functions) and produces a static array
+// describing all the host functions.
+macro_rules! generate_host_function_bindings {
+    {
+        $(
+            $(#[$mod_attr:meta])*
+            mod $mod_id:ident $mod_str:literal
+            {
+                $(
+                    // The x-macro only passes doc attributes for functions,
+                    // which we match as such in order to get the docs text.
+                    $(#[doc = $doc:literal])*
+                    { $fn_str:literal, $($min_proto:literal)?, $($max_proto:literal)?, fn $fn_id:ident ($($arg:ident:$type:ty),*) -> $ret:ty }
+                )*
+            }
+        )*
+    }
+
+    =>  // The part of the macro above this line is a matcher; below is its expansion.
+
+    {
+        /// All the host functions, in the order of their modules and export
+        /// names.
+        pub static HOST_FUNCTIONS: &[HostFunctionBinding] = &[
+            $(
+                $(
+                    HostFunctionBinding {
+                        module_export: $mod_str,
+                        module_name: stringify!($mod_id),
+                        export: $fn_str,
+                        name: stringify!($fn_id),
+                        args: &[$((stringify!($arg), stringify!($type))),*],
+                        ret: stringify!($ret),
+                        docs: concat!($($doc),*),
+                        min_proto: optional_proto!($($min_proto)?),
+                        max_proto: optional_proto!($($max_proto)?),
+                    },
+                )*
+            )*
+        ];
+    };
+}
+
+call_macro_with_all_host_functions! { generate_host_function_bindings }
+
+const GENERATED_NOTICE: &str =
+    "Generated from env.json by soroban_env_common::bindings, do not edit.";
+
+// Returns all the valid tags and `Tag::Bad` (used by the generated helpers
+// for the invalid inputs), along with their names. All the invalid tag values
+// map to `Tag::Bad`, which is only returned for its own value.
+fn tags() -> impl Iterator<Item = (String, Tag)> {
+    (0..=u8::MAX)
+        .map(|i| (i, Tag::from_u8(i)))
+        .filter(|(i, tag)| *tag as u8 == *i)
+        .map(|(_, tag)| (format!("{tag:?}"), tag))
+}
+
+// Converts a camel case name (e.g. `U32Val`) to the screaming snake case
+// (e.g. `U32_VAL`).
+fn screaming_snake_case(name: &str) -> String {
+    let mut res = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if i > 0 && ch.is_ascii_uppercase() {
+            res.push('_');
+        }
+        res.push(ch.to_ascii_uppercase());
+    }
+    res
+}
+
+fn protocol_note(hf: &HostFunctionBinding) -> Option<String> {
+    match (hf.min_proto, hf.max_proto) {
+        (None, None) => None,
+        (Some(min), None) => Some(format!("Available since protocol {min}.")),
+        (None, Some(max)) => Some(format!("Available until protocol {max}.")),
+        (Some(min), Some(max)) => Some(format!("Available in protocols {min} to {max}.")),
+    }
+}
+
+fn c_type(ty: &str) -> String {
+    match ty {
+        "i64" => "int64_t".to_string(),
+        "u64" => "uint64_t".to_string(),
+        _ => format!("Soroban{ty}"),
+    }
+}
+
+fn c_value_types() -> Vec<&'static str> {
+    let mut types: Vec<&'static str> = HOST_FUNCTIONS
+        .iter()
+        .flat_map(|hf| hf.args.iter().map(|(_, ty)| *ty).chain([hf.ret]))
+        .filter(|ty| !matches!(*ty, "i64" | "u64" | "Val"))
+        .collect();
+    types.sort_unstable();
+    types.dedup();
+    types
+}
+
+/// Generates the C header declaring all the host function imports and the
+/// `Val` encoding helpers.
+pub fn c_header() -> String {
+    let mut out = String::new();
+    let w = &mut out;
+    let _ = writeln!(w, "// {GENERATED_NOTICE}");
+    let _ = writeln!(w);
+    let _ = writeln!(w, "#ifndef SOROBAN_ENV_H");
+    let _ = writeln!(w, "#define SOROBAN_ENV_H");
+    let _ = writeln!(w);
+    let _ = writeln!(w, "#include <stdint.h>");
+    let _ = writeln!(w);
+    let _ = writeln!(
+        w,
+        "#define SOROBAN_INTERFACE_VERSION {}ULL",
+        meta::INTERFACE_VERSION
+    );
+    let _ = writeln!(w);
+
+    let _ = writeln!(w, "// Val encoding.");
+    let _ = writeln!(w);
+    let _ = writeln!(w, "typedef uint64_t SorobanVal;");
+    for ty in c_value_types() {
+        let _ = writeln!(w, "typedef SorobanVal {};", c_type(ty));
+    }
+    let _ = writeln!(w);
+    let _ = writeln!(w, "#define SOROBAN_TAG_BITS {TAG_BITS}");
+    let _ = writeln!(w, "#define SOROBAN_MINOR_BITS {MINOR_BITS}");
+    for (name, tag) in tags() {
+        let _ = writeln!(
+            w,
+            "#define SOROBAN_TAG_{} {}",
+            screaming_snake_case(&name),
+            tag as u8
+        );
+    }
+    let _ = writeln!(w);
+    let _ = writeln!(w, "#define SOROBAN_FALSE ((SorobanBool)SOROBAN_TAG_FALSE)");
+    let _ = writeln!(w, "#define SOROBAN_TRUE ((SorobanBool)SOROBAN_TAG_TRUE)");
+    let _ = writeln!(w, "#define SOROBAN_VOID ((SorobanVoid)SOROBAN_TAG_VOID)");
+    let _ = write!(
+        w,
+        r#"
+static inline uint8_t soroban_val_tag(SorobanVal v) {{
+    return (uint8_t)v;
+}}
+
+static inline SorobanVal soroban_val_from_major_minor(uint32_t major, uint32_t minor, uint8_t tag) {{
+    return ((((uint64_t)major << SOROBAN_MINOR_BITS) | minor) << SOROBAN_TAG_BITS) | tag;
+}}
+
+static inline uint32_t soroban_val_major(SorobanVal v) {{
+    return (uint32_t)(v >> (SOROBAN_MINOR_BITS + SOROBAN_TAG_BITS));
+}}
+
+static inline uint32_t soroban_val_minor(SorobanVal v) {{
+    return (uint32_t)(v >> SOROBAN_TAG_BITS) & ((1u << SOROBAN_MINOR_BITS) - 1);
+}}
+
+static inline SorobanBool soroban_bool(int b) {{
+    return b ? SOROBAN_TRUE : SOROBAN_FALSE;
+}}
+
+static inline SorobanU32Val soroban_u32_val(uint32_t x) {{
+    return soroban_val_from_major_minor(x, 0, SOROBAN_TAG_U32_VAL);
+}}
+
+static inline uint32_t soroban_u32_from_val(SorobanU32Val v) {{
+    return soroban_val_major(v);
+}}
+
+static inline SorobanVal soroban_i32_val(int32_t x) {{
+    return soroban_val_from_major_minor((uint32_t)x, 0, SOROBAN_TAG_I32_VAL);
+}}
+
+static inline int32_t soroban_i32_from_val(SorobanVal v) {{
+    return (int32_t)soroban_val_major(v);
+}}
+
+// Only valid for values that fit into {body_bits} bits, larger ones need to be
+// converted to objects by the host.
+static inline SorobanVal soroban_u64_small_val(uint64_t x) {{
+    return (x << SOROBAN_TAG_BITS) | SOROBAN_TAG_U64_SMALL;
+}}
+
+static inline uint64_t soroban_u64_from_small_val(SorobanVal v) {{
+    return v >> SOROBAN_TAG_BITS;
+}}
+
+// Only valid for values that fit into {body_bits} bits, larger ones need to be
+// converted to objects by the host.
+static inline SorobanVal soroban_i64_small_val(int64_t x) {{
+    return ((uint64_t)x << SOROBAN_TAG_BITS) | SOROBAN_TAG_I64_SMALL;
+}}
+
+static inline int64_t soroban_i64_from_small_val(SorobanVal v) {{
+    return (int64_t)v >> SOROBAN_TAG_BITS;
+}}
+
+static inline SorobanError soroban_error(uint32_t type, uint32_t code) {{
+    return soroban_val_from_major_minor(code, type, SOROBAN_TAG_ERROR);
+}}
+
+static inline uint32_t soroban_object_handle(SorobanVal v) {{
+    return soroban_val_major(v);
+}}
+
+// Encodes a symbol of up to {max_small_chars} characters from [_0-9A-Za-z] as a small symbol.
+// Returns a value with the SOROBAN_TAG_BAD tag for any other string.
+static inline SorobanSymbol soroban_symbol_small(const char *s) {{
+    uint64_t body = 0;
+    int n = 0;
+    for (; s[n] != '\0'; ++n) {{
+        char ch = s[n];
+        uint64_t code;
+        if (n >= {max_small_chars}) {{
+            return SOROBAN_TAG_BAD;
+        }} else if (ch == '_') {{
+            code = 1;
+        }} else if (ch >= '0' && ch <= '9') {{
+            code = 2 + (uint64_t)(ch - '0');
+        }} else if (ch >= 'A' && ch <= 'Z') {{
+            code = 12 + (uint64_t)(ch - 'A');
+        }} else if (ch >= 'a' && ch <= 'z') {{
+            code = 38 + (uint64_t)(ch - 'a');
+        }} else {{
+            return SOROBAN_TAG_BAD;
+        }}
+        body = (body << {code_bits}) | code;
+    }}
+    return (body << SOROBAN_TAG_BITS) | SOROBAN_TAG_SYMBOL_SMALL;
+}}
+"#,
+        body_bits = 64 - TAG_BITS,
+        max_small_chars = MAX_SMALL_CHARS,
+        code_bits = CODE_BITS,
+    );
+
+    let mut module = "";
+    for hf in HOST_FUNCTIONS {
+        if hf.module_name != module {
+            module = hf.module_name;
+            let _ = writeln!(w);
+            let _ = writeln!(
+                w,
+                "// Module '{}' (imported as \"{}\").",
+                hf.module_name, hf.module_export
+            );
+        }
+        let _ = writeln!(w);
+        for line in hf.docs.lines() {
+            let _ = writeln!(w, "// {}", line.trim_end());
+        }
+        if let Some(note) = protocol_note(hf) {
+            let _ = writeln!(w, "// {note}");
+        }
+        let args = if hf.args.is_empty() {
+            "void".to_string()
+        } else {
+            hf.args
+                .iter()
+                .map(|(name, ty)| format!("{} {name}", c_type(ty)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let _ = writeln!(
+            w,
+            "__attribute__((import_module(\"{}\"), import_name(\"{}\")))",
+            hf.module_export, hf.export
+        );
+        let _ = writeln!(w, "{} {}({args});", c_type(hf.ret), hf.name);
+    }
+    let _ = writeln!(w);
+    let _ = writeln!(w, "#endif // SOROBAN_ENV_H");
+    out
+}
+
+// Renames the types that would shadow the AssemblyScript builtins.
+fn assemblyscript_type(ty: &str) -> &str {
+    match ty {
+        "Error" => "ErrorVal",
+        "Symbol" => "SymbolVal",
+        _ => ty,
+    }
+}
+
+/// Generates the AssemblyScript module declaring all the host function
+/// imports and the `Val` encoding helpers.
+pub fn assemblyscript_imports() -> String {
+    let mut out = String::new();
+    let w = &mut out;
+    let _ = writeln!(w, "// {GENERATED_NOTICE}");
+    let _ = writeln!(w);
+    let _ = writeln!(
+        w,
+        "export const INTERFACE_VERSION: u64 = {};",
+        meta::INTERFACE_VERSION
+    );
+    let _ = writeln!(w);
+    let _ = writeln!(w, "// Val encoding.");
+    let _ = writeln!(w);
+    let _ = writeln!(w, "export type Val = u64;");
+    for ty in c_value_types() {
+        let _ = writeln!(w, "export type {} = Val;", assemblyscript_type(ty));
+    }
+    let _ = writeln!(w);
+    let _ = writeln!(w, "export const TAG_BITS: u64 = {TAG_BITS};");
+    let _ = writeln!(w, "export const MINOR_BITS: u64 = {MINOR_BITS};");
+    for (name, tag) in tags() {
+        let _ = writeln!(
+            w,
+            "export const TAG_{}: u64 = {};",
+            screaming_snake_case(&name),
+            tag as u8
+        );
+    }
+    let _ = write!(
+        w,
+        r#"
+export const FALSE: Bool = TAG_FALSE;
+export const TRUE: Bool = TAG_TRUE;
+export const VOID: Void = TAG_VOID;
+
+export function valTag(v: Val): u8 {{
+  return <u8>v;
+}}
+
+export function valFromMajorMinor(major: u32, minor: u32, tag: u64): Val {{
+  return ((((<u64>major) << MINOR_BITS) | <u64>minor) << TAG_BITS) | tag;
+}}
+
+export function valMajor(v: Val): u32 {{
+  return <u32>(v >> (MINOR_BITS + TAG_BITS));
+}}
+
+export function valMinor(v: Val): u32 {{
+  return <u32>(v >> TAG_BITS) & ((1 << <u32>MINOR_BITS) - 1);
+}}
+
+export function boolVal(b: bool): Bool {{
+  return b ? TRUE : FALSE;
+}}
+
+export function u32Val(x: u32): U32Val {{
+  return valFromMajorMinor(x, 0, TAG_U32_VAL);
+}}
+
+export function u32FromVal(v: U32Val): u32 {{
+  return valMajor(v);
+}}
+
+export function i32Val(x: i32): Val {{
+  return valFromMajorMinor(<u32>x, 0, TAG_I32_VAL);
+}}
+
+export function i32FromVal(v: Val): i32 {{
+  return <i32>valMajor(v);
+}}
+
+// Only valid for values that fit into {body_bits} bits, larger ones need to be
+// converted to objects by the host.
+export function u64SmallVal(x: u64): Val {{
+  return (x << TAG_BITS) | TAG_U64_SMALL;
+}}
+
+export function u64FromSmallVal(v: Val): u64 {{
+  return v >> TAG_BITS;
+}}
+
+// Only valid for values that fit into {body_bits} bits, larger ones need to be
+// converted to objects by the host.
+export function i64SmallVal(x: i64): Val {{
+  return (<u64>x << TAG_BITS) | TAG_I64_SMALL;
+}}
+
+export function i64FromSmallVal(v: Val): i64 {{
+  return (<i64>v) >> <i64>TAG_BITS;
+}}
+
+export function errorVal(type: u32, code: u32): ErrorVal {{
+  return valFromMajorMinor(code, type, TAG_ERROR);
+}}
+
+export function objectHandle(v: Val): u32 {{
+  return valMajor(v);
+}}
+
+// Encodes a symbol of up to {max_small_chars} characters from [_0-9A-Za-z] as a small symbol.
+// Returns a value with the TAG_BAD tag for any other string.
+export function symbolSmall(s: string): SymbolVal {{
+  if (s.length > {max_small_chars}) {{
+    return TAG_BAD;
+  }}
+  let body: u64 = 0;
+  for (let i = 0; i < s.length; ++i) {{
+    const ch = s.charCodeAt(i);
+    let code: u64;
+    if (ch == 0x5f) {{
+      code = 1;
+    }} else if (ch >= 0x30 && ch <= 0x39) {{
+      code = 2 + <u64>(ch - 0x30);
+    }} else if (ch >= 0x41 && ch <= 0x5a) {{
+      code = 12 + <u64>(ch - 0x41);
+    }} else if (ch >= 0x61 && ch <= 0x7a) {{
+      code = 38 + <u64>(ch - 0x61);
+    }} else {{
+      return TAG_BAD;
+    }}
+    body = (body << {code_bits}) | code;
+  }}
+  return (body << TAG_BITS) | TAG_SYMBOL_SMALL;
+}}
+"#,
+        body_bits = 64 - TAG_BITS,
+        max_small_chars = MAX_SMALL_CHARS,
+        code_bits = CODE_BITS,
+    );
+
+    let mut module = "";
+    for hf in HOST_FUNCTIONS {
+        if hf.module_name != module {
+            module = hf.module_name;
+            let _ = writeln!(w);
+            let _ = writeln!(
+                w,
+                "// Module '{}' (imported as \"{}\").",
+                hf.module_name, hf.module_export
+            );
+        }
+        let _ = writeln!(w);
+        for line in hf.docs.lines() {
+            let _ = writeln!(w, "// {}", line.trim_end());
+        }
+        if let Some(note) = protocol_note(hf) {
+            let _ = writeln!(w, "// {note}");
+        }
+        let args = hf
+            .args
+            .iter()
+            .map(|(name, ty)| format!("{name}: {}", assemblyscript_type(ty)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(w, "@external(\"{}\", \"{}\")", hf.module_export, hf.export);
+        let _ = writeln!(
+            w,
+            "export declare function {}({args}): {};",
+            hf.name,
+            assemblyscript_type(hf.ret)
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{assemblyscript_imports, c_header, HOST_FUNCTIONS};
+
+    // Compares the generated bindings with the checked-in file, or updates
+    // the file when `UPDATE_BINDINGS` is set.
+    fn check_bindings_file(file_name: &str, generated: String) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("bindings")
+            .join(file_name);
+        if std::env::var("UPDATE_BINDINGS").is_ok() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, generated).unwrap();
+            return;
+        }
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            existing == generated,
+            "{} is out of date, re-run the tests with UPDATE_BINDINGS=1",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn test_c_header_is_up_to_date() {
+        check_bindings_file("soroban_env.h", c_header());
+    }
+
+    // Checks the syntax of the generated header with the C compiler of the
+    // host (`$CC` or `cc`), if there is one. Compilers that don't target Wasm
+    // ignore the import attributes, hence `-Wno-attributes`.
+    #[test]
+    fn test_c_header_compiles() {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+        let Ok(mut child) = Command::new(&cc)
+            .args(["-fsyntax-only", "-Wall", "-Werror", "-Wno-attributes"])
+            .args(["-x", "c", "-"])
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        else {
+            eprintln!("skipping the C header check: {cc} is not available");
+            return;
+        };
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(c_header().as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[test]
+    fn test_assemblyscript_imports_are_up_to_date() {
+        check_bindings_file("soroban_env.ts", assemblyscript_imports());
+    }
+
+    #[test]
+    fn test_every_host_function_is_declared() {
+        let header = c_header();
+        let imports = assemblyscript_imports();
+        for hf in HOST_FUNCTIONS {
+            let import = format!(
+                "import_module(\"{}\"), import_name(\"{}\")",
+                hf.module_export, hf.export
+            );
+            assert!(header.contains(&import));
+            assert!(imports.contains(&format!(
+                "@external(\"{}\", \"{}\")",
+                hf.module_export, hf.export
+            )));
+            assert!(imports.contains(&format!("export declare function {}(", hf.name)));
+        }
+    }
+}
@@ -286,6 +286,12 @@ impl_checkedenvarg_for_val_or_wrapper!(U32Val);
 //
 // All callback macros have essentially the same token-tree matcher part,
 // only their expansion parts differ.
+//
+// Functions in env.json may carry optional `min_supported_protocol` and
+// `max_supported_protocol` fields, bounding the ledger protocols in which they
+// can be imported by contracts (e.g. to introduce a new host function in a
+// protocol upgrade). These are passed to the callback macros as optional
+// literals after the export name.
 
 generate_call_macro_with_all_host_functions!("env.json");
 
@@ -332,7 +338,7 @@ macro_rules! generate_env_trait {
                     // pattern-repetition matcher so that it will match all such
                     // descriptions.
                     $(#[$fn_attr:meta])*
-                    { $fn_str:literal, fn $fn_id:ident $args:tt -> $ret:ty }
+                    { $fn_str:literal, $($min_proto:literal)?, $($max_proto:literal)?, fn $fn_id:ident $args:tt -> $ret:ty }
                 )*
             }
         )*
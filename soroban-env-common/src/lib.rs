@@ -58,6 +58,9 @@ pub const VERSION: Version = Version {
 
 mod wrapper_macros;
 
+#[cfg(feature = "std")]
+pub mod bindings;
+
 #[cfg(feature = "testutils")]
 mod arbitrary;
 mod bytes;
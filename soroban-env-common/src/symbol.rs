@@ -96,7 +96,7 @@ use super::val::BODY_BITS;
 // Small symbols admit 9 6-bit chars for 54 bits.
 
 pub(crate) const MAX_SMALL_CHARS: usize = 9;
-const CODE_BITS: usize = 6;
+pub(crate) const CODE_BITS: usize = 6;
 const CODE_MASK: u64 = (1u64 << CODE_BITS) - 1;
 const SMALL_MASK: u64 = (1u64 << (MAX_SMALL_CHARS * CODE_BITS)) - 1;
 sa::const_assert!(CODE_MASK == 0x3f);
@@ -31,7 +31,7 @@ sa::const_assert!(BODY_BITS == 56);
 
 #[allow(dead_code)]
 const MAJOR_BITS: usize = 32;
-const MINOR_BITS: usize = 24;
+pub(crate) const MINOR_BITS: usize = 24;
 #[allow(dead_code)]
 const MAJOR_MASK: u64 = (1u64 << MAJOR_BITS) - 1;
 const MINOR_MASK: u64 = (1u64 << MINOR_BITS) - 1;
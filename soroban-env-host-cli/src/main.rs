@@ -0,0 +1,500 @@
+// This is a command-line driver that embeds and runs the host: it invokes a
+// single host function against a ledger snapshot file (see the
+// `ledger_snapshot` module) and prints everything the invocation produced, so
+// that a failing transaction can be reproduced without writing a harness.
+//
+// It also provides a target for the `cackle` API-checker to observe the
+// linking of soroban-env-host as a dependency, and thus check API uses inside
+// soroban-env-host (this is probably a limitation of the `cackle` tool but at
+// the moment I haven't figured out a workaround). It lives in its own package
+// so that only the driver, and not the host, is allowed the `fs` and `env`
+// APIs it needs for its arguments and files.
+
+use std::{
+    fmt, fs,
+    io::{BufReader, BufWriter},
+    rc::Rc,
+};
+
+use sha2::{Digest, Sha256};
+use soroban_env_host::{
+    budget::Budget,
+    e2e_invoke::{invoke_host_function, LedgerEntryChange},
+    events::HostEvent,
+    ledger_snapshot::LedgerSnapshot,
+    storage::SnapshotSource,
+    xdr::{
+        AccountId, DiagnosticEvent, Hash, HostFunction, LedgerKey, Limits, PublicKey, ReadXdr,
+        ScVal, SorobanAuthorizationEntry, SorobanResources, TtlEntry, Uint256, WriteXdr,
+    },
+    HostError, LedgerInfo, DEFAULT_XDR_RW_LIMITS,
+};
+
+const USAGE: &str = "\
+Usage: soroban-env-host-cli --host-fn <FILE> [OPTIONS]
+
+Invokes a host function against a ledger snapshot and prints the result, the
+ledger changes, the contract and diagnostic events and the budget breakdown.
+
+The invocation runs in the enforcing mode when --resources is given (and the
+footprint and authorization entries are enforced), and in the recording mode
+otherwise (which requires the `recording_auth` feature).
+
+XDR inputs may be either raw or base64-encoded. The host function may also be
+given as JSON (with the `serde` feature) in a file ending in `.json`. Likewise,
+the snapshots are in the JSON format in the files ending in `.json`, and in the
+XDR format otherwise (see the `soroban_env_host::ledger_snapshot` docs).
+
+The diagnostics are always enabled, so the printed events include the contract
+events, in the order they were emitted. With the `serde` feature, --events-json
+writes the same events as a JSON array, in the format described in the
+`soroban_env_host::events::JsonScVal` documentation.
+
+Options:
+  --host-fn <FILE>             HostFunction to invoke
+  --snapshot <FILE>            Ledger snapshot to read the entries from
+  --write-snapshot <FILE>      Write the snapshot with the ledger changes applied
+  --events-json <FILE>         Write the events as JSON (requires `serde`)
+  --resources <FILE>           SorobanResources, to run in the enforcing mode
+  --auth <FILE>                Signed SorobanAuthorizationEntry (repeatable); in the
+                               recording mode only the other addresses are recorded
+  --source <ACCOUNT>           Source account strkey (G...)
+  --seed <HEX>                 32-byte base PRNG seed [default: all zeros]
+  --protocol-version <N>       [default: the host's protocol version]
+  --sequence <N>               Ledger sequence number [default: 1]
+  --timestamp <N>              Ledger close time [default: 0]
+  --network-passphrase <S>     [default: the testnet passphrase]
+  --base-reserve <N>           [default: 5000000]
+  --min-temp-entry-ttl <N>     [default: 16]
+  --min-persistent-entry-ttl <N>  [default: 4096]
+  --max-entry-ttl <N>          [default: 6312000]
+  --help                       Print this message
+";
+
+const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+// Errors are reported through the `Debug` implementation when returned from
+// `main`, so it prints the plain message.
+struct CliError(String);
+
+impl fmt::Debug for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<HostError> for CliError {
+    fn from(e: HostError) -> Self {
+        CliError(format!("{:?}", e))
+    }
+}
+
+fn cli_error(msg: impl fmt::Display) -> CliError {
+    CliError(msg.to_string())
+}
+
+struct Args {
+    host_fn: String,
+    snapshot: Option<String>,
+    write_snapshot: Option<String>,
+    #[cfg(feature = "serde")]
+    events_json: Option<String>,
+    resources: Option<String>,
+    auth: Vec<String>,
+    source: Option<String>,
+    seed: [u8; 32],
+    ledger_info: LedgerInfo,
+    network_passphrase: String,
+}
+
+fn parse_hex_seed(s: &str) -> Result<[u8; 32], CliError> {
+    let invalid = || cli_error(format!("invalid seed (expected 64 hex digits): {}", s));
+    if s.len() != 64 {
+        return Err(invalid());
+    }
+    let mut seed = [0u8; 32];
+    for (i, b) in seed.iter_mut().enumerate() {
+        *b = u8::from_str_radix(s.get(2 * i..2 * i + 2).ok_or_else(invalid)?, 16)
+            .map_err(|_| invalid())?;
+    }
+    Ok(seed)
+}
+
+fn parse_args() -> Result<Option<Args>, CliError> {
+    let mut host_fn = None;
+    let mut args = Args {
+        host_fn: String::new(),
+        snapshot: None,
+        write_snapshot: None,
+        #[cfg(feature = "serde")]
+        events_json: None,
+        resources: None,
+        auth: vec![],
+        source: None,
+        seed: [0; 32],
+        ledger_info: LedgerInfo {
+            protocol_version: soroban_env_host::meta::get_ledger_protocol_version(
+                soroban_env_host::meta::INTERFACE_VERSION,
+            ),
+            sequence_number: 1,
+            timestamp: 0,
+            network_id: [0; 32],
+            base_reserve: 5_000_000,
+            min_temp_entry_ttl: 16,
+            min_persistent_entry_ttl: 4096,
+            max_entry_ttl: 6_312_000,
+        },
+        network_passphrase: TESTNET_PASSPHRASE.to_string(),
+    };
+    let mut it = std::env::args().skip(1);
+    while let Some(flag) = it.next() {
+        if flag == "--help" || flag == "-h" {
+            return Ok(None);
+        }
+        let value = it
+            .next()
+            .ok_or_else(|| cli_error(format!("missing value for {}", flag)))?;
+        let number = |value: &str| -> Result<u64, CliError> {
+            value
+                .parse()
+                .map_err(|_| cli_error(format!("invalid value for {}: {}", flag, value)))
+        };
+        let number_u32 = |value: &str| -> Result<u32, CliError> {
+            u32::try_from(number(value)?)
+                .map_err(|_| cli_error(format!("value for {} is too large: {}", flag, value)))
+        };
+        let info = &mut args.ledger_info;
+        match flag.as_str() {
+            "--host-fn" => host_fn = Some(value),
+            "--snapshot" => args.snapshot = Some(value),
+            "--write-snapshot" => args.write_snapshot = Some(value),
+            #[cfg(feature = "serde")]
+            "--events-json" => args.events_json = Some(value),
+            "--resources" => args.resources = Some(value),
+            "--auth" => args.auth.push(value),
+            "--source" => args.source = Some(value),
+            "--seed" => args.seed = parse_hex_seed(&value)?,
+            "--protocol-version" => info.protocol_version = number_u32(&value)?,
+            "--sequence" => info.sequence_number = number_u32(&value)?,
+            "--timestamp" => info.timestamp = number(&value)?,
+            "--network-passphrase" => args.network_passphrase = value,
+            "--base-reserve" => info.base_reserve = number_u32(&value)?,
+            "--min-temp-entry-ttl" => info.min_temp_entry_ttl = number_u32(&value)?,
+            "--min-persistent-entry-ttl" => info.min_persistent_entry_ttl = number_u32(&value)?,
+            "--max-entry-ttl" => info.max_entry_ttl = number_u32(&value)?,
+            _ => return Err(cli_error(format!("unknown option: {}\n\n{}", flag, USAGE))),
+        }
+    }
+    args.host_fn = host_fn.ok_or_else(|| cli_error(format!("missing --host-fn\n\n{}", USAGE)))?;
+    args.ledger_info.network_id = Sha256::digest(args.network_passphrase.as_bytes()).into();
+    Ok(Some(args))
+}
+
+// Reads an XDR value from a file holding either its raw or its base64
+// encoding.
+fn read_xdr_file<T: ReadXdr>(path: &str) -> Result<T, CliError> {
+    let bytes = fs::read(path).map_err(|e| cli_error(format!("reading {}: {}", path, e)))?;
+    T::from_xdr(&bytes, DEFAULT_XDR_RW_LIMITS)
+        .or_else(|_| {
+            let text = String::from_utf8_lossy(&bytes);
+            T::from_xdr_base64(text.trim(), DEFAULT_XDR_RW_LIMITS)
+        })
+        .map_err(|e| cli_error(format!("decoding {}: {}", path, e)))
+}
+
+// Without the `serde` feature, the `.json` files are rejected rather than
+// parsed as XDR.
+fn is_json_path(path: &str) -> Result<bool, CliError> {
+    let is_json = path.ends_with(".json");
+    if is_json && !cfg!(feature = "serde") {
+        return Err(cli_error(format!(
+            "{}: JSON files require the `serde` feature",
+            path
+        )));
+    }
+    Ok(is_json)
+}
+
+fn read_host_fn(path: &str) -> Result<HostFunction, CliError> {
+    if is_json_path(path)? {
+        #[cfg(feature = "serde")]
+        {
+            let bytes =
+                fs::read(path).map_err(|e| cli_error(format!("reading {}: {}", path, e)))?;
+            return serde_json::from_slice(&bytes)
+                .map_err(|e| cli_error(format!("decoding {}: {}", path, e)));
+        }
+    }
+    read_xdr_file(path)
+}
+
+fn read_snapshot(path: &str) -> Result<LedgerSnapshot, CliError> {
+    let file = fs::File::open(path).map_err(|e| cli_error(format!("reading {}: {}", path, e)))?;
+    let r = BufReader::new(file);
+    if is_json_path(path)? {
+        #[cfg(feature = "serde")]
+        return Ok(LedgerSnapshot::read_json(r)?);
+    }
+    Ok(LedgerSnapshot::read_xdr(r)?)
+}
+
+fn write_snapshot(snapshot: &LedgerSnapshot, path: &str) -> Result<(), CliError> {
+    let file = fs::File::create(path).map_err(|e| cli_error(format!("writing {}: {}", path, e)))?;
+    let w = BufWriter::new(file);
+    if is_json_path(path)? {
+        #[cfg(feature = "serde")]
+        return Ok(snapshot.write_json(w)?);
+    }
+    Ok(snapshot.write_xdr(w)?)
+}
+
+fn parse_source_account(source: Option<&str>) -> Result<AccountId, CliError> {
+    let key = match source {
+        Some(s) => {
+            stellar_strkey::ed25519::PublicKey::from_string(s)
+                .map_err(|_| cli_error(format!("invalid source account: {}", s)))?
+                .0
+        }
+        None => [0; 32],
+    };
+    Ok(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key))))
+}
+
+fn to_xdr<T: WriteXdr>(v: &T) -> Result<Vec<u8>, CliError> {
+    v.to_xdr(DEFAULT_XDR_RW_LIMITS)
+        .map_err(|e| cli_error(format!("encoding XDR: {}", e)))
+}
+
+// Everything an invocation produced, in either mode.
+struct Outcome {
+    invoke_result: Result<ScVal, HostError>,
+    ledger_changes: Vec<LedgerEntryChange>,
+    recorded: Option<(SorobanResources, Vec<SorobanAuthorizationEntry>)>,
+}
+
+fn run_enforcing(
+    args: &Args,
+    snapshot: &LedgerSnapshot,
+    budget: &Budget,
+    host_fn: &HostFunction,
+    source_account: &AccountId,
+    resources_path: &str,
+    diagnostic_events: &mut Vec<DiagnosticEvent>,
+) -> Result<Outcome, CliError> {
+    let resources: SorobanResources = read_xdr_file(resources_path)?;
+    let auth_entries = args
+        .auth
+        .iter()
+        .map(|path| to_xdr(&read_xdr_file::<SorobanAuthorizationEntry>(path)?))
+        .collect::<Result<Vec<_>, CliError>>()?;
+
+    // Pass the snapshot entries in the footprint, along with their TTLs.
+    let mut ledger_entries = vec![];
+    let mut ttl_entries = vec![];
+    let footprint = &resources.footprint;
+    for key in footprint
+        .read_only
+        .iter()
+        .chain(footprint.read_write.iter())
+    {
+        let key = Rc::new(key.clone());
+        if !snapshot.has(&key)? {
+            continue;
+        }
+        let (entry, live_until_ledger) = snapshot.get(&key)?;
+        ledger_entries.push(to_xdr(entry.as_ref())?);
+        ttl_entries.push(match live_until_ledger {
+            Some(live_until_ledger_seq) => to_xdr(&TtlEntry {
+                key_hash: Hash(Sha256::digest(to_xdr(key.as_ref())?).into()),
+                live_until_ledger_seq,
+            })?,
+            None => vec![],
+        });
+    }
+
+    let res = invoke_host_function(
+        budget,
+        true,
+        &to_xdr(host_fn)?,
+        &to_xdr(&resources)?,
+        &to_xdr(source_account)?,
+        auth_entries.iter(),
+        args.ledger_info.clone(),
+        ledger_entries.iter(),
+        ttl_entries.iter(),
+        &args.seed.to_vec(),
+        diagnostic_events,
+    )?;
+    let invoke_result = match res.encoded_invoke_result {
+        Ok(encoded) => Ok(ScVal::from_xdr(encoded, DEFAULT_XDR_RW_LIMITS)
+            .map_err(|e| cli_error(format!("decoding the result: {}", e)))?),
+        Err(e) => Err(e),
+    };
+    Ok(Outcome {
+        invoke_result,
+        ledger_changes: res.ledger_changes,
+        recorded: None,
+    })
+}
+
+#[cfg(feature = "recording_auth")]
+fn run_recording(
+    args: &Args,
+    snapshot: &LedgerSnapshot,
+    budget: &Budget,
+    host_fn: HostFunction,
+    source_account: AccountId,
+    diagnostic_events: &mut Vec<DiagnosticEvent>,
+) -> Result<Outcome, CliError> {
+    let auth_entries = args
+        .auth
+        .iter()
+        .map(|path| read_xdr_file::<SorobanAuthorizationEntry>(path))
+        .collect::<Result<Vec<_>, CliError>>()?;
+    let res = soroban_env_host::e2e_invoke::simulate_invoke_host_function(
+        Rc::new(snapshot.clone()),
+        budget,
+        true,
+        host_fn,
+        source_account,
+        args.ledger_info.clone(),
+        args.seed,
+        true,
+        auth_entries,
+        diagnostic_events,
+    )?;
+    Ok(Outcome {
+        invoke_result: res.invoke_result,
+        ledger_changes: res.ledger_changes,
+        recorded: Some((res.resources, res.auth)),
+    })
+}
+
+#[cfg(not(feature = "recording_auth"))]
+fn run_recording(
+    _args: &Args,
+    _snapshot: &LedgerSnapshot,
+    _budget: &Budget,
+    _host_fn: HostFunction,
+    _source_account: AccountId,
+    _diagnostic_events: &mut Vec<DiagnosticEvent>,
+) -> Result<Outcome, CliError> {
+    Err(cli_error(
+        "the recording mode requires the `recording_auth` feature; pass --resources to run in the enforcing mode",
+    ))
+}
+
+fn print_ledger_change(change: &LedgerEntryChange) -> Result<(), CliError> {
+    let key = LedgerKey::from_xdr(&change.encoded_key, Limits::none())
+        .map_err(|e| cli_error(format!("decoding a ledger key: {}", e)))?;
+    let kind = match (change.read_only, &change.encoded_new_value) {
+        (true, _) => "read-only",
+        (false, Some(_)) => "written",
+        (false, None) => "removed",
+    };
+    print!("  [{}] {:?}", kind, key);
+    if let Some(ttl_change) = &change.ttl_change {
+        print!(
+            " (live until {} -> {})",
+            ttl_change.old_live_until_ledger, ttl_change.new_live_until_ledger
+        );
+    }
+    println!();
+    Ok(())
+}
+
+// Writes the events as a JSON array if --events-json was given.
+#[cfg(feature = "serde")]
+fn write_events_json(args: &Args, events: &[HostEvent]) -> Result<(), CliError> {
+    let Some(path) = &args.events_json else {
+        return Ok(());
+    };
+    let file = fs::File::create(path).map_err(|e| cli_error(format!("writing {}: {}", path, e)))?;
+    serde_json::to_writer_pretty(file, events)
+        .map_err(|e| cli_error(format!("writing {}: {}", path, e)))?;
+    println!("Wrote the events to {}", path);
+    Ok(())
+}
+
+fn main() -> Result<(), CliError> {
+    let Some(args) = parse_args()? else {
+        print!("{}", USAGE);
+        return Ok(());
+    };
+    let mut snapshot = match &args.snapshot {
+        Some(path) => read_snapshot(path)?,
+        None => LedgerSnapshot::new(),
+    };
+    let host_fn = read_host_fn(&args.host_fn)?;
+    let source_account = parse_source_account(args.source.as_deref())?;
+
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let outcome = match &args.resources {
+        Some(resources_path) => run_enforcing(
+            &args,
+            &snapshot,
+            &budget,
+            &host_fn,
+            &source_account,
+            resources_path,
+            &mut diagnostic_events,
+        ),
+        None => run_recording(
+            &args,
+            &snapshot,
+            &budget,
+            host_fn,
+            source_account,
+            &mut diagnostic_events,
+        ),
+    };
+    // The diagnostic events include the contract events as well. Diagnostics
+    // are most useful when something went wrong, so print them before
+    // reporting any error.
+    let events: Vec<HostEvent> = diagnostic_events
+        .into_iter()
+        .map(|event| HostEvent {
+            event: event.event,
+            failed_call: !event.in_successful_contract_call,
+        })
+        .collect();
+    println!("Events:");
+    for event in &events {
+        println!("  {}", event);
+    }
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            #[cfg(feature = "serde")]
+            write_events_json(&args, &events)?;
+            return Err(e);
+        }
+    };
+
+    match &outcome.invoke_result {
+        Ok(val) => println!("Result: {:?}", val),
+        Err(e) => println!("Result: error: {:?}", e),
+    }
+    if let Some((resources, auth)) = &outcome.recorded {
+        println!("Recorded resources: {:?}", resources);
+        println!("Recorded authorization entries:");
+        for entry in auth {
+            println!("  {:?}", entry);
+        }
+    }
+    println!("Ledger changes:");
+    for change in &outcome.ledger_changes {
+        print_ledger_change(change)?;
+    }
+    println!("Budget:\n{}", budget);
+    #[cfg(feature = "serde")]
+    write_events_json(&args, &events)?;
+
+    if let Some(path) = &args.write_snapshot {
+        snapshot.apply_ledger_changes(&outcome.ledger_changes)?;
+        write_snapshot(&snapshot, path)?;
+        println!("Wrote the post-invocation snapshot to {}", path);
+    }
+    Ok(())
+}
@@ -151,8 +151,8 @@ use std::rc::Rc;
 use soroban_env_common::xdr::{
     ContractDataEntry, CreateContractArgs, HashIdPreimage, HashIdPreimageSorobanAuthorization,
     InvokeContractArgs, LedgerEntry, LedgerEntryData, LedgerEntryExt, ScAddress, ScErrorCode,
-    ScErrorType, ScNonceKey, ScVal, SorobanAuthorizationEntry, SorobanAuthorizedFunction,
-    SorobanCredentials,
+    ScErrorType, ScMap, ScMapEntry, ScNonceKey, ScSymbol, ScVal, ScVec, SorobanAuthorizationEntry,
+    SorobanAuthorizedFunction, SorobanCredentials,
 };
 use soroban_env_common::{AddressObject, Compare, Symbol, TryFromVal, TryIntoVal, Val, VecObject};
 
@@ -162,9 +162,10 @@ use crate::builtin_contracts::account_contract::{
 };
 use crate::builtin_contracts::invoker_contract_auth::invoker_contract_auth_to_authorized_invocation;
 use crate::host::metered_clone::{MeteredAlloc, MeteredClone, MeteredContainer, MeteredIterator};
+use crate::host::metered_xdr::metered_write_xdr;
 use crate::host::Frame;
 use crate::host_object::HostVec;
-use crate::{Host, HostError};
+use crate::{DiagnosticLevel, Host, HostError};
 
 use super::xdr;
 use super::xdr::Hash;
@@ -274,6 +275,8 @@ pub struct AuthorizationManagerSnapshot {
     invoker_contract_tracker_root_snapshots: Vec<AuthorizedInvocationSnapshot>,
     #[cfg(any(test, feature = "recording_auth"))]
     tracker_by_address_handle: Option<BTreeMap<u32, usize>>,
+    #[cfg(any(test, feature = "recording_auth"))]
+    enforced_trackers_snapshot: Vec<Option<AccountAuthorizationTrackerSnapshot>>,
 }
 
 // Snapshot of the `account_trackers` in `AuthorizationManager`.
@@ -311,6 +314,12 @@ struct RecordingAuthInfo {
     // Whether to allow root authorized invocation to not match the root
     // contract invocation.
     disable_non_root_auth: bool,
+    // Trackers built from the signed authorization entries provided for the
+    // recording mode. Authorization of the addresses in `enforced_addresses`
+    // is enforced using these trackers (just like in the enforcing mode),
+    // while authorization of all the other addresses is recorded.
+    enforced_trackers: Vec<RefCell<AccountAuthorizationTracker>>,
+    enforced_addresses: Vec<AddressObject>,
 }
 
 #[cfg(feature = "testutils")]
@@ -320,6 +329,11 @@ impl std::hash::Hash for RecordingAuthInfo {
             tracker_by_address_handle.hash(state);
         }
         self.disable_non_root_auth.hash(state);
+        for tracker in self.enforced_trackers.iter() {
+            if let Ok(tracker) = tracker.try_borrow() {
+                tracker.hash(state);
+            }
+        }
     }
 }
 
@@ -343,6 +357,18 @@ impl RecordingAuthInfo {
             "recording_auth_info.tracker_by_address_handle.try_borrow_mut failed",
         )
     }
+
+    // Returns whether authorization of `address` has to be enforced using
+    // the provided authorization entries instead of being recorded.
+    // metering: covered by components
+    fn is_enforced_address(&self, host: &Host, address: AddressObject) -> Result<bool, HostError> {
+        for enforced_address in self.enforced_addresses.iter() {
+            if host.compare(enforced_address, &address)?.is_eq() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -629,6 +655,133 @@ impl AuthorizedFunction {
             }
         }
     }
+
+    // Builds the representation of the function that is used in the auth
+    // failure diagnostics.
+    // metering: covered by the host
+    fn to_diagnostic_scval(&self, host: &Host) -> Result<ScVal, HostError> {
+        match self {
+            AuthorizedFunction::ContractFn(contract_fn) => diagnostic_map(
+                host,
+                [
+                    (
+                        "args",
+                        ScVal::Vec(Some(ScVec(
+                            host.vals_to_scval_vec(contract_fn.args.as_slice())?,
+                        ))),
+                    ),
+                    (
+                        "contract",
+                        ScVal::Address(host.scaddress_from_address(contract_fn.contract_address)?),
+                    ),
+                    (
+                        "fn_name",
+                        ScVal::Symbol(host.scsymbol_from_symbol(contract_fn.function_name)?),
+                    ),
+                ],
+            ),
+            AuthorizedFunction::CreateContractHostFn(create_contract_args) => {
+                let mut buf = vec![];
+                metered_write_xdr(host.budget_ref(), create_contract_args, &mut buf)?;
+                diagnostic_map(host, [("create_contract", ScVal::Bytes(buf.try_into()?))])
+            }
+        }
+    }
+
+    // Finds the first difference between this (authorized) function and the
+    // function that requires authorization. Returns the mismatch reason and,
+    // for the argument mismatches, the index of the first mismatching
+    // argument.
+    // metering: covered by components
+    fn diagnose_mismatch(
+        &self,
+        host: &Host,
+        function: &AuthorizedFunction,
+    ) -> Result<(&'static str, Option<u32>), HostError> {
+        match (self, function) {
+            (AuthorizedFunction::ContractFn(f1), AuthorizedFunction::ContractFn(f2)) => {
+                if !host
+                    .compare(&f1.contract_address, &f2.contract_address)?
+                    .is_eq()
+                {
+                    return Ok(("contract", None));
+                }
+                if !host.compare(&f1.function_name, &f2.function_name)?.is_eq() {
+                    return Ok(("fn_name", None));
+                }
+                if f1.args.len() != f2.args.len() {
+                    return Ok(("args_len", None));
+                }
+                for (i, (a1, a2)) in f1.args.iter().zip(f2.args.iter()).enumerate() {
+                    if !host.compare(a1, a2)?.is_eq() {
+                        return Ok(("arg", Some(host.usize_to_u32(i)?)));
+                    }
+                }
+                Ok(("none", None))
+            }
+            (
+                AuthorizedFunction::CreateContractHostFn(c1),
+                AuthorizedFunction::CreateContractHostFn(c2),
+            ) => {
+                if host.compare(c1, c2)?.is_eq() {
+                    Ok(("none", None))
+                } else {
+                    Ok(("create_contract", None))
+                }
+            }
+            _ => Ok(("fn_type", None)),
+        }
+    }
+}
+
+// Builds a symbol-keyed `ScMap` for the auth failure diagnostics. The entries
+// must be provided in the key order.
+// metering: covered
+fn diagnostic_map<const N: usize>(
+    host: &Host,
+    entries: [(&str, ScVal); N],
+) -> Result<ScVal, HostError> {
+    Vec::<ScMapEntry>::charge_bulk_init_cpy(N as u64, host)?;
+    let mut map_entries = Vec::with_capacity(N);
+    for (key, val) in entries {
+        map_entries.push(ScMapEntry {
+            key: ScVal::Symbol(ScSymbol(key.try_into()?)),
+            val,
+        });
+    }
+    Ok(ScVal::Map(Some(ScMap(map_entries.try_into()?))))
+}
+
+// Describes a single node of the authorized invocation tree (identified by
+// the path of sub-invocation indices from the root) that didn't match the
+// invocation requiring authorization.
+// metering: covered
+fn diagnostic_mismatch(
+    host: &Host,
+    path: &[u32],
+    reason: &str,
+    arg_index: Option<u32>,
+) -> Result<ScVal, HostError> {
+    let node = diagnostic_node_path(host, path)?;
+    let reason = ScVal::Symbol(ScSymbol(reason.try_into()?));
+    match arg_index {
+        Some(arg_index) => diagnostic_map(
+            host,
+            [
+                ("arg_index", ScVal::U32(arg_index)),
+                ("node", node),
+                ("reason", reason),
+            ],
+        ),
+        None => diagnostic_map(host, [("node", node), ("reason", reason)]),
+    }
+}
+
+// metering: covered
+fn diagnostic_node_path(host: &Host, path: &[u32]) -> Result<ScVal, HostError> {
+    Vec::<ScVal>::charge_bulk_init_cpy(path.len() as u64, host)?;
+    let path: Vec<ScVal> = path.iter().map(|i| ScVal::U32(*i)).collect();
+    Ok(ScVal::Vec(Some(ScVec(path.try_into()?))))
 }
 
 impl AuthorizedInvocation {
@@ -689,6 +842,28 @@ impl AuthorizedInvocation {
         })
     }
 
+    // Builds the representation of the invocation tree that is used in the
+    // auth failure diagnostics.
+    // metering: covered
+    fn to_diagnostic_scval(&self, host: &Host) -> Result<ScVal, HostError> {
+        let sub_invocations = self
+            .sub_invocations
+            .iter()
+            .map(|i| i.to_diagnostic_scval(host))
+            .metered_collect::<Result<Vec<ScVal>, HostError>>(host)??;
+        diagnostic_map(
+            host,
+            [
+                ("exhausted", ScVal::Bool(self.is_exhausted)),
+                ("function", self.function.to_diagnostic_scval(host)?),
+                (
+                    "sub_invocations",
+                    ScVal::Vec(Some(ScVec(sub_invocations.try_into()?))),
+                ),
+            ],
+        )
+    }
+
     // Walks a path in the tree defined by `match_stack` and
     // returns the last visited authorized node.
     // metering: free
@@ -769,6 +944,22 @@ impl AuthorizationManager {
         host: &Host,
         auth_entries: Vec<SorobanAuthorizationEntry>,
     ) -> Result<Self, HostError> {
+        Ok(Self {
+            mode: AuthorizationMode::Enforcing,
+            call_stack: RefCell::new(vec![]),
+            account_trackers: RefCell::new(Self::trackers_from_authorization_entries(
+                host,
+                auth_entries,
+            )?),
+            invoker_contract_trackers: RefCell::new(vec![]),
+        })
+    }
+
+    // metering: covered
+    fn trackers_from_authorization_entries(
+        host: &Host,
+        auth_entries: Vec<SorobanAuthorizationEntry>,
+    ) -> Result<Vec<RefCell<AccountAuthorizationTracker>>, HostError> {
         Vec::<AccountAuthorizationTracker>::charge_bulk_init_cpy(auth_entries.len() as u64, host)?;
         let mut trackers = Vec::with_capacity(auth_entries.len());
         for auth_entry in auth_entries {
@@ -776,12 +967,7 @@ impl AuthorizationManager {
                 AccountAuthorizationTracker::from_authorization_entry(host, auth_entry)?,
             ));
         }
-        Ok(Self {
-            mode: AuthorizationMode::Enforcing,
-            call_stack: RefCell::new(vec![]),
-            account_trackers: RefCell::new(trackers),
-            invoker_contract_trackers: RefCell::new(vec![]),
-        })
+        Ok(trackers)
     }
 
     // Creates a new enforcing `AuthorizationManager` that doesn't allow any
@@ -807,6 +993,8 @@ impl AuthorizationManager {
             mode: AuthorizationMode::Recording(RecordingAuthInfo {
                 tracker_by_address_handle: Default::default(),
                 disable_non_root_auth,
+                enforced_trackers: vec![],
+                enforced_addresses: vec![],
             }),
             call_stack: RefCell::new(vec![]),
             account_trackers: RefCell::new(vec![]),
@@ -814,6 +1002,47 @@ impl AuthorizationManager {
         }
     }
 
+    // Creates a new recording `AuthorizationManager` that enforces the
+    // provided authorization entries for their respective addresses, while
+    // recording the authorization requirements for all the other addresses.
+    // The recorded requirements can then be retrieved using
+    // `get_recorded_auth_payloads`.
+    // metering: covered
+    #[cfg(any(test, feature = "recording_auth"))]
+    pub(crate) fn new_recording_with_entries(
+        host: &Host,
+        disable_non_root_auth: bool,
+        auth_entries: Vec<SorobanAuthorizationEntry>,
+    ) -> Result<Self, HostError> {
+        let enforced_trackers = Self::trackers_from_authorization_entries(host, auth_entries)?;
+        let mut enforced_addresses: Vec<AddressObject> = vec![];
+        for tracker in enforced_trackers.iter() {
+            let address = tracker.try_borrow_or_err()?.address;
+            let mut is_duplicate = false;
+            for existing in enforced_addresses.iter() {
+                if host.compare(existing, &address)?.is_eq() {
+                    is_duplicate = true;
+                    break;
+                }
+            }
+            if !is_duplicate {
+                Vec::<AddressObject>::charge_bulk_init_cpy(1, host)?;
+                enforced_addresses.push(address);
+            }
+        }
+        Ok(Self {
+            mode: AuthorizationMode::Recording(RecordingAuthInfo {
+                tracker_by_address_handle: Default::default(),
+                disable_non_root_auth,
+                enforced_trackers,
+                enforced_addresses,
+            }),
+            call_stack: RefCell::new(vec![]),
+            account_trackers: RefCell::new(vec![]),
+            invoker_contract_trackers: RefCell::new(vec![]),
+        })
+    }
+
     // Require the `address` to have authorized the current contract invocation
     // with provided args and within the current context (i.e. the current
     // authorized call stack and for the current network).
@@ -911,6 +1140,7 @@ impl AuthorizationManager {
     fn require_auth_enforcing(
         &self,
         host: &Host,
+        trackers: &[RefCell<AccountAuthorizationTracker>],
         address: AddressObject,
         function: &AuthorizedFunction,
     ) -> Result<(), HostError> {
@@ -920,7 +1150,7 @@ impl AuthorizationManager {
         // This prevents matching sets of disjoint authorization entries to
         // a tree of calls.
         let mut has_active_tracker = false;
-        for tracker in self.try_borrow_account_trackers(host)?.iter() {
+        for tracker in trackers.iter() {
             if let Ok(tracker) = tracker.try_borrow() {
                 // If address doesn't match, just skip the tracker.
                 if host.compare(&tracker.address, &address)?.is_eq()
@@ -935,7 +1165,7 @@ impl AuthorizationManager {
 
         // Iterate all the trackers and try to find one that
         // fulfills the authorization requirement.
-        for tracker in self.try_borrow_account_trackers(host)?.iter() {
+        for tracker in trackers.iter() {
             // Tracker can only be borrowed by the authorization manager itself.
             // The only scenario in which re-borrow might occur is when
             // `require_auth` is called within `__check_auth` call. The tracker
@@ -968,6 +1198,7 @@ impl AuthorizationManager {
         }
         // No matching tracker found, hence the invocation isn't
         // authorized.
+        self.auth_failure_diagnostics(host, trackers, address, function, !has_active_tracker);
         Err(host.err(
             ScErrorType::Auth,
             ScErrorCode::InvalidAction,
@@ -976,6 +1207,40 @@ impl AuthorizationManager {
         ))
     }
 
+    // Records a diagnostic event explaining why none of the trackers for
+    // `address` could authorize `function`.
+    // metering: covered by components (debug mode only)
+    fn auth_failure_diagnostics(
+        &self,
+        host: &Host,
+        trackers: &[RefCell<AccountAuthorizationTracker>],
+        address: AddressObject,
+        function: &AuthorizedFunction,
+        allow_matching_root: bool,
+    ) {
+        host.with_diagnostic_level(DiagnosticLevel::Errors, || {
+            let mut tracker_diagnostics = vec![];
+            for tracker in trackers.iter() {
+                if let Ok(tracker) = tracker.try_borrow() {
+                    if host.compare(&tracker.address, &address)?.is_eq() {
+                        Vec::<ScVal>::charge_bulk_init_cpy(1, host)?;
+                        tracker_diagnostics.push(tracker.diagnose_mismatch(
+                            host,
+                            function,
+                            allow_matching_root,
+                        )?);
+                    }
+                }
+            }
+            host.auth_failure_diagnostics(
+                address,
+                function.to_diagnostic_scval(host)?,
+                tracker_diagnostics,
+            );
+            Ok(())
+        })
+    }
+
     #[cfg(any(test, feature = "recording_auth"))]
     fn require_auth_recording(
         &self,
@@ -1099,11 +1364,25 @@ impl AuthorizationManager {
         }
         // Then check the AccountAuthorizationTrackers
         match &self.mode {
-            AuthorizationMode::Enforcing => self.require_auth_enforcing(host, address, &function),
-            // metering: free for recording
+            AuthorizationMode::Enforcing => self.require_auth_enforcing(
+                host,
+                &self.try_borrow_account_trackers(host)?,
+                address,
+                &function,
+            ),
             #[cfg(any(test, feature = "recording_auth"))]
             AuthorizationMode::Recording(recording_info) => {
-                self.require_auth_recording(host, address, function, recording_info)
+                if recording_info.is_enforced_address(host, address)? {
+                    self.require_auth_enforcing(
+                        host,
+                        &recording_info.enforced_trackers,
+                        address,
+                        &function,
+                    )
+                } else {
+                    // metering: free for recording
+                    self.require_auth_recording(host, address, function, recording_info)
+                }
             }
         }
     }
@@ -1113,25 +1392,9 @@ impl AuthorizationManager {
     fn snapshot(&self, host: &Host) -> Result<AuthorizationManagerSnapshot, HostError> {
         let _span = tracy_span!("snapshot auth");
         let account_trackers_snapshot = match &self.mode {
-            AuthorizationMode::Enforcing => {
-                let len = self.try_borrow_account_trackers(host)?.len();
-                let mut snapshots = Vec::with_capacity(len);
-                Vec::<Option<AccountAuthorizationTrackerSnapshot>>::charge_bulk_init_cpy(
-                    len as u64, host,
-                )?;
-                for t in self.try_borrow_account_trackers(host)?.iter() {
-                    let sp = if let Ok(tracker) = t.try_borrow() {
-                        Some(tracker.snapshot(host.as_budget())?)
-                    } else {
-                        // If tracker is borrowed, snapshotting it is a no-op
-                        // (it can't change until we release it higher up the
-                        // stack).
-                        None
-                    };
-                    snapshots.push(sp);
-                }
-                AccountTrackersSnapshot::Enforcing(snapshots)
-            }
+            AuthorizationMode::Enforcing => AccountTrackersSnapshot::Enforcing(
+                Self::snapshot_enforcing_trackers(host, &self.try_borrow_account_trackers(host)?)?,
+            ),
             #[cfg(any(test, feature = "recording_auth"))]
             AuthorizationMode::Recording(_) => {
                 // All trackers should be available to borrow for copy as in
@@ -1146,13 +1409,23 @@ impl AuthorizationManager {
             .map(|t| t.invocation_tracker.snapshot(host.as_budget()))
             .metered_collect::<Result<Vec<AuthorizedInvocationSnapshot>, HostError>>(host)??;
         #[cfg(any(test, feature = "recording_auth"))]
-        let tracker_by_address_handle = match &self.mode {
-            AuthorizationMode::Enforcing => None,
-            AuthorizationMode::Recording(recording_info) => Some(
-                // metering: free for recording
-                recording_info
-                    .try_borrow_tracker_by_address_handle(host)?
-                    .clone(),
+        let (tracker_by_address_handle, enforced_trackers_snapshot) = match &self.mode {
+            AuthorizationMode::Enforcing => (None, vec![]),
+            AuthorizationMode::Recording(recording_info) => (
+                Some(
+                    // metering: free for recording
+                    recording_info
+                        .try_borrow_tracker_by_address_handle(host)?
+                        .clone(),
+                ),
+                // Don't charge for the snapshot when there is nothing to
+                // enforce, so that the pure recording mode costs stay
+                // unchanged.
+                if recording_info.enforced_trackers.is_empty() {
+                    vec![]
+                } else {
+                    Self::snapshot_enforcing_trackers(host, &recording_info.enforced_trackers)?
+                },
             ),
         };
         Ok(AuthorizationManagerSnapshot {
@@ -1160,9 +1433,78 @@ impl AuthorizationManager {
             invoker_contract_tracker_root_snapshots,
             #[cfg(any(test, feature = "recording_auth"))]
             tracker_by_address_handle,
+            #[cfg(any(test, feature = "recording_auth"))]
+            enforced_trackers_snapshot,
         })
     }
 
+    // Snapshots the mutable part of the enforcing mode trackers.
+    // metering: covered
+    fn snapshot_enforcing_trackers(
+        host: &Host,
+        trackers: &[RefCell<AccountAuthorizationTracker>],
+    ) -> Result<Vec<Option<AccountAuthorizationTrackerSnapshot>>, HostError> {
+        let mut snapshots = Vec::with_capacity(trackers.len());
+        Vec::<Option<AccountAuthorizationTrackerSnapshot>>::charge_bulk_init_cpy(
+            trackers.len() as u64,
+            host,
+        )?;
+        for t in trackers.iter() {
+            let sp = if let Ok(tracker) = t.try_borrow() {
+                Some(tracker.snapshot(host.as_budget())?)
+            } else {
+                // If tracker is borrowed, snapshotting it is a no-op
+                // (it can't change until we release it higher up the
+                // stack).
+                None
+            };
+            snapshots.push(sp);
+        }
+        Ok(snapshots)
+    }
+
+    // Rolls back the enforcing mode trackers to the snapshot produced by
+    // `snapshot_enforcing_trackers`.
+    // metering: covered
+    fn rollback_enforcing_trackers(
+        host: &Host,
+        trackers: &[RefCell<AccountAuthorizationTracker>],
+        trackers_snapshot: &[Option<AccountAuthorizationTrackerSnapshot>],
+    ) -> Result<(), HostError> {
+        if trackers.len() != trackers_snapshot.len() {
+            return Err(host.err(
+                ScErrorType::Auth,
+                ScErrorCode::InternalError,
+                "unexpected bad auth snapshot",
+                &[],
+            ));
+        }
+        for (i, tracker) in trackers.iter().enumerate() {
+            let Some(snapopt) = trackers_snapshot.get(i) else {
+                return Err(host.err(
+                    ScErrorType::Auth,
+                    ScErrorCode::InternalError,
+                    "unexpected auth snapshot index",
+                    &[],
+                ));
+            };
+            if let Some(tracker_snapshot) = snapopt {
+                tracker
+                    .try_borrow_mut()
+                    .map_err(|_| {
+                        host.err(
+                            ScErrorType::Auth,
+                            ScErrorCode::InternalError,
+                            "unexpected bad auth borrow",
+                            &[],
+                        )
+                    })?
+                    .rollback(&tracker_snapshot)?;
+            }
+        }
+        Ok(())
+    }
+
     // Rolls back this `AuthorizationManager` to the snapshot state.
     // metering: covered
     fn rollback(
@@ -1173,38 +1515,11 @@ impl AuthorizationManager {
         let _span = tracy_span!("rollback auth");
         match snapshot.account_trackers_snapshot {
             AccountTrackersSnapshot::Enforcing(trackers_snapshot) => {
-                let trackers = self.try_borrow_account_trackers(host)?;
-                if trackers.len() != trackers_snapshot.len() {
-                    return Err(host.err(
-                        ScErrorType::Auth,
-                        ScErrorCode::InternalError,
-                        "unexpected bad auth snapshot",
-                        &[],
-                    ));
-                }
-                for (i, tracker) in trackers.iter().enumerate() {
-                    let Some(snapopt) = trackers_snapshot.get(i) else {
-                        return Err(host.err(
-                            ScErrorType::Auth,
-                            ScErrorCode::InternalError,
-                            "unexpected auth snapshot index",
-                            &[],
-                        ));
-                    };
-                    if let Some(tracker_snapshot) = snapopt {
-                        tracker
-                            .try_borrow_mut()
-                            .map_err(|_| {
-                                host.err(
-                                    ScErrorType::Auth,
-                                    ScErrorCode::InternalError,
-                                    "unexpected bad auth borrow",
-                                    &[],
-                                )
-                            })?
-                            .rollback(&tracker_snapshot)?;
-                    }
-                }
+                Self::rollback_enforcing_trackers(
+                    host,
+                    &self.try_borrow_account_trackers(host)?,
+                    &trackers_snapshot,
+                )?;
             }
             #[cfg(any(test, feature = "recording_auth"))]
             AccountTrackersSnapshot::Recording(s) => {
@@ -1235,6 +1550,11 @@ impl AuthorizationManager {
                 AuthorizationMode::Recording(recording_info) => {
                     *recording_info.try_borrow_tracker_by_address_handle_mut(host)? =
                         tracker_by_address_handle;
+                    Self::rollback_enforcing_trackers(
+                        host,
+                        &recording_info.enforced_trackers,
+                        &snapshot.enforced_trackers_snapshot,
+                    )?;
                 }
             }
         }
@@ -1250,6 +1570,14 @@ impl AuthorizationManager {
                 tracker.push_frame(host.as_budget())?;
             }
         }
+        #[cfg(any(test, feature = "recording_auth"))]
+        if let AuthorizationMode::Recording(recording_info) = &self.mode {
+            for tracker in recording_info.enforced_trackers.iter() {
+                if let Ok(mut tracker) = tracker.try_borrow_mut() {
+                    tracker.push_frame(host.as_budget())?;
+                }
+            }
+        }
         for tracker in self
             .try_borrow_invoker_contract_trackers_mut(host)?
             .iter_mut()
@@ -1341,6 +1669,14 @@ impl AuthorizationManager {
                 tracker.pop_frame();
             }
         }
+        #[cfg(any(test, feature = "recording_auth"))]
+        if let AuthorizationMode::Recording(recording_info) = &self.mode {
+            for tracker in recording_info.enforced_trackers.iter() {
+                if let Ok(mut tracker) = tracker.try_borrow_mut() {
+                    tracker.pop_frame();
+                }
+            }
+        }
 
         let mut invoker_contract_trackers = self.try_borrow_invoker_contract_trackers_mut(host)?;
         for tracker in invoker_contract_trackers.iter_mut() {
@@ -1429,18 +1765,24 @@ impl AuthorizationManager {
     }
 
     // Returns all authorizations that have been authenticated for the
-    // last contract invocation.
+    // last contract invocation, including the ones enforced using the signed
+    // entries in the recording mode.
     // metering: free, testutils
     #[cfg(any(test, feature = "testutils"))]
     pub(crate) fn get_authenticated_authorizations(
         &self,
         host: &Host,
     ) -> Vec<(ScAddress, xdr::SorobanAuthorizedInvocation)> {
+        let enforced_trackers: &[RefCell<AccountAuthorizationTracker>] = match &self.mode {
+            AuthorizationMode::Enforcing => &[],
+            AuthorizationMode::Recording(recording_info) => &recording_info.enforced_trackers,
+        };
         host.as_budget()
             .with_observable_shadow_mode(|| {
                 self.account_trackers
                     .borrow()
                     .iter()
+                    .chain(enforced_trackers.iter())
                     .filter(|t| t.borrow().verified)
                     .map(|t| {
                         (
@@ -1660,6 +2002,80 @@ impl InvocationTracker {
         Ok(new_match_state.is_matched())
     }
 
+    // Explains why `maybe_extend_invocation_match` hasn't matched the provided
+    // invocation. Returns a description for every node of the authorized
+    // invocation tree that has been considered as a match candidate.
+    // metering: covered by components
+    fn diagnose_mismatch(
+        &self,
+        host: &Host,
+        function: &AuthorizedFunction,
+        allow_matching_root: bool,
+    ) -> Result<Vec<ScVal>, HostError> {
+        let mut path = vec![];
+        let mut curr_invocation = None;
+        for m in self.match_stack.iter() {
+            match m {
+                MatchState::RootMatch => curr_invocation = Some(&self.root_authorized_invocation),
+                MatchState::SubMatch { index_in_parent } => {
+                    curr_invocation = curr_invocation.and_then(|i: &AuthorizedInvocation| {
+                        i.sub_invocations.get(*index_in_parent)
+                    });
+                    Vec::<u32>::charge_bulk_init_cpy(1, host)?;
+                    path.push(host.usize_to_u32(*index_in_parent)?);
+                }
+                MatchState::Unmatched => (),
+            }
+        }
+        let mut mismatches = vec![];
+        if self.current_frame_is_already_matched() {
+            Vec::<ScVal>::charge_bulk_init_cpy(1, host)?;
+            mismatches.push(diagnostic_mismatch(
+                host,
+                &path,
+                "frame_already_matched",
+                None,
+            )?);
+        } else if let Some(curr_invocation) = curr_invocation {
+            if curr_invocation.sub_invocations.is_empty() {
+                Vec::<ScVal>::charge_bulk_init_cpy(1, host)?;
+                mismatches.push(diagnostic_mismatch(
+                    host,
+                    &path,
+                    "no_sub_invocations",
+                    None,
+                )?);
+            }
+            for (index_in_parent, sub_invocation) in
+                curr_invocation.sub_invocations.iter().enumerate()
+            {
+                let (reason, arg_index) = if sub_invocation.is_exhausted {
+                    ("exhausted", None)
+                } else {
+                    sub_invocation.function.diagnose_mismatch(host, function)?
+                };
+                let mut sub_path = path.metered_clone(host)?;
+                Vec::<u32>::charge_bulk_init_cpy(1, host)?;
+                sub_path.push(host.usize_to_u32(index_in_parent)?);
+                Vec::<ScVal>::charge_bulk_init_cpy(1, host)?;
+                mismatches.push(diagnostic_mismatch(host, &sub_path, reason, arg_index)?);
+            }
+        } else {
+            let (reason, arg_index) = if self.root_authorized_invocation.is_exhausted {
+                ("exhausted", None)
+            } else if !allow_matching_root {
+                ("root_not_allowed", None)
+            } else {
+                self.root_authorized_invocation
+                    .function
+                    .diagnose_mismatch(host, function)?
+            };
+            Vec::<ScVal>::charge_bulk_init_cpy(1, host)?;
+            mismatches.push(diagnostic_mismatch(host, &path, reason, arg_index)?);
+        }
+        Ok(mismatches)
+    }
+
     // Records the invocation in this tracker.
     // This is needed for the recording mode only.
     // This assumes that the address matching is correctly performed before
@@ -1915,6 +2331,35 @@ impl AccountAuthorizationTracker {
             .to_xdr(host, false)
     }
 
+    // Describes the authorized invocation tree of this tracker along with the
+    // nodes that failed to match `function`.
+    // metering: covered by components
+    fn diagnose_mismatch(
+        &self,
+        host: &Host,
+        function: &AuthorizedFunction,
+        allow_matching_root: bool,
+    ) -> Result<ScVal, HostError> {
+        let mismatches =
+            self.invocation_tracker
+                .diagnose_mismatch(host, function, allow_matching_root)?;
+        diagnostic_map(
+            host,
+            [
+                (
+                    "mismatches",
+                    ScVal::Vec(Some(ScVec(mismatches.try_into()?))),
+                ),
+                (
+                    "tree",
+                    self.invocation_tracker
+                        .root_authorized_invocation
+                        .to_diagnostic_scval(host)?,
+                ),
+            ],
+        )
+    }
+
     // metering: covered
     fn push_frame(&mut self, budget: &Budget) -> Result<(), HostError> {
         self.invocation_tracker.push_frame(budget)
@@ -0,0 +1,212 @@
+//! This module contains helpers for signing the authorization entries, i.e.
+//! for turning the unsigned [`SorobanAuthorizationEntry`] entries (such as
+//! the entries recorded in the recording authorization mode) into the entries
+//! that pass the authentication in the enforcing mode.
+//!
+//! The helpers don't require a [`Host`](crate::Host) instance and are not
+//! metered, so they can be used by the clients (such as wallets) as well as
+//! by the tests.
+//!
+//! Signing an entry consists of the following steps:
+//! - Building the [`HashIdPreimage::SorobanAuthorization`] preimage from the
+//!   network id, the nonce, the signature expiration ledger and the root
+//!   invocation of the entry ([`authorization_preimage`]).
+//! - Hashing the preimage to get the signature payload
+//!   ([`authorization_payload`]).
+//! - Signing the payload and building the signature value in the format
+//!   expected by the address. For the classic Stellar accounts this is a
+//!   vector of `AccountEd25519Signature` structures sorted by the public key
+//!   ([`account_signature`]), while the custom accounts define their own
+//!   signature format.
+
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    xdr::{
+        HashIdPreimage, HashIdPreimageSorobanAuthorization, ScAddress, ScBytes, ScErrorCode,
+        ScErrorType, ScMap, ScMapEntry, ScSymbol, ScVal, ScVec, SorobanAuthorizationEntry,
+        SorobanAuthorizedInvocation, SorobanCredentials, WriteXdr,
+    },
+    HostError, DEFAULT_XDR_RW_LIMITS,
+};
+
+#[cfg(any(test, feature = "recording_auth"))]
+use crate::{auth::RecordedAuthPayload, xdr::SorobanAddressCredentials};
+
+fn invalid_input() -> HostError {
+    (ScErrorType::Auth, ScErrorCode::InvalidInput).into()
+}
+
+/// Computes the network id (SHA-256 hash of the network passphrase) that is
+/// a part of every authorization preimage.
+pub fn network_id_from_passphrase(network_passphrase: &str) -> [u8; 32] {
+    Sha256::digest(network_passphrase.as_bytes()).into()
+}
+
+/// Builds the preimage of the signature payload for authorizing
+/// `invocation` with the given `nonce` until `signature_expiration_ledger`
+/// (inclusive) on the network identified by `network_id`.
+pub fn authorization_preimage(
+    network_id: &[u8; 32],
+    nonce: i64,
+    signature_expiration_ledger: u32,
+    invocation: SorobanAuthorizedInvocation,
+) -> HashIdPreimage {
+    HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+        network_id: (*network_id).into(),
+        nonce,
+        signature_expiration_ledger,
+        invocation,
+    })
+}
+
+/// Computes the signature payload, i.e. the SHA-256 hash of the XDR-encoded
+/// preimage.
+pub fn authorization_payload(preimage: &HashIdPreimage) -> Result<[u8; 32], HostError> {
+    let preimage_xdr = preimage.to_xdr(DEFAULT_XDR_RW_LIMITS)?;
+    Ok(Sha256::digest(preimage_xdr).into())
+}
+
+/// Builds the signature value of a classic Stellar account for the provided
+/// signature `payload` signed by every key in `signers`.
+///
+/// The signatures are sorted by the public key and the duplicate signers are
+/// removed, as required by the account authentication. The total weight of
+/// the signers has to reach the account's medium threshold for the
+/// authentication to succeed.
+pub fn account_signature(payload: &[u8; 32], signers: &[SigningKey]) -> Result<ScVal, HostError> {
+    let mut signers: Vec<&SigningKey> = signers.iter().collect();
+    signers.sort_by_key(|signer| signer.verifying_key().to_bytes());
+    signers.dedup_by_key(|signer| signer.verifying_key().to_bytes());
+    let signatures = signers
+        .into_iter()
+        .map(|signer| -> Result<ScVal, HostError> {
+            let public_key = ScVal::Bytes(ScBytes(
+                signer.verifying_key().to_bytes().to_vec().try_into()?,
+            ));
+            let signature = ScVal::Bytes(ScBytes(
+                signer
+                    .sign(payload.as_slice())
+                    .to_bytes()
+                    .to_vec()
+                    .try_into()?,
+            ));
+            // The map keys must be sorted.
+            Ok(ScVal::Map(Some(ScMap(
+                vec![
+                    ScMapEntry {
+                        key: ScVal::Symbol(ScSymbol("public_key".try_into()?)),
+                        val: public_key,
+                    },
+                    ScMapEntry {
+                        key: ScVal::Symbol(ScSymbol("signature".try_into()?)),
+                        val: signature,
+                    },
+                ]
+                .try_into()?,
+            ))))
+        })
+        .collect::<Result<Vec<ScVal>, HostError>>()?;
+    Ok(ScVal::Vec(Some(ScVec(signatures.try_into()?))))
+}
+
+/// Signs the authorization entry using the provided `sign` function that
+/// receives the signature payload and returns the signature value in the
+/// format expected by the entry address (e.g. a custom account contract).
+///
+/// The signature expiration ledger of the entry is set to
+/// `signature_expiration_ledger`. Entries with the source account credentials
+/// don't need a signature and are returned unchanged.
+pub fn sign_authorization_entry_with<F>(
+    entry: &SorobanAuthorizationEntry,
+    network_id: &[u8; 32],
+    signature_expiration_ledger: u32,
+    sign: F,
+) -> Result<SorobanAuthorizationEntry, HostError>
+where
+    F: FnOnce(&[u8; 32]) -> Result<ScVal, HostError>,
+{
+    let mut entry = entry.clone();
+    if let SorobanCredentials::Address(credentials) = &mut entry.credentials {
+        let preimage = authorization_preimage(
+            network_id,
+            credentials.nonce,
+            signature_expiration_ledger,
+            entry.root_invocation.clone(),
+        );
+        credentials.signature_expiration_ledger = signature_expiration_ledger;
+        credentials.signature = sign(&authorization_payload(&preimage)?)?;
+    }
+    Ok(entry)
+}
+
+/// Signs the authorization entry of a classic Stellar account with the
+/// provided ed25519 `signers` (see [`account_signature`]).
+///
+/// Returns an error if the entry address is not a classic account, as the
+/// custom accounts need to be signed via [`sign_authorization_entry_with`].
+pub fn sign_account_authorization_entry(
+    entry: &SorobanAuthorizationEntry,
+    network_id: &[u8; 32],
+    signature_expiration_ledger: u32,
+    signers: &[SigningKey],
+) -> Result<SorobanAuthorizationEntry, HostError> {
+    if let SorobanCredentials::Address(credentials) = &entry.credentials {
+        if !matches!(credentials.address, ScAddress::Account(_)) {
+            return Err(invalid_input());
+        }
+    }
+    sign_authorization_entry_with(entry, network_id, signature_expiration_ledger, |payload| {
+        account_signature(payload, signers)
+    })
+}
+
+/// Builds the unsigned authorization entry for the payload recorded in the
+/// recording authorization mode.
+#[cfg(any(test, feature = "recording_auth"))]
+pub fn recorded_auth_payload_to_entry(
+    payload: RecordedAuthPayload,
+) -> Result<SorobanAuthorizationEntry, HostError> {
+    let credentials = match (payload.address, payload.nonce) {
+        (Some(address), Some(nonce)) => SorobanCredentials::Address(SorobanAddressCredentials {
+            address,
+            nonce,
+            signature_expiration_ledger: 0,
+            signature: ScVal::Void,
+        }),
+        (None, None) => SorobanCredentials::SourceAccount,
+        _ => return Err((ScErrorType::Auth, ScErrorCode::InternalError).into()),
+    };
+    Ok(SorobanAuthorizationEntry {
+        credentials,
+        root_invocation: payload.invocation,
+    })
+}
+
+/// Builds the signed authorization entries for the payloads recorded in the
+/// recording authorization mode. `signers` provides the signing keys for
+/// every recorded (classic account) address; the source account payloads
+/// don't need to be signed.
+#[cfg(any(test, feature = "recording_auth"))]
+pub fn sign_recorded_auth_payloads<F>(
+    payloads: Vec<RecordedAuthPayload>,
+    network_id: &[u8; 32],
+    signature_expiration_ledger: u32,
+    mut signers: F,
+) -> Result<Vec<SorobanAuthorizationEntry>, HostError>
+where
+    F: FnMut(&ScAddress) -> Vec<SigningKey>,
+{
+    payloads
+        .into_iter()
+        .map(|payload| {
+            let entry = recorded_auth_payload_to_entry(payload)?;
+            let keys = match &entry.credentials {
+                SorobanCredentials::Address(credentials) => signers(&credentials.address),
+                SorobanCredentials::SourceAccount => vec![],
+            };
+            sign_account_authorization_entry(&entry, network_id, signature_expiration_ledger, &keys)
+        })
+        .collect()
+}
@@ -1,9 +1,11 @@
+mod call_tree;
 mod dimension;
 mod limits;
 mod model;
 mod util;
 mod wasmi_helper;
 
+pub use call_tree::{write_folded_stacks, CallFrameCost, FoldedStackWeight};
 pub(crate) use limits::DepthLimiter;
 pub use limits::{DEFAULT_HOST_DEPTH_LIMIT, DEFAULT_XDR_RW_LIMITS};
 pub use model::{MeteredCostComponent, ScaledU64};
@@ -20,8 +22,9 @@ use crate::{
     Error, Host, HostError,
 };
 
+use call_tree::CallTreeTracker;
 use dimension::{BudgetDimension, IsCpu, IsShadowMode};
-use wasmi_helper::FuelConfig;
+pub(crate) use wasmi_helper::FuelConfig;
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CostTracker {
@@ -139,6 +142,8 @@ pub(crate) struct BudgetImpl {
     is_in_shadow_mode: bool,
     fuel_config: FuelConfig,
     depth_limit: u32,
+    /// For reporting per-call-frame consumption only, see `Budget::enable_call_tree`
+    call_tree: Option<CallTreeTracker>,
 }
 
 impl BudgetImpl {
@@ -156,6 +161,7 @@ impl BudgetImpl {
             is_in_shadow_mode: false,
             fuel_config: Default::default(),
             depth_limit: DEFAULT_HOST_DEPTH_LIMIT,
+            call_tree: None,
         };
 
         b.cpu_insns.reset(cpu_limit);
@@ -209,6 +215,9 @@ impl BudgetImpl {
         )?;
         if !self.is_in_shadow_mode {
             tracker.mem = tracker.mem.saturating_add(mem_charged);
+            if let Some(call_tree) = self.call_tree.as_mut() {
+                call_tree.record_charge(ty, iterations, cpu_charged, mem_charged);
+            }
         }
         self.mem_bytes
             .check_budget_limit(IsShadowMode(self.is_in_shadow_mode))
@@ -248,6 +257,7 @@ impl Default for BudgetImpl {
             is_in_shadow_mode: false,
             fuel_config: Default::default(),
             depth_limit: DEFAULT_HOST_DEPTH_LIMIT,
+            call_tree: None,
         };
 
         for ct in ContractCostType::variants() {
@@ -702,6 +712,19 @@ impl Budget {
         )?))))
     }
 
+    /// Like [Budget::default], but without the CPU and memory limits. This is
+    /// only meant for the metered helpers used outside of the contract
+    /// execution, e.g. for preparing its inputs.
+    pub(crate) fn try_new_unlimited() -> Result<Self, HostError> {
+        let budget = Self::default();
+        budget.with_mut_budget(|mut b| {
+            b.cpu_insns.reset(u64::MAX);
+            b.mem_bytes.reset(u64::MAX);
+            Ok(())
+        })?;
+        Ok(budget)
+    }
+
     // Helper function to avoid panics from multiple borrow_muts
     fn with_mut_budget<T, F>(&self, f: F) -> Result<T, HostError>
     where
@@ -799,6 +822,55 @@ impl Budget {
             .track_time(ty, duration)
     }
 
+    /// Starts attributing budget consumption to the call frames (host
+    /// function invocations and contract calls) it occurs in, discarding any
+    /// previously recorded call tree. This is for reporting only and has no
+    /// effect on metering.
+    pub fn enable_call_tree(&self) -> Result<(), HostError> {
+        self.0.try_borrow_mut_or_err()?.call_tree = Some(Default::default());
+        Ok(())
+    }
+
+    /// Returns the [CallFrameCost] tree of every top-level frame completed
+    /// since [Budget::enable_call_tree] was called, or an empty vector if it
+    /// wasn't. Since the budget is shared with (and outlives) the [Host], this
+    /// can be called after [Host::try_finish].
+    pub fn get_call_tree(&self) -> Result<Vec<CallFrameCost>, HostError> {
+        Ok(self
+            .0
+            .try_borrow_or_err()?
+            .call_tree
+            .as_ref()
+            .map(|t| t.roots().to_vec())
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn is_tracking_call_tree(&self) -> Result<bool, HostError> {
+        Ok(self.0.try_borrow_or_err()?.call_tree.is_some())
+    }
+
+    pub(crate) fn push_call_frame(
+        &self,
+        contract_id: Option<crate::xdr::Hash>,
+        function: String,
+    ) -> Result<(), HostError> {
+        let mut b = self.0.try_borrow_mut_or_err()?;
+        let (cpu, mem) = (b.cpu_insns.get_total_count(), b.mem_bytes.get_total_count());
+        if let Some(tree) = b.call_tree.as_mut() {
+            tree.push(contract_id, function, cpu, mem);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pop_call_frame(&self) -> Result<(), HostError> {
+        let mut b = self.0.try_borrow_mut_or_err()?;
+        let (cpu, mem) = (b.cpu_insns.get_total_count(), b.mem_bytes.get_total_count());
+        if let Some(tree) = b.call_tree.as_mut() {
+            tree.pop(cpu, mem);
+        }
+        Ok(())
+    }
+
     pub fn get_cpu_insns_consumed(&self) -> Result<u64, HostError> {
         Ok(self.0.try_borrow_or_err()?.cpu_insns.get_total_count())
     }
@@ -819,6 +891,10 @@ impl Budget {
         self.0.try_borrow_mut_or_err()?.get_wasmi_fuel_remaining()
     }
 
+    pub(crate) fn wasmi_fuel_config(&self) -> Result<FuelConfig, HostError> {
+        Ok(self.0.try_borrow_or_err()?.fuel_config.clone())
+    }
+
     // generate a wasmi fuel cost schedule based on our calibration
     pub(crate) fn wasmi_fuel_costs(&self) -> Result<wasmi::FuelCosts, HostError> {
         let config = &self.0.try_borrow_or_err()?.fuel_config;
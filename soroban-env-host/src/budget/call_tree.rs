@@ -0,0 +1,187 @@
+use super::CostTracker;
+use crate::xdr::{ContractCostType, Hash};
+use std::{collections::BTreeMap, io::Write};
+
+/// The budget consumed by a single call frame (a host function invocation or
+/// a contract function call), along with the frames it called in turn.
+///
+/// Inclusive costs cover everything charged while the frame was on the
+/// context stack, including its children; exclusive costs exclude the
+/// inclusive costs of its children.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallFrameCost {
+    /// The contract the frame executes, or `None` for host function frames.
+    pub contract_id: Option<Hash>,
+    /// The contract function called, or the host function type name for host
+    /// function frames.
+    pub function: String,
+    pub cpu_insns_inclusive: u64,
+    pub mem_bytes_inclusive: u64,
+    pub cpu_insns_exclusive: u64,
+    pub mem_bytes_exclusive: u64,
+    /// The exclusive costs broken down by cost type. `inputs` is not tracked.
+    pub cost_types: BTreeMap<ContractCostType, CostTracker>,
+    pub children: Vec<CallFrameCost>,
+}
+
+impl CallFrameCost {
+    /// Calls `f` on this frame and all its descendants in depth-first
+    /// pre-order, along with the depth of each frame (0 for this frame).
+    pub fn visit<F: FnMut(usize, &CallFrameCost)>(&self, f: &mut F) {
+        self.visit_at_depth(0, f)
+    }
+
+    /// Returns the name of the frame as it appears in a folded-stack export:
+    /// the contract's strkey and the function name separated by a colon, or
+    /// just the host function name for host function frames.
+    pub fn label(&self) -> String {
+        match &self.contract_id {
+            Some(id) => format!("{}:{}", stellar_strkey::Contract(id.0), self.function),
+            None => self.function.clone(),
+        }
+    }
+
+    fn visit_at_depth<F: FnMut(usize, &CallFrameCost)>(&self, depth: usize, f: &mut F) {
+        f(depth, self);
+        for child in self.children.iter() {
+            child.visit_at_depth(depth + 1, f);
+        }
+    }
+}
+
+struct OpenFrame {
+    cost: CallFrameCost,
+    cpu_start: u64,
+    mem_start: u64,
+}
+
+/// Builds the tree of [CallFrameCost]s from the budget totals observed as
+/// frames are pushed and popped. This is for reporting only and is never
+/// consulted for budget-limiting.
+#[derive(Default)]
+pub(crate) struct CallTreeTracker {
+    open: Vec<OpenFrame>,
+    roots: Vec<CallFrameCost>,
+}
+
+impl Clone for CallTreeTracker {
+    // Frames still open when the budget is cloned are not carried over.
+    fn clone(&self) -> Self {
+        Self {
+            open: Vec::new(),
+            roots: self.roots.clone(),
+        }
+    }
+}
+
+impl CallTreeTracker {
+    pub(crate) fn push(
+        &mut self,
+        contract_id: Option<Hash>,
+        function: String,
+        cpu_start: u64,
+        mem_start: u64,
+    ) {
+        self.open.push(OpenFrame {
+            cost: CallFrameCost {
+                contract_id,
+                function,
+                ..Default::default()
+            },
+            cpu_start,
+            mem_start,
+        })
+    }
+
+    pub(crate) fn pop(&mut self, cpu_end: u64, mem_end: u64) {
+        let Some(OpenFrame {
+            mut cost,
+            cpu_start,
+            mem_start,
+        }) = self.open.pop()
+        else {
+            return;
+        };
+        cost.cpu_insns_inclusive = cpu_end.saturating_sub(cpu_start);
+        cost.mem_bytes_inclusive = mem_end.saturating_sub(mem_start);
+        let (children_cpu, children_mem) = cost.children.iter().fold((0u64, 0u64), |acc, c| {
+            (
+                acc.0.saturating_add(c.cpu_insns_inclusive),
+                acc.1.saturating_add(c.mem_bytes_inclusive),
+            )
+        });
+        cost.cpu_insns_exclusive = cost.cpu_insns_inclusive.saturating_sub(children_cpu);
+        cost.mem_bytes_exclusive = cost.mem_bytes_inclusive.saturating_sub(children_mem);
+        match self.open.last_mut() {
+            Some(parent) => parent.cost.children.push(cost),
+            None => self.roots.push(cost),
+        }
+    }
+
+    pub(crate) fn record_charge(
+        &mut self,
+        ty: ContractCostType,
+        iterations: u64,
+        cpu: u64,
+        mem: u64,
+    ) {
+        if let Some(frame) = self.open.last_mut() {
+            let tracker = frame.cost.cost_types.entry(ty).or_default();
+            tracker.iterations = tracker.iterations.saturating_add(iterations);
+            tracker.cpu = tracker.cpu.saturating_add(cpu);
+            tracker.mem = tracker.mem.saturating_add(mem);
+        }
+    }
+
+    pub(crate) fn roots(&self) -> &[CallFrameCost] {
+        &self.roots
+    }
+}
+
+/// The quantity used as the weight of each stack in a folded-stack export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FoldedStackWeight {
+    CpuInsns,
+    MemBytes,
+}
+
+/// Writes the call trees in `roots` in the collapsed ("folded") stack format
+/// consumed by flamegraph tooling such as `inferno` and `flamegraph.pl`: one
+/// line per frame and cost type charged in it, consisting of the labels of the
+/// frames from the root down to the frame, then the cost type, separated by
+/// semicolons, followed by a space and the exclusive cost. Lines with zero
+/// cost are omitted.
+pub fn write_folded_stacks<W: Write>(
+    roots: &[CallFrameCost],
+    weight: FoldedStackWeight,
+    w: &mut W,
+) -> std::io::Result<()> {
+    let mut stack = Vec::new();
+    for root in roots {
+        write_frame_folded_stacks(root, weight, &mut stack, w)?;
+    }
+    Ok(())
+}
+
+fn write_frame_folded_stacks<W: Write>(
+    frame: &CallFrameCost,
+    weight: FoldedStackWeight,
+    stack: &mut Vec<String>,
+    w: &mut W,
+) -> std::io::Result<()> {
+    stack.push(frame.label());
+    for (ty, tracker) in frame.cost_types.iter() {
+        let cost = match weight {
+            FoldedStackWeight::CpuInsns => tracker.cpu,
+            FoldedStackWeight::MemBytes => tracker.mem,
+        };
+        if cost > 0 {
+            writeln!(w, "{};{} {}", stack.join(";"), ty.name(), cost)?;
+        }
+    }
+    for child in frame.children.iter() {
+        write_frame_folded_stacks(child, weight, stack, w)?;
+    }
+    stack.pop();
+    Ok(())
+}
@@ -0,0 +1,291 @@
+//! This module decodes the `contractspecv0` custom section that the SDK embeds
+//! in contract WASM into a [ContractSpec] (the contract's function signatures
+//! and user-defined types), and checks invocation arguments against it.
+//!
+//! The host does not need the spec to run a contract; checking arguments
+//! against it is opt-in (see [Host::set_contract_spec_validation]) and exists
+//! so that tooling can report a mistyped argument by name, rather than as a
+//! trap somewhere inside the guest.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    budget::Budget,
+    host::metered_clone::MeteredContainer,
+    xdr::{
+        ContractCostType, Hash, Limited, ReadXdr, ScError, ScErrorCode, ScErrorType, ScSpecEntry,
+        ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtUnionCaseV0, ScVal,
+    },
+    Host, HostError, Symbol, SymbolStr, TryFromVal, TryIntoVal, U32Val, Val, Vm,
+    DEFAULT_XDR_RW_LIMITS,
+};
+
+/// Name of the WASM custom section holding the XDR stream of [ScSpecEntry]s.
+pub const CONTRACT_SPEC_SECTION_NAME: &str = "contractspecv0";
+
+/// The decoded contents of a contract's `contractspecv0` section: its
+/// function signatures, keyed by function name, and its user-defined types
+/// (structs, unions, enums and error enums), keyed by type name.
+#[derive(Clone, Debug, Default)]
+pub struct ContractSpec {
+    functions: BTreeMap<String, ScSpecFunctionV0>,
+    udts: BTreeMap<String, ScSpecEntry>,
+}
+
+/// Describes how a list of arguments fails to match a function's spec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpecMismatch {
+    /// The function takes `expected` arguments but was passed `actual`.
+    ArgCount { expected: usize, actual: usize },
+    /// The argument at `index`, named `name` in the spec, does not have the
+    /// declared type.
+    ArgType { index: usize, name: String },
+}
+
+impl ContractSpec {
+    /// Decodes a `contractspecv0` section, charging the deserialization of
+    /// its bytes to `budget`.
+    pub fn from_xdr_stream(budget: &Budget, section: &[u8]) -> Result<Self, HostError> {
+        let _span = tracy_span!("decode contract spec");
+        budget.charge(ContractCostType::ValDeser, Some(section.len() as u64))?;
+        let mut limits = DEFAULT_XDR_RW_LIMITS;
+        limits.len = section.len();
+        let mut spec = ContractSpec::default();
+        let mut cursor = Limited::new(std::io::Cursor::new(section), limits);
+        for entry in ScSpecEntry::read_xdr_iter(&mut cursor) {
+            match entry? {
+                ScSpecEntry::FunctionV0(f) => {
+                    spec.functions.insert(f.name.0.to_utf8_string_lossy(), f);
+                }
+                e @ ScSpecEntry::UdtStructV0(_) => spec.insert_udt(e),
+                e @ ScSpecEntry::UdtUnionV0(_) => spec.insert_udt(e),
+                e @ ScSpecEntry::UdtEnumV0(_) => spec.insert_udt(e),
+                e @ ScSpecEntry::UdtErrorEnumV0(_) => spec.insert_udt(e),
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Like [ContractSpec::from_xdr_stream], but returns an empty spec if
+    /// there is no `section`.
+    pub(crate) fn from_custom_section(
+        budget: &Budget,
+        section: Option<&[u8]>,
+    ) -> Result<Self, HostError> {
+        match section {
+            Some(section) => Self::from_xdr_stream(budget, section),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn insert_udt(&mut self, entry: ScSpecEntry) {
+        let name = match &entry {
+            ScSpecEntry::FunctionV0(_) => return,
+            ScSpecEntry::UdtStructV0(s) => s.name.to_utf8_string_lossy(),
+            ScSpecEntry::UdtUnionV0(u) => u.name.to_utf8_string_lossy(),
+            ScSpecEntry::UdtEnumV0(e) => e.name.to_utf8_string_lossy(),
+            ScSpecEntry::UdtErrorEnumV0(e) => e.name.to_utf8_string_lossy(),
+        };
+        self.udts.insert(name, entry);
+    }
+
+    /// Returns the signature of the function named `name`, if declared.
+    pub fn function(&self, name: &str) -> Option<&ScSpecFunctionV0> {
+        self.functions.get(name)
+    }
+
+    /// Returns all declared function signatures, ordered by name.
+    pub fn functions(&self) -> impl Iterator<Item = &ScSpecFunctionV0> {
+        self.functions.values()
+    }
+
+    /// Returns the definition of the user-defined type named `name`, if
+    /// declared.
+    pub fn udt(&self, name: &str) -> Option<&ScSpecEntry> {
+        self.udts.get(name)
+    }
+
+    /// Checks `args` against the declared inputs of function `func`. Functions
+    /// that aren't declared in the spec are not checked.
+    pub fn check_args(&self, func: &str, args: &[ScVal]) -> Result<(), SpecMismatch> {
+        let Some(f) = self.function(func) else {
+            return Ok(());
+        };
+        if f.inputs.len() != args.len() {
+            return Err(SpecMismatch::ArgCount {
+                expected: f.inputs.len(),
+                actual: args.len(),
+            });
+        }
+        for (index, (input, arg)) in f.inputs.iter().zip(args.iter()).enumerate() {
+            if !self.value_matches(&input.type_, arg) {
+                return Err(SpecMismatch::ArgType {
+                    index,
+                    name: input.name.to_utf8_string_lossy(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if `val` is a valid encoding of a value of type `ty`.
+    pub fn value_matches(&self, ty: &ScSpecTypeDef, val: &ScVal) -> bool {
+        match (ty, val) {
+            (ScSpecTypeDef::Val, _)
+            | (ScSpecTypeDef::Bool, ScVal::Bool(_))
+            | (ScSpecTypeDef::Void, ScVal::Void)
+            | (ScSpecTypeDef::Error, ScVal::Error(_))
+            | (ScSpecTypeDef::U32, ScVal::U32(_))
+            | (ScSpecTypeDef::I32, ScVal::I32(_))
+            | (ScSpecTypeDef::U64, ScVal::U64(_))
+            | (ScSpecTypeDef::I64, ScVal::I64(_))
+            | (ScSpecTypeDef::Timepoint, ScVal::Timepoint(_))
+            | (ScSpecTypeDef::Duration, ScVal::Duration(_))
+            | (ScSpecTypeDef::U128, ScVal::U128(_))
+            | (ScSpecTypeDef::I128, ScVal::I128(_))
+            | (ScSpecTypeDef::U256, ScVal::U256(_))
+            | (ScSpecTypeDef::I256, ScVal::I256(_))
+            | (ScSpecTypeDef::Bytes, ScVal::Bytes(_))
+            | (ScSpecTypeDef::String, ScVal::String(_))
+            | (ScSpecTypeDef::Symbol, ScVal::Symbol(_))
+            | (ScSpecTypeDef::Address, ScVal::Address(_)) => true,
+            (ScSpecTypeDef::Option(_), ScVal::Void) => true,
+            (ScSpecTypeDef::Option(o), v) => self.value_matches(&o.value_type, v),
+            (ScSpecTypeDef::Result(r), v) => {
+                self.value_matches(&r.ok_type, v) || self.value_matches(&r.error_type, v)
+            }
+            (ScSpecTypeDef::Vec(t), ScVal::Vec(Some(v))) => {
+                v.iter().all(|e| self.value_matches(&t.element_type, e))
+            }
+            (ScSpecTypeDef::Map(t), ScVal::Map(Some(m))) => m.iter().all(|e| {
+                self.value_matches(&t.key_type, &e.key) && self.value_matches(&t.value_type, &e.val)
+            }),
+            (ScSpecTypeDef::Tuple(t), ScVal::Vec(Some(v))) => {
+                t.value_types.len() == v.len()
+                    && t.value_types
+                        .iter()
+                        .zip(v.iter())
+                        .all(|(t, e)| self.value_matches(t, e))
+            }
+            (ScSpecTypeDef::BytesN(n), ScVal::Bytes(b)) => b.len() == n.n as usize,
+            (ScSpecTypeDef::Udt(u), v) => match self.udt(&u.name.to_utf8_string_lossy()) {
+                Some(udt) => self.udt_value_matches(udt, v),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn udt_value_matches(&self, udt: &ScSpecEntry, val: &ScVal) -> bool {
+        match (udt, val) {
+            // Structs with numbered fields are encoded as tuples, others as
+            // maps keyed by field name (in the order the SDK sorts them).
+            (ScSpecEntry::UdtStructV0(s), ScVal::Vec(Some(v)))
+                if s.fields
+                    .iter()
+                    .all(|f| f.name.iter().all(u8::is_ascii_digit)) =>
+            {
+                s.fields.len() == v.len()
+                    && s.fields
+                        .iter()
+                        .zip(v.iter())
+                        .all(|(f, e)| self.value_matches(&f.type_, e))
+            }
+            (ScSpecEntry::UdtStructV0(s), ScVal::Map(Some(m))) => {
+                s.fields.len() == m.len()
+                    && s.fields.iter().all(|f| {
+                        m.iter().any(|e| match &e.key {
+                            ScVal::Symbol(k) => {
+                                k.as_slice() == f.name.as_slice()
+                                    && self.value_matches(&f.type_, &e.val)
+                            }
+                            _ => false,
+                        })
+                    })
+            }
+            // Unions are encoded as a vector of the case name followed by the
+            // case's values, if any.
+            (ScSpecEntry::UdtUnionV0(u), ScVal::Vec(Some(v))) => {
+                let Some(ScVal::Symbol(case_name)) = v.first() else {
+                    return false;
+                };
+                u.cases.iter().any(|case| match case {
+                    ScSpecUdtUnionCaseV0::VoidV0(c) => {
+                        c.name.as_slice() == case_name.as_slice() && v.len() == 1
+                    }
+                    ScSpecUdtUnionCaseV0::TupleV0(c) => {
+                        c.name.as_slice() == case_name.as_slice()
+                            && c.type_.len() + 1 == v.len()
+                            && c.type_
+                                .iter()
+                                .zip(v.iter().skip(1))
+                                .all(|(t, e)| self.value_matches(t, e))
+                    }
+                })
+            }
+            (ScSpecEntry::UdtEnumV0(e), ScVal::U32(n)) => e.cases.iter().any(|c| c.value == *n),
+            (ScSpecEntry::UdtErrorEnumV0(e), ScVal::Error(ScError::Contract(n))) => {
+                e.cases.iter().any(|c| c.value == *n)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Host {
+    /// Checks the arguments of a call to `func` against the contract spec of
+    /// `vm` (instantiated from the contract code with `code_hash`), if
+    /// contract spec validation is enabled. A mismatch is reported as an
+    /// error naming the offending argument.
+    ///
+    /// The validation is optional, so it's metered by the shadow budget, and
+    /// it's skipped if the shadow budget is exceeded. The decoded spec is
+    /// cached along with the parsed module.
+    pub(crate) fn check_args_against_contract_spec(
+        &self,
+        vm: &Vm,
+        code_hash: &Hash,
+        func: &Symbol,
+        args: &[Val],
+    ) -> Result<(), HostError> {
+        if !*self.try_borrow_contract_spec_validation()? {
+            return Ok(());
+        }
+        let mut mismatch = None;
+        self.budget_ref().with_shadow_mode(|| {
+            let spec = self
+                .module_cache()?
+                .get_contract_spec(self, code_hash, vm)?;
+            let func_name: SymbolStr = func.try_into_val(self)?;
+            Vec::<ScVal>::charge_bulk_init_cpy(args.len() as u64, self)?;
+            let sc_args = args
+                .iter()
+                .map(|a| self.from_host_val(*a))
+                .collect::<Result<Vec<ScVal>, HostError>>()?;
+            mismatch = spec.check_args(func_name.as_ref(), &sc_args).err();
+            Ok(())
+        });
+        match mismatch {
+            None => Ok(()),
+            Some(SpecMismatch::ArgCount { expected, actual }) => Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::UnexpectedSize,
+                "number of arguments does not match contract spec",
+                &[
+                    func.to_val(),
+                    U32Val::from(expected as u32).to_val(),
+                    U32Val::from(actual as u32).to_val(),
+                ],
+            )),
+            Some(SpecMismatch::ArgType { index, name }) => {
+                let arg_name = Symbol::try_from_val(self, &name.as_str())?;
+                Err(self.err(
+                    ScErrorType::Value,
+                    ScErrorCode::UnexpectedType,
+                    "argument does not match type in contract spec",
+                    &[func.to_val(), arg_name.to_val(), args[index]],
+                ))
+            }
+        }
+    }
+}
@@ -4,10 +4,16 @@
 /// host functions.
 use std::{cmp::max, rc::Rc};
 
+#[cfg(any(test, feature = "recording_auth"))]
+use crate::auth_signing::recorded_auth_payload_to_entry;
 use crate::{
     budget::{AsBudget, Budget},
     events::Events,
-    fees::LedgerEntryRentChange,
+    fees::{
+        compute_rent_fee, compute_transaction_resource_fee_breakdown, FeeConfiguration,
+        LedgerEntryRentChange, RentFeeConfiguration, TransactionResourceFeeBreakdown,
+        TransactionResources,
+    },
     host::{
         crypto::sha256_hash_from_bytes,
         ledger_info_helper::get_key_durability,
@@ -16,10 +22,12 @@ use crate::{
     },
     storage::{AccessType, Footprint, FootprintMap, SnapshotSource, Storage, StorageMap},
     xdr::{
-        AccountId, ContractDataDurability, ContractEventType, DiagnosticEvent, HostFunction,
-        LedgerEntry, LedgerEntryData, LedgerFootprint, LedgerKey, LedgerKeyAccount,
-        LedgerKeyContractCode, LedgerKeyContractData, LedgerKeyTrustLine, ScErrorCode, ScErrorType,
-        SorobanAuthorizationEntry, SorobanResources, TtlEntry,
+        AccountId, ContractDataDurability, ContractEvent, ContractEventType, DiagnosticEvent,
+        ExtensionPoint, Hash, HostFunction, LedgerEntry, LedgerEntryChange as XdrLedgerEntryChange,
+        LedgerEntryChanges, LedgerEntryData, LedgerEntryExt, LedgerFootprint, LedgerKey,
+        LedgerKeyAccount, LedgerKeyContractCode, LedgerKeyContractData, LedgerKeyTrustLine,
+        LedgerKeyTtl, OperationMeta, ScErrorCode, ScErrorType, ScVal, SorobanAuthorizationEntry,
+        SorobanResources, SorobanTransactionMeta, TransactionMeta, TransactionMetaV3, TtlEntry,
     },
     DiagnosticLevel, Error, Host, HostError, LedgerInfo, MeteredOrdMap,
 };
@@ -83,7 +91,7 @@ pub struct LedgerEntryLiveUntilChange {
 /// Returns the difference between the `storage` and its initial snapshot as
 /// `LedgerEntryChanges`.
 /// Returns an entry for every item in `storage` footprint.
-pub fn get_ledger_changes<T: SnapshotSource>(
+pub fn get_ledger_changes<T: SnapshotSource + ?Sized>(
     budget: &Budget,
     storage: &Storage,
     init_storage_snapshot: &T,
@@ -200,6 +208,281 @@ pub fn extract_rent_changes(ledger_changes: &Vec<LedgerEntryChange>) -> Vec<Ledg
         .collect()
 }
 
+/// Safety margins applied when estimating the resource fee of a simulated
+/// invocation, as percentages added on top of the values it has consumed.
+///
+/// The enforcing invocation may consume more resources than the simulated one
+/// (e.g. because the ledger state changes in between), so the estimated
+/// resources might need some slack in order for the transaction to succeed.
+#[derive(Clone, Default)]
+pub struct ResourceFeeMargins {
+    /// Margin for the CPU instructions.
+    pub instructions_percent: u32,
+    /// Margin for the bytes read from and written to the ledger.
+    pub ledger_bytes_percent: u32,
+    /// Margin for the refundable fee (i.e. the events and rent fees).
+    pub refundable_fee_percent: u32,
+}
+
+/// Resource fee estimated for an invocation by `estimate_resource_fee`.
+pub struct ResourceFeeEstimate {
+    /// Resources derived from the invocation, with the margins applied.
+    pub resources: TransactionResources,
+    /// Fee for every resource in `resources`.
+    pub breakdown: TransactionResourceFeeBreakdown,
+    /// Rent fee for the TTL extensions and entry size increases.
+    pub rent_fee: i64,
+    /// Non-refundable part of the resource fee.
+    pub non_refundable_fee: i64,
+    /// Refundable part of the resource fee, i.e. the events fee and the rent
+    /// fee, with the margin applied.
+    pub refundable_fee: i64,
+}
+
+impl ResourceFeeEstimate {
+    /// Returns the total resource fee of the transaction.
+    pub fn resource_fee(&self) -> i64 {
+        self.non_refundable_fee.saturating_add(self.refundable_fee)
+    }
+}
+
+fn add_margin(value: u64, percent: u32) -> u64 {
+    value.saturating_add(value.saturating_mul(percent as u64) / 100)
+}
+
+/// Estimates the resource fee of a transaction from an invocation of its host
+/// function (either via `invoke_host_function`, or via
+/// `simulate_invoke_host_function` followed by
+/// `InvokeHostFunctionSimulationResult::into_invoke_host_function_result`).
+///
+/// The transaction resources are derived from the invocation: instructions
+/// are the CPU instructions consumed from `budget`, the entry counts come
+/// from `footprint`, the read bytes are the sizes of all the entries before
+/// the invocation, the write bytes are the sizes of the read-write entries
+/// after it, and the events size is the size of the contract events and of
+/// the return value. The rent fee is computed from the rent changes of
+/// `result.ledger_changes` (see `extract_rent_changes`).
+///
+/// `transaction_size_bytes` is the size of the transaction envelope, which
+/// the invocation can't derive.
+#[allow(clippy::too_many_arguments)]
+pub fn estimate_resource_fee(
+    budget: &Budget,
+    footprint: &LedgerFootprint,
+    result: &InvokeHostFunctionResult,
+    transaction_size_bytes: u32,
+    fee_config: &FeeConfiguration,
+    rent_fee_config: &RentFeeConfiguration,
+    current_ledger_seq: u32,
+    margins: &ResourceFeeMargins,
+) -> Result<ResourceFeeEstimate, HostError> {
+    let mut read_bytes: u64 = 0;
+    let mut write_bytes: u64 = 0;
+    for change in &result.ledger_changes {
+        read_bytes = read_bytes.saturating_add(change.old_entry_size_bytes as u64);
+        if let Some(new_value) = &change.encoded_new_value {
+            write_bytes = write_bytes.saturating_add(new_value.len() as u64);
+        }
+    }
+    let mut contract_events_size_bytes: u64 = result
+        .encoded_contract_events
+        .iter()
+        .map(|e| e.len() as u64)
+        .sum();
+    if let Ok(encoded_result) = &result.encoded_invoke_result {
+        contract_events_size_bytes =
+            contract_events_size_bytes.saturating_add(encoded_result.len() as u64);
+    }
+    let to_u32 = |v: u64| u32::try_from(v).unwrap_or(u32::MAX);
+    let resources = TransactionResources {
+        instructions: to_u32(add_margin(
+            budget.get_cpu_insns_consumed()?,
+            margins.instructions_percent,
+        )),
+        read_entries: to_u32(footprint.read_only.len() as u64),
+        write_entries: to_u32(footprint.read_write.len() as u64),
+        read_bytes: to_u32(add_margin(read_bytes, margins.ledger_bytes_percent)),
+        write_bytes: to_u32(add_margin(write_bytes, margins.ledger_bytes_percent)),
+        contract_events_size_bytes: to_u32(contract_events_size_bytes),
+        transaction_size_bytes,
+    };
+    let breakdown = compute_transaction_resource_fee_breakdown(&resources, fee_config);
+    let rent_fee = compute_rent_fee(
+        &extract_rent_changes(&result.ledger_changes),
+        rent_fee_config,
+        current_ledger_seq,
+    );
+    let refundable_fee = add_margin(
+        breakdown.refundable_fee().saturating_add(rent_fee).max(0) as u64,
+        margins.refundable_fee_percent,
+    );
+    Ok(ResourceFeeEstimate {
+        non_refundable_fee: breakdown.non_refundable_fee(),
+        refundable_fee: i64::try_from(refundable_fee).unwrap_or(i64::MAX),
+        resources,
+        breakdown,
+        rent_fee,
+    })
+}
+
+/// Builds the `TransactionMeta` (in the `V3` form) of a transaction with a
+/// single `InvokeHostFunctionOp` from the result of `invoke_host_function`
+/// (or of a converted simulation, see
+/// `InvokeHostFunctionSimulationResult::into_invoke_host_function_result`),
+/// the snapshot it ran against and the diagnostic events it produced.
+///
+/// The operation meta holds the `LedgerEntryChanges` of the invocation: a
+/// `Created` change for every new entry, a `State` change with the entry
+/// before the invocation followed by an `Updated` or a `Removed` change for
+/// every modified or removed entry, and the same for the respective TTL
+/// entries. Entries that were left unchanged are skipped. `Created` and
+/// `Updated` entries are stamped with `ledger_seq` as their last modified
+/// ledger; TTL entries before the invocation are stamped with the last
+/// modified ledger of the entry they belong to, as the snapshot doesn't
+/// track it separately.
+///
+/// The soroban meta holds the contract events, the return value and the
+/// diagnostic events. When the invocation has failed, there are no ledger
+/// changes nor contract events and the return value is `Void`.
+///
+/// The changes before and after the transaction (i.e. the fee and sequence
+/// number processing) are left empty.
+pub fn build_transaction_meta<T: SnapshotSource + ?Sized>(
+    budget: &Budget,
+    init_storage_snapshot: &T,
+    result: &InvokeHostFunctionResult,
+    diagnostic_events: &[DiagnosticEvent],
+    ledger_seq: u32,
+) -> Result<TransactionMeta, HostError> {
+    fn internal_error<E>(_: E) -> HostError {
+        Error::from_type_and_code(ScErrorType::Storage, ScErrorCode::InternalError).into()
+    }
+    let return_value = match &result.encoded_invoke_result {
+        Ok(encoded) => metered_from_xdr_with_budget::<ScVal>(encoded, budget)?,
+        Err(_) => ScVal::Void,
+    };
+    let events = result
+        .encoded_contract_events
+        .iter()
+        .map(|e| metered_from_xdr_with_budget::<ContractEvent>(e, budget))
+        .collect::<Result<Vec<_>, HostError>>()?;
+
+    let mut changes = vec![];
+    for change in &result.ledger_changes {
+        let key = Rc::new(metered_from_xdr_with_budget::<LedgerKey>(
+            &change.encoded_key,
+            budget,
+        )?);
+        let old = if init_storage_snapshot.has(&key)? {
+            Some(init_storage_snapshot.get(&key)?)
+        } else {
+            None
+        };
+        let new_entry = match &change.encoded_new_value {
+            Some(encoded) => {
+                let mut entry = metered_from_xdr_with_budget::<LedgerEntry>(encoded, budget)?;
+                entry.last_modified_ledger_seq = ledger_seq;
+                Some(entry)
+            }
+            None => None,
+        };
+        if !change.read_only {
+            match (&old, new_entry) {
+                (None, Some(new_entry)) => changes.push(XdrLedgerEntryChange::Created(new_entry)),
+                (Some((old_entry, _)), Some(new_entry)) => {
+                    if old_entry.data != new_entry.data {
+                        changes.push(XdrLedgerEntryChange::State(
+                            old_entry.as_ref().metered_clone(budget)?,
+                        ));
+                        changes.push(XdrLedgerEntryChange::Updated(new_entry));
+                    }
+                }
+                (Some((old_entry, _)), None) => {
+                    changes.push(XdrLedgerEntryChange::State(
+                        old_entry.as_ref().metered_clone(budget)?,
+                    ));
+                    changes.push(XdrLedgerEntryChange::Removed(
+                        key.as_ref().metered_clone(budget)?,
+                    ));
+                }
+                (None, None) => (),
+            }
+        }
+
+        let Some(ttl_change) = &change.ttl_change else {
+            continue;
+        };
+        let key_hash = Hash(
+            ttl_change
+                .key_hash
+                .as_slice()
+                .try_into()
+                .map_err(internal_error)?,
+        );
+        let ttl_entry = |live_until_ledger_seq, last_modified_ledger_seq| LedgerEntry {
+            last_modified_ledger_seq,
+            data: LedgerEntryData::Ttl(TtlEntry {
+                key_hash: key_hash.clone(),
+                live_until_ledger_seq,
+            }),
+            ext: LedgerEntryExt::V0,
+        };
+        let removed = !change.read_only && change.encoded_new_value.is_none();
+        match &old {
+            None if !removed => changes.push(XdrLedgerEntryChange::Created(ttl_entry(
+                ttl_change.new_live_until_ledger,
+                ledger_seq,
+            ))),
+            Some((old_entry, _)) => {
+                if removed || ttl_change.new_live_until_ledger > ttl_change.old_live_until_ledger {
+                    changes.push(XdrLedgerEntryChange::State(ttl_entry(
+                        ttl_change.old_live_until_ledger,
+                        old_entry.last_modified_ledger_seq,
+                    )));
+                }
+                if removed {
+                    changes.push(XdrLedgerEntryChange::Removed(LedgerKey::Ttl(
+                        LedgerKeyTtl {
+                            key_hash: key_hash.clone(),
+                        },
+                    )));
+                } else if ttl_change.new_live_until_ledger > ttl_change.old_live_until_ledger {
+                    changes.push(XdrLedgerEntryChange::Updated(ttl_entry(
+                        ttl_change.new_live_until_ledger,
+                        ledger_seq,
+                    )));
+                }
+            }
+            None => (),
+        }
+    }
+
+    let (changes, events) = if result.encoded_invoke_result.is_ok() {
+        (changes, events)
+    } else {
+        (vec![], vec![])
+    };
+    Ok(TransactionMeta::V3(TransactionMetaV3 {
+        ext: ExtensionPoint::V0,
+        tx_changes_before: Default::default(),
+        operations: vec![OperationMeta {
+            changes: LedgerEntryChanges(changes.try_into().map_err(internal_error)?),
+        }]
+        .try_into()
+        .map_err(internal_error)?,
+        tx_changes_after: Default::default(),
+        soroban_meta: Some(SorobanTransactionMeta {
+            ext: ExtensionPoint::V0,
+            events: events.try_into().map_err(internal_error)?,
+            return_value,
+            diagnostic_events: diagnostic_events
+                .to_vec()
+                .try_into()
+                .map_err(internal_error)?,
+        }),
+    }))
+}
+
 /// Invokes a host function within a fresh host instance.
 ///
 /// This collects the necessary inputs as encoded XDR and returns the outputs
@@ -307,6 +590,497 @@ pub fn invoke_host_function<T: AsRef<[u8]>, I: ExactSizeIterator<Item = T>>(
     }
 }
 
+/// Result of simulating a single host function invocation in the recording
+/// mode, prepared for embedder consumption (e.g. for transaction preflight).
+#[cfg(any(test, feature = "recording_auth"))]
+pub struct InvokeHostFunctionSimulationResult {
+    /// Result value of the function on success, or error.
+    pub invoke_result: Result<ScVal, HostError>,
+    /// Resources recorded during the invocation: the footprint of all the
+    /// accessed ledger entries, the instructions consumed and the number of
+    /// bytes read from and written to the ledger.
+    ///
+    /// Empty footprint and zero resources when invocation fails.
+    pub resources: SorobanResources,
+    /// Authorization entries required for the invocation to succeed in the
+    /// enforcing mode. Entries for the `Address` credentials carry the
+    /// recorded nonce and need to be signed by the respective addresses.
+    /// Doesn't include the already signed entries passed to the simulation.
+    ///
+    /// Empty when invocation fails.
+    pub auth: Vec<SorobanAuthorizationEntry>,
+    /// All the ledger changes caused by this invocation, including no-ops.
+    /// This contains an entry for every item in the recorded footprint.
+    ///
+    /// Empty when invocation fails.
+    pub ledger_changes: Vec<LedgerEntryChange>,
+    /// All the events that contracts emitted during invocation.
+    ///
+    /// Empty when invocation fails.
+    pub contract_events: Vec<ContractEvent>,
+}
+
+#[cfg(any(test, feature = "recording_auth"))]
+impl InvokeHostFunctionSimulationResult {
+    /// Converts the simulation result into the `InvokeHostFunctionResult`
+    /// that `invoke_host_function` would produce for the same invocation, by
+    /// encoding the return value and the contract events as XDR. This allows
+    /// passing the simulation result to `estimate_resource_fee` and
+    /// `build_transaction_meta` (along with `resources.footprint`, which has
+    /// to be taken out of the simulation result beforehand).
+    pub fn into_invoke_host_function_result(
+        self,
+        budget: &Budget,
+    ) -> Result<InvokeHostFunctionResult, HostError> {
+        let encoded_invoke_result = match self.invoke_result {
+            Ok(res) => {
+                let mut encoded_result_sc_val = vec![];
+                metered_write_xdr(budget, &res, &mut encoded_result_sc_val)?;
+                Ok(encoded_result_sc_val)
+            }
+            Err(e) => Err(e),
+        };
+        let encoded_contract_events = self
+            .contract_events
+            .iter()
+            .map(|e| {
+                let mut buf = vec![];
+                metered_write_xdr(budget, e, &mut buf)?;
+                Ok(buf)
+            })
+            .metered_collect::<Result<Vec<Vec<u8>>, HostError>>(budget)??;
+        Ok(InvokeHostFunctionResult {
+            encoded_invoke_result,
+            ledger_changes: self.ledger_changes,
+            encoded_contract_events,
+        })
+    }
+}
+
+/// Simulates a host function invocation within a fresh host instance using
+/// the recording footprint and the recording authorization modes.
+///
+/// This is the recording mode counterpart of `invoke_host_function`: instead
+/// of enforcing the provided footprint and authorization entries, it reads
+/// the ledger entries on demand from `snapshot_source` and records the
+/// footprint, authorization payloads and resources that an enforcing
+/// invocation would need.
+///
+/// Just like `invoke_host_function`, this may only fail when budget is
+/// exceeded or if there is an internal error. Host function invocation errors
+/// are stored within `Ok(InvokeHostFunctionSimulationResult)`.
+///
+/// `auth_entries` may contain the already signed authorization entries for
+/// some of the addresses. These are enforced just like in
+/// `invoke_host_function`, while the authorization requirements of all the
+/// other addresses are recorded. The resulting `auth` only contains the
+/// entries for the recorded addresses.
+///
+/// When diagnostics are enabled, we try to populate `diagnostic_events`
+/// even if the invocation fails for any reason.
+#[cfg(any(test, feature = "recording_auth"))]
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_invoke_host_function(
+    snapshot_source: Rc<dyn SnapshotSource>,
+    budget: &Budget,
+    enable_diagnostics: bool,
+    host_fn: HostFunction,
+    source_account: AccountId,
+    ledger_info: LedgerInfo,
+    base_prng_seed: [u8; 32],
+    disable_non_root_auth: bool,
+    auth_entries: Vec<SorobanAuthorizationEntry>,
+    diagnostic_events: &mut Vec<DiagnosticEvent>,
+) -> Result<InvokeHostFunctionSimulationResult, HostError> {
+    let _span0 = tracy_span!("simulate_invoke_host_function");
+
+    let storage = Storage::with_recording_footprint(Rc::clone(&snapshot_source));
+    let host = Host::with_storage_and_budget(storage, budget.clone());
+    host.set_source_account(source_account)?;
+    host.set_ledger_info(ledger_info)?;
+    host.switch_to_recording_auth_with_entries(disable_non_root_auth, auth_entries)?;
+    host.set_base_prng_seed(base_prng_seed)?;
+    if enable_diagnostics {
+        host.set_diagnostic_level(DiagnosticLevel::Debug)?;
+    }
+    let invoke_result = {
+        let _span1 = tracy_span!("Host::invoke_function");
+        host.invoke_function(host_fn)
+    };
+    let recorded_auth_payloads = if invoke_result.is_ok() {
+        host.get_recorded_auth_payloads()?
+    } else {
+        vec![]
+    };
+    let (storage, events) = host.try_finish()?;
+    if enable_diagnostics {
+        extract_diagnostic_events(&events, diagnostic_events);
+    }
+    if invoke_result.is_err() {
+        return Ok(InvokeHostFunctionSimulationResult {
+            invoke_result,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: Default::default(),
+                    read_write: Default::default(),
+                },
+                instructions: 0,
+                read_bytes: 0,
+                write_bytes: 0,
+            },
+            auth: vec![],
+            ledger_changes: vec![],
+            contract_events: vec![],
+        });
+    }
+
+    let ledger_changes =
+        get_ledger_changes(budget, &storage, &*snapshot_source, TtlEntryMap::new())?;
+    let resources = build_recorded_resources(budget, &storage, &ledger_changes)?;
+    let auth = recorded_auth_payloads
+        .into_iter()
+        .map(recorded_auth_payload_to_entry)
+        .metered_collect::<Result<Vec<SorobanAuthorizationEntry>, HostError>>(budget)??;
+    let contract_events = events
+        .0
+        .into_iter()
+        .filter(|e| !e.failed_call && e.event.type_ != ContractEventType::Diagnostic)
+        .map(|e| e.event)
+        .metered_collect::<Vec<ContractEvent>>(budget)?;
+    Ok(InvokeHostFunctionSimulationResult {
+        invoke_result,
+        resources,
+        auth,
+        ledger_changes,
+        contract_events,
+    })
+}
+
+// Builds the resources required for an enforcing invocation from the
+// footprint recorded in `storage` and the ledger changes it has caused.
+#[cfg(any(test, feature = "recording_auth"))]
+fn build_recorded_resources(
+    budget: &Budget,
+    storage: &Storage,
+    ledger_changes: &[LedgerEntryChange],
+) -> Result<SorobanResources, HostError> {
+    let mut read_only = vec![];
+    let mut read_write = vec![];
+    for (key, access_type) in storage.footprint.0.iter(budget)? {
+        let key = key.as_ref().metered_clone(budget)?;
+        match access_type {
+            AccessType::ReadOnly => read_only.push(key),
+            AccessType::ReadWrite => read_write.push(key),
+        }
+    }
+    let mut read_bytes: u32 = 0;
+    let mut write_bytes: u32 = 0;
+    for change in ledger_changes {
+        read_bytes = read_bytes.saturating_add(change.old_entry_size_bytes);
+        if let Some(new_value) = &change.encoded_new_value {
+            write_bytes = write_bytes.saturating_add(new_value.len() as u32);
+        }
+    }
+    let internal_error = |_| {
+        HostError::from(Error::from_type_and_code(
+            ScErrorType::Storage,
+            ScErrorCode::InternalError,
+        ))
+    };
+    Ok(SorobanResources {
+        footprint: LedgerFootprint {
+            read_only: read_only.try_into().map_err(internal_error)?,
+            read_write: read_write.try_into().map_err(internal_error)?,
+        },
+        instructions: u32::try_from(budget.get_cpu_insns_consumed()?).unwrap_or(u32::MAX),
+        read_bytes,
+        write_bytes,
+    })
+}
+
+/// Result of applying an `ExtendFootprintTtlOp` or a `RestoreFootprintOp`,
+/// prepared for embedder consumption.
+pub struct FootprintTtlOpResult {
+    /// The changes of the entries whose TTL has been extended or that have
+    /// been restored. Unlike for `invoke_host_function`, entries that have
+    /// been left unchanged are skipped.
+    pub ledger_changes: Vec<LedgerEntryChange>,
+    /// The rent-related changes of the same entries, to compute the rent fee
+    /// with `fees::compute_rent_fee`.
+    pub rent_changes: Vec<LedgerEntryRentChange>,
+}
+
+// A footprint entry passed to a TTL operation, along with its TTL entry.
+struct EntryWithTtl {
+    key: Rc<LedgerKey>,
+    encoded_entry: Vec<u8>,
+    ttl: TtlEntry,
+}
+
+fn malformed_footprint_op() -> HostError {
+    Error::from_type_and_code(ScErrorType::Storage, ScErrorCode::InvalidInput).into()
+}
+
+// Builds the footprint of a TTL operation, rejecting duplicate keys.
+fn build_footprint_op_footprint(
+    budget: &Budget,
+    keys: &[LedgerKey],
+    access_type: AccessType,
+) -> Result<FootprintMap, HostError> {
+    let mut footprint_map = FootprintMap::new();
+    for key in keys {
+        let key = Rc::metered_new(key.metered_clone(budget)?, budget)?;
+        if footprint_map.contains_key::<Rc<LedgerKey>>(&key, budget)? {
+            return Err(malformed_footprint_op());
+        }
+        footprint_map = footprint_map.insert(key, access_type, budget)?;
+    }
+    Ok(footprint_map)
+}
+
+// Decodes the ledger entries passed to a TTL operation and matches them
+// with their TTL entries. Every entry must be in the footprint (at most once)
+// and must have a TTL entry, as only contract data and code entries can be
+// passed to these operations. Entries that don't exist are simply not passed.
+fn decode_entries_with_ttl<T: AsRef<[u8]>, I: ExactSizeIterator<Item = T>>(
+    budget: &Budget,
+    mut footprint_map: FootprintMap,
+    encoded_ledger_entries: I,
+    encoded_ttl_entries: I,
+) -> Result<Vec<EntryWithTtl>, HostError> {
+    if encoded_ledger_entries.len() != encoded_ttl_entries.len() {
+        return Err(malformed_footprint_op());
+    }
+    // Skip allocation metering for the vector for the sake of simplicity -
+    // the bounding factor here is XDR decoding which is metered.
+    let mut entries = Vec::with_capacity(encoded_ledger_entries.len());
+    for (entry_buf, ttl_buf) in encoded_ledger_entries.zip(encoded_ttl_entries) {
+        let entry = metered_from_xdr_with_budget::<LedgerEntry>(entry_buf.as_ref(), budget)?;
+        let key = Rc::metered_new(ledger_entry_to_ledger_key(&entry, budget)?, budget)?;
+        // Matched keys are removed from the footprint, so that an entry can't
+        // be passed twice.
+        footprint_map = match footprint_map.remove::<Rc<LedgerKey>>(&key, budget)? {
+            Some((footprint_map, _)) => footprint_map,
+            None => return Err(malformed_footprint_op()),
+        };
+        let ttl = metered_from_xdr_with_budget::<TtlEntry>(ttl_buf.as_ref(), budget)?;
+        let mut key_buf = vec![];
+        metered_write_xdr(budget, key.as_ref(), &mut key_buf)?;
+        if ttl.key_hash.0.as_slice() != sha256_hash_from_bytes(&key_buf, budget)?.as_slice() {
+            return Err(malformed_footprint_op());
+        }
+        Vec::<u8>::charge_bulk_init_cpy(entry_buf.as_ref().len() as u64, budget)?;
+        entries.push(EntryWithTtl {
+            key,
+            encoded_entry: entry_buf.as_ref().to_vec(),
+            ttl,
+        });
+    }
+    Ok(entries)
+}
+
+// Adds the size of an entry to a number of bytes read or written by a TTL
+// operation, failing when it exceeds the declared resources.
+fn add_footprint_op_bytes(bytes: &mut u32, entry_size: u32, limit: u32) -> Result<(), HostError> {
+    *bytes = bytes.saturating_add(entry_size);
+    if *bytes > limit {
+        return Err(
+            Error::from_type_and_code(ScErrorType::Budget, ScErrorCode::ExceededLimit).into(),
+        );
+    }
+    Ok(())
+}
+
+/// Applies an `ExtendFootprintTtlOp`: extends the TTL of every live entry in
+/// the read-only footprint of `encoded_resources` to `extend_to` ledgers
+/// after the current one.
+///
+/// `encoded_ledger_entries` and `encoded_ttl_entries` hold the footprint
+/// entries that exist (in any order) as `LedgerEntry` and `TtlEntry` XDR,
+/// including the archived ones, which are skipped.
+///
+/// Fails when the operation is malformed (the read-write footprint is not
+/// empty, the footprint has non-contract entries or duplicate keys, or
+/// `extend_to` exceeds the network's maximum entry TTL), or when the entries
+/// that are extended exceed the declared `read_bytes`.
+pub fn extend_footprint_ttl<T: AsRef<[u8]>, I: ExactSizeIterator<Item = T>>(
+    budget: &Budget,
+    encoded_resources: T,
+    extend_to: u32,
+    ledger_info: &LedgerInfo,
+    encoded_ledger_entries: I,
+    encoded_ttl_entries: I,
+) -> Result<FootprintTtlOpResult, HostError> {
+    let resources: SorobanResources =
+        metered_from_xdr_with_budget(encoded_resources.as_ref(), budget)?;
+    let footprint = &resources.footprint;
+    if !footprint.read_write.is_empty()
+        || footprint
+            .read_only
+            .iter()
+            .any(|key| get_key_durability(key).is_none())
+        || extend_to > ledger_info.max_entry_ttl.saturating_sub(1)
+    {
+        return Err(malformed_footprint_op());
+    }
+    let new_live_until_ledger = ledger_info
+        .sequence_number
+        .checked_add(extend_to)
+        .ok_or_else(malformed_footprint_op)?;
+
+    let footprint_map =
+        build_footprint_op_footprint(budget, footprint.read_only.as_slice(), AccessType::ReadOnly)?;
+    let entries = decode_entries_with_ttl(
+        budget,
+        footprint_map,
+        encoded_ledger_entries,
+        encoded_ttl_entries,
+    )?;
+    let mut result = FootprintTtlOpResult {
+        ledger_changes: vec![],
+        rent_changes: vec![],
+    };
+    let mut read_bytes: u32 = 0;
+    for EntryWithTtl {
+        key,
+        encoded_entry,
+        ttl,
+    } in entries
+    {
+        let old_live_until_ledger = ttl.live_until_ledger_seq;
+        // Archived entries can't be extended and have to be restored first.
+        // Only the entries that are actually extended are read.
+        if old_live_until_ledger < ledger_info.sequence_number
+            || old_live_until_ledger >= new_live_until_ledger
+        {
+            continue;
+        }
+        let entry_size = encoded_entry.len() as u32;
+        add_footprint_op_bytes(&mut read_bytes, entry_size, resources.read_bytes)?;
+        let durability = get_key_durability(&key).ok_or_else(malformed_footprint_op)?;
+        let mut encoded_key = vec![];
+        metered_write_xdr(budget, key.as_ref(), &mut encoded_key)?;
+        result.ledger_changes.push(LedgerEntryChange {
+            read_only: true,
+            encoded_key,
+            old_entry_size_bytes: entry_size,
+            encoded_new_value: None,
+            ttl_change: Some(LedgerEntryLiveUntilChange {
+                key_hash: ttl.key_hash.0.to_vec(),
+                durability,
+                old_live_until_ledger,
+                new_live_until_ledger,
+            }),
+        });
+        result.rent_changes.push(LedgerEntryRentChange {
+            is_persistent: durability == ContractDataDurability::Persistent,
+            old_size_bytes: entry_size,
+            new_size_bytes: entry_size,
+            old_live_until_ledger,
+            new_live_until_ledger,
+        });
+    }
+    Ok(result)
+}
+
+/// Applies a `RestoreFootprintOp`: restores every archived entry in the
+/// read-write footprint of `encoded_resources`, making it live for the
+/// minimum persistent entry TTL of the network.
+///
+/// `encoded_ledger_entries` and `encoded_ttl_entries` hold the footprint
+/// entries that exist (in any order) as `LedgerEntry` and `TtlEntry` XDR,
+/// including the live ones, which are skipped.
+///
+/// Fails when the operation is malformed (the read-only footprint is not
+/// empty, or the footprint has duplicate keys or entries other than
+/// persistent contract data and contract code), or when the restored entries exceed the declared
+/// `read_bytes` or `write_bytes`.
+pub fn restore_footprint<T: AsRef<[u8]>, I: ExactSizeIterator<Item = T>>(
+    budget: &Budget,
+    encoded_resources: T,
+    ledger_info: &LedgerInfo,
+    encoded_ledger_entries: I,
+    encoded_ttl_entries: I,
+) -> Result<FootprintTtlOpResult, HostError> {
+    let resources: SorobanResources =
+        metered_from_xdr_with_budget(encoded_resources.as_ref(), budget)?;
+    let footprint = &resources.footprint;
+    if !footprint.read_only.is_empty()
+        || footprint
+            .read_write
+            .iter()
+            .any(|key| get_key_durability(key) != Some(ContractDataDurability::Persistent))
+    {
+        return Err(malformed_footprint_op());
+    }
+    let restored_live_until_ledger = ledger_info
+        .sequence_number
+        .checked_add(ledger_info.min_persistent_entry_ttl.saturating_sub(1))
+        .ok_or_else(|| {
+            HostError::from(Error::from_type_and_code(
+                ScErrorType::Context,
+                ScErrorCode::InternalError,
+            ))
+        })?;
+
+    let footprint_map = build_footprint_op_footprint(
+        budget,
+        footprint.read_write.as_slice(),
+        AccessType::ReadWrite,
+    )?;
+    let entries = decode_entries_with_ttl(
+        budget,
+        footprint_map,
+        encoded_ledger_entries,
+        encoded_ttl_entries,
+    )?;
+    let mut result = FootprintTtlOpResult {
+        ledger_changes: vec![],
+        rent_changes: vec![],
+    };
+    let mut read_bytes: u32 = 0;
+    let mut write_bytes: u32 = 0;
+    for EntryWithTtl {
+        key,
+        encoded_entry,
+        ttl,
+    } in entries
+    {
+        let old_live_until_ledger = ttl.live_until_ledger_seq;
+        // Live entries don't need to be restored.
+        if old_live_until_ledger >= ledger_info.sequence_number {
+            continue;
+        }
+        let entry_size = encoded_entry.len() as u32;
+        add_footprint_op_bytes(&mut read_bytes, entry_size, resources.read_bytes)?;
+        add_footprint_op_bytes(&mut write_bytes, entry_size, resources.write_bytes)?;
+        let mut encoded_key = vec![];
+        metered_write_xdr(budget, key.as_ref(), &mut encoded_key)?;
+        result.ledger_changes.push(LedgerEntryChange {
+            read_only: false,
+            encoded_key,
+            old_entry_size_bytes: entry_size,
+            encoded_new_value: Some(encoded_entry),
+            ttl_change: Some(LedgerEntryLiveUntilChange {
+                key_hash: ttl.key_hash.0.to_vec(),
+                durability: ContractDataDurability::Persistent,
+                old_live_until_ledger,
+                new_live_until_ledger: restored_live_until_ledger,
+            }),
+        });
+        // For the rent purposes the restored entry is treated as if it didn't
+        // exist before.
+        result.rent_changes.push(LedgerEntryRentChange {
+            is_persistent: true,
+            old_size_bytes: 0,
+            new_size_bytes: entry_size,
+            old_live_until_ledger: 0,
+            new_live_until_ledger: restored_live_until_ledger,
+        });
+    }
+    Ok(result)
+}
+
 /// Encodes host events as `ContractEvent` XDR.
 pub fn encode_contract_events(budget: &Budget, events: &Events) -> Result<Vec<Vec<u8>>, HostError> {
     let ce = events
@@ -336,7 +1110,10 @@ fn extract_diagnostic_events(events: &Events, diagnostic_events: &mut Vec<Diagno
     }
 }
 
-fn ledger_entry_to_ledger_key(le: &LedgerEntry, budget: &Budget) -> Result<LedgerKey, HostError> {
+pub(crate) fn ledger_entry_to_ledger_key(
+    le: &LedgerEntry,
+    budget: &Budget,
+) -> Result<LedgerKey, HostError> {
     match &le.data {
         LedgerEntryData::Account(a) => Ok(LedgerKey::Account(LedgerKeyAccount {
             account_id: a.account_id.metered_clone(budget)?,
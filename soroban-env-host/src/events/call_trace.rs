@@ -0,0 +1,334 @@
+//! Reconstruction of the contract call tree from the diagnostic events.
+//!
+//! When the diagnostics are enabled (at least at [`DiagnosticLevel::Debug`]),
+//! the host records a `fn_call` diagnostic event before every contract call
+//! and a `fn_return` diagnostic event after every successful contract call.
+//! These events, together with all the other events, are externalized as a
+//! flat chronological list ([`Events`]). [`Events::call_trace`] turns that
+//! list back into a tree of the contract calls.
+//!
+//! The failed calls don't have a `fn_return` event, so a call frame is
+//! considered failed when it is closed by an event that could only have been
+//! recorded by one of its ancestors: the `fn_return` event of an ancestor, or
+//! any event recorded by a contract of an ancestor that is different from the
+//! frame's contract (such as the `fn_call` event of the next call or the
+//! error event of the failed `try_call`). The frames that are still open at
+//! the end of the event list are failed as well.
+//!
+//! [`DiagnosticLevel::Debug`]: crate::DiagnosticLevel::Debug
+
+use crate::{
+    xdr::{ContractEventBody, ContractEventType, Hash, ScBytes, ScSymbol, ScVal},
+    Error,
+};
+
+use super::{display_scval, Events, HostEvent};
+
+/// The outcome of a contract call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CallOutcome {
+    /// The call has returned the value.
+    Returned(ScVal),
+    /// The call has failed. Contains the last error recorded in the frame of
+    /// the call (if any).
+    Failed(Option<Error>),
+}
+
+/// A single entry in the call trace: either an event recorded directly in
+/// the frame, or a nested call.
+#[derive(Clone, Debug)]
+pub enum CallTraceEntry {
+    Event(HostEvent),
+    Call(CallFrame),
+}
+
+/// A single contract call reconstructed from the diagnostic events.
+#[derive(Clone, Debug)]
+pub struct CallFrame {
+    /// The contract that has made the call, `None` for the calls made
+    /// directly by the host (e.g. the root invocation).
+    pub caller: Option<Hash>,
+    /// The called contract.
+    pub contract: Hash,
+    pub function: ScSymbol,
+    /// The call arguments. Note, that the diagnostic events don't preserve
+    /// the argument count, so the single vector argument can't be
+    /// distinguished from multiple arguments and is returned as the latter.
+    pub args: Vec<ScVal>,
+    pub outcome: CallOutcome,
+    /// Whether the effects of this call have been rolled back, either due to
+    /// its own failure, or due to the failure of any of its callers.
+    pub rolled_back: bool,
+    /// The events and nested calls in the chronological order.
+    pub entries: Vec<CallTraceEntry>,
+}
+
+/// The contract call tree, along with the events recorded outside of any
+/// contract call.
+#[derive(Clone, Debug, Default)]
+pub struct CallTrace {
+    pub entries: Vec<CallTraceEntry>,
+}
+
+impl CallFrame {
+    /// Returns the error of the last `error` diagnostic event recorded
+    /// directly in this frame.
+    fn last_error(&self) -> Option<Error> {
+        self.entries.iter().rev().find_map(|entry| match entry {
+            CallTraceEntry::Event(event) => match event_topics(event) {
+                Some([ScVal::Symbol(topic), ScVal::Error(err), ..])
+                    if topic.0.as_slice() == b"error" =>
+                {
+                    Some(Error::from_scerror(err.clone()))
+                }
+                _ => None,
+            },
+            CallTraceEntry::Call(_) => None,
+        })
+    }
+}
+
+fn event_topics(event: &HostEvent) -> Option<&[ScVal]> {
+    let ContractEventBody::V0(body) = &event.event.body;
+    if event.event.type_ == ContractEventType::Diagnostic {
+        Some(body.topics.as_slice())
+    } else {
+        None
+    }
+}
+
+fn event_data(event: &HostEvent) -> &ScVal {
+    let ContractEventBody::V0(body) = &event.event.body;
+    &body.data
+}
+
+fn contract_id_from_scval(val: &ScVal) -> Option<Hash> {
+    match val {
+        ScVal::Bytes(ScBytes(bytes)) => Some(Hash(bytes.as_slice().try_into().ok()?)),
+        _ => None,
+    }
+}
+
+fn args_from_scval(val: &ScVal) -> Vec<ScVal> {
+    match val {
+        ScVal::Void => vec![],
+        ScVal::Vec(Some(args)) => args.0.to_vec(),
+        arg => vec![arg.clone()],
+    }
+}
+
+/// The call frames that haven't been closed yet, from the outermost to the
+/// innermost one.
+struct OpenFrames {
+    top_level: Vec<CallTraceEntry>,
+    stack: Vec<CallFrame>,
+}
+
+impl OpenFrames {
+    fn entries(&mut self) -> &mut Vec<CallTraceEntry> {
+        match self.stack.last_mut() {
+            Some(frame) => &mut frame.entries,
+            None => &mut self.top_level,
+        }
+    }
+
+    // Closes the innermost frame as failed.
+    fn fail_innermost(&mut self) {
+        if let Some(mut frame) = self.stack.pop() {
+            frame.outcome = CallOutcome::Failed(frame.last_error());
+            frame.rolled_back = true;
+            self.entries().push(CallTraceEntry::Call(frame));
+        }
+    }
+
+    // Closes as failed all the frames above the innermost frame of
+    // `contract`, if there is any.
+    fn fail_frames_above(&mut self, contract: &Hash) {
+        if let Some(pos) = self
+            .stack
+            .iter()
+            .rposition(|frame| &frame.contract == contract)
+        {
+            while self.stack.len() > pos + 1 {
+                self.fail_innermost();
+            }
+        }
+    }
+
+    fn on_call(&mut self, event: &HostEvent, contract: Hash, function: &ScVal) -> bool {
+        let ScVal::Symbol(function) = function else {
+            return false;
+        };
+        let caller = event.event.contract_id.clone();
+        // Only the innermost frame can make calls, so all the frames that
+        // don't belong to the caller must have failed.
+        match &caller {
+            Some(caller) => self.fail_frames_above(caller),
+            None => {
+                while !self.stack.is_empty() {
+                    self.fail_innermost();
+                }
+            }
+        }
+        self.stack.push(CallFrame {
+            caller,
+            contract,
+            function: function.clone(),
+            args: args_from_scval(event_data(event)),
+            outcome: CallOutcome::Failed(None),
+            rolled_back: event.failed_call,
+            entries: vec![],
+        });
+        true
+    }
+
+    fn on_return(&mut self, event: &HostEvent, function: &ScVal) -> bool {
+        let Some(contract) = &event.event.contract_id else {
+            return false;
+        };
+        let Some(pos) = self.stack.iter().rposition(|frame| {
+            &frame.contract == contract
+                && matches!(function, ScVal::Symbol(f) if f == &frame.function)
+        }) else {
+            return false;
+        };
+        while self.stack.len() > pos + 1 {
+            self.fail_innermost();
+        }
+        if let Some(mut frame) = self.stack.pop() {
+            frame.outcome = CallOutcome::Returned(event_data(event).clone());
+            frame.rolled_back = event.failed_call;
+            self.entries().push(CallTraceEntry::Call(frame));
+        }
+        true
+    }
+
+    fn on_event(&mut self, event: &HostEvent) {
+        let handled = match event_topics(event) {
+            Some([ScVal::Symbol(topic), callee, function]) if topic.0.as_slice() == b"fn_call" => {
+                contract_id_from_scval(callee)
+                    .map(|contract| self.on_call(event, contract, function))
+                    .unwrap_or(false)
+            }
+            Some([ScVal::Symbol(topic), function]) if topic.0.as_slice() == b"fn_return" => {
+                self.on_return(event, function)
+            }
+            _ => false,
+        };
+        if !handled {
+            if let Some(contract) = &event.event.contract_id {
+                self.fail_frames_above(contract);
+            }
+            self.entries().push(CallTraceEntry::Event(event.clone()));
+        }
+    }
+
+    fn finish(mut self) -> CallTrace {
+        while !self.stack.is_empty() {
+            self.fail_innermost();
+        }
+        CallTrace {
+            entries: self.top_level,
+        }
+    }
+}
+
+impl Events {
+    /// Reconstructs the contract call tree from the `fn_call` and `fn_return`
+    /// diagnostic events. The failed calls don't have a `fn_return` event, so
+    /// a call is considered failed when its frame is closed by an event of
+    /// one of its callers, or when it's still open at the end of the events.
+    /// Note, that the events recorded without a contract id (e.g. by the host
+    /// outside of the contract execution) are attributed to the innermost
+    /// open frame.
+    /// The malformed `fn_call`/`fn_return` events and the `fn_return` events
+    /// that don't match any open call are kept as regular events.
+    pub fn call_trace(&self) -> CallTrace {
+        let mut frames = OpenFrames {
+            top_level: vec![],
+            stack: vec![],
+        };
+        for event in self.0.iter() {
+            frames.on_event(event);
+        }
+        frames.finish()
+    }
+}
+
+struct Indent(usize);
+
+impl core::fmt::Display for Indent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:width$}", "", width = self.0 * 2)
+    }
+}
+
+fn display_entries(
+    entries: &[CallTraceEntry],
+    depth: usize,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    for entry in entries {
+        match entry {
+            CallTraceEntry::Event(event) => writeln!(f, "{}{}", Indent(depth), event)?,
+            CallTraceEntry::Call(frame) => display_frame(frame, depth, f)?,
+        }
+    }
+    Ok(())
+}
+
+fn display_frame(
+    frame: &CallFrame,
+    depth: usize,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    write!(
+        f,
+        "{}call {}:{}(",
+        Indent(depth),
+        frame.contract,
+        frame.function.0
+    )?;
+    for (i, arg) in frame.args.iter().enumerate() {
+        if i != 0 {
+            write!(f, ", ")?;
+        }
+        display_scval(arg, f)?;
+    }
+    writeln!(f, ")")?;
+    display_entries(&frame.entries, depth + 1, f)?;
+    write!(f, "{}", Indent(depth))?;
+    match &frame.outcome {
+        CallOutcome::Returned(val) => {
+            write!(f, "return ")?;
+            display_scval(val, f)?;
+        }
+        CallOutcome::Failed(Some(err)) => write!(f, "fail {:?}", err)?,
+        CallOutcome::Failed(None) => write!(f, "fail")?,
+    }
+    if frame.rolled_back {
+        write!(f, " (rolled back)")?;
+    }
+    writeln!(f)
+}
+
+/// Renders the call tree with every nested call indented by two spaces, e.g.
+///
+/// ```text
+/// call <contract>:transfer(<from>, <to>, 10)
+///   call <token>:transfer(<from>, <to>, 10)
+///     [Contract Event] contract:<token>, topics:[transfer, ...], data:10
+///   return Void
+/// return Void
+/// ```
+impl core::fmt::Display for CallTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_entries(&self.entries, 0, f)
+    }
+}
+
+impl core::fmt::Display for CallFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_frame(self, 0, f)
+    }
+}
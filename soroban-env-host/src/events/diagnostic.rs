@@ -6,15 +6,37 @@ use crate::{
         InternalEvent, InternalEventsBuffer,
     },
     host::metered_clone::{MeteredAlloc, MeteredClone, MeteredContainer, MeteredIterator},
-    xdr::{Hash, ScBytes, ScString, ScVal, StringM},
-    Error, Host, HostError, Symbol, SymbolSmall, Val,
+    xdr::{Hash, ScBytes, ScString, ScSymbol, ScVal, StringM},
+    AddressObject, Error, Host, HostError, Symbol, SymbolSmall, Val,
 };
 
-#[derive(Clone, Default)]
+/// The verbosity of the diagnostic events recorded by the host. Every level
+/// records the events of all the lower levels as well.
+///
+/// Diagnostic work never affects the observable execution: at every level
+/// above `None` it is performed in the shadow mode of the budget, i.e. it
+/// is charged against the shadow limits instead of the invocation budget,
+/// and a shadow budget overrun silently stops the diagnostic work instead of
+/// failing the invocation. At `None` no diagnostic work is done at all.
+/// The higher levels only differ in how much shadow budget they consume.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DiagnosticLevel {
     #[default]
     None,
+    /// Only the error events (with `error` topic), which includes the
+    /// authorization failure explanations (with `auth_failure` topic). This
+    /// is cheap enough to be always enabled in production.
+    Errors,
+    /// Errors and the `log` events emitted by the contracts and the host.
+    Logs,
+    /// Errors, logs and the `fn_call`/`fn_return` events that trace the
+    /// contract calls.
     Debug,
+    /// Everything above and the `host_fn_call`/`host_fn_return` events for
+    /// every host function called by the Wasm contracts, with all the
+    /// arguments and the result. This is very expensive and is only meant
+    /// for local debugging.
+    HostFnTrace,
 }
 
 impl Host {
@@ -24,7 +46,7 @@ impl Host {
         topics: Vec<InternalDiagnosticArg>,
         args: Vec<InternalDiagnosticArg>,
     ) -> Result<(), HostError> {
-        self.with_debug_mode(|| {
+        self.with_diagnostic_level(DiagnosticLevel::Errors, || {
             let de = Rc::metered_new(
                 InternalDiagnosticEvent {
                     contract_id,
@@ -39,7 +61,7 @@ impl Host {
     }
 
     pub(crate) fn log_diagnostics(&self, msg: &str, args: &[Val]) {
-        self.with_debug_mode(|| {
+        self.with_diagnostic_level(DiagnosticLevel::Logs, || {
             let calling_contract = self.get_current_contract_id_opt_internal()?;
             let log_sym = SymbolSmall::try_from_str("log")?;
             Vec::<InternalDiagnosticArg>::charge_bulk_init_cpy(1, self)?;
@@ -61,7 +83,7 @@ impl Host {
         msg: &str,
         args: &[Val],
     ) {
-        self.with_debug_mode(|| {
+        self.with_diagnostic_level(DiagnosticLevel::Errors, || {
             let error_sym = SymbolSmall::try_from_str("error")?;
             let contract_id = self.get_current_contract_id_opt_internal()?;
             Vec::<InternalDiagnosticArg>::charge_bulk_init_cpy(2, self)?;
@@ -134,4 +156,80 @@ impl Host {
             self.record_diagnostic_event(Some(contract_id.metered_clone(self)?), topics, args)
         })
     }
+
+    // Emits an event with topic = ["auth_failure", address] and data =
+    // [function, tracker_1, tracker_2, ...], where every tracker entry
+    // describes an invocation tree authorized by the address, along with the
+    // tree nodes that didn't match the function requiring authorization.
+    pub(crate) fn auth_failure_diagnostics(
+        &self,
+        address: AddressObject,
+        function: ScVal,
+        trackers: Vec<ScVal>,
+    ) {
+        self.with_diagnostic_level(DiagnosticLevel::Errors, || {
+            let contract_id = self.get_current_contract_id_opt_internal()?;
+            Vec::<InternalDiagnosticArg>::charge_bulk_init_cpy(2, self)?;
+            let topics = vec![
+                InternalDiagnosticArg::XdrVal(ScVal::Symbol(ScSymbol("auth_failure".try_into()?))),
+                InternalDiagnosticArg::HostVal(address.to_val()),
+            ];
+            let args = std::iter::once(function)
+                .chain(trackers)
+                .map(InternalDiagnosticArg::XdrVal)
+                .metered_collect(self)?;
+            self.record_diagnostic_event(contract_id, topics, args)
+        })
+    }
+
+    // Converts a host-side string to an `ScVal::String` diagnostic argument.
+    fn diagnostic_string_arg(&self, s: &str) -> Result<InternalDiagnosticArg, HostError> {
+        Ok(InternalDiagnosticArg::XdrVal(ScVal::String(
+            ScString::from(StringM::try_from(self.metered_slice_to_vec(s.as_bytes())?)?),
+        )))
+    }
+
+    // Emits an event with topic = ["host_fn_call", function_name] and data =
+    // [arg1, arg2, ...], where the args are formatted with `Debug` (like in
+    // `env_call_hook`). The function name is a string rather than a symbol,
+    // as some host function names are longer than `SCSYMBOL_LIMIT`. Only
+    // recorded at `DiagnosticLevel::HostFnTrace`: the caller must call this
+    // (and do the formatting) within
+    // `with_diagnostic_level(DiagnosticLevel::HostFnTrace, ...)`.
+    pub(crate) fn host_fn_call_diagnostics(
+        &self,
+        func: &str,
+        args: &[String],
+    ) -> Result<(), HostError> {
+        let calling_contract = self.get_current_contract_id_opt_internal()?;
+        Vec::<InternalDiagnosticArg>::charge_bulk_init_cpy(2, self)?;
+        let topics = vec![
+            InternalDiagnosticArg::XdrVal(ScVal::Symbol(ScSymbol("host_fn_call".try_into()?))),
+            self.diagnostic_string_arg(func)?,
+        ];
+        let args = args
+            .iter()
+            .map(|arg| self.diagnostic_string_arg(arg))
+            .metered_collect::<Result<Vec<_>, HostError>>(self)??;
+        self.record_diagnostic_event(calling_contract, topics, args)
+    }
+
+    // Emits an event with topic = ["host_fn_return", function_name] and data =
+    // [result], where the result is formatted with `Debug` (like in
+    // `env_ret_hook`). Like `host_fn_call_diagnostics`, must be called within
+    // `with_diagnostic_level(DiagnosticLevel::HostFnTrace, ...)`.
+    pub(crate) fn host_fn_return_diagnostics(
+        &self,
+        func: &str,
+        res: &str,
+    ) -> Result<(), HostError> {
+        let calling_contract = self.get_current_contract_id_opt_internal()?;
+        Vec::<InternalDiagnosticArg>::charge_bulk_init_cpy(3, self)?;
+        let topics = vec![
+            InternalDiagnosticArg::XdrVal(ScVal::Symbol(ScSymbol("host_fn_return".try_into()?))),
+            self.diagnostic_string_arg(func)?,
+        ];
+        let args = vec![self.diagnostic_string_arg(res)?];
+        self.record_diagnostic_event(calling_contract, topics, args)
+    }
 }
@@ -3,9 +3,12 @@ use std::rc::Rc;
 use crate::{
     budget::AsBudget,
     events::{Events, HostEvent},
-    host::metered_clone::{MeteredClone, MeteredContainer, MeteredIterator},
-    xdr::{self, ScVal},
-    BytesObject, Host, HostError, Val, VecObject,
+    host::{
+        metered_clone::{MeteredClone, MeteredContainer, MeteredIterator},
+        metered_xdr::metered_write_xdr,
+    },
+    xdr::{self, ScErrorCode, ScErrorType, ScVal},
+    BytesObject, DiagnosticLevel, Error, Host, HostError, Val, VecObject,
 };
 
 /// The internal representation of a `ContractEvent` that is stored in the
@@ -165,9 +168,15 @@ pub(crate) enum EventError {
 }
 
 /// The events buffer. Stores `InternalEvent`s in the chronological order.
+///
+/// When the contract events size limit is set, the total encoded size of the
+/// contract (non-diagnostic) events of the successful calls is checked
+/// against it when the events are externalized. The size is not measured
+/// otherwise, as measuring it has a cost.
 #[derive(Clone, Default)]
 pub(crate) struct InternalEventsBuffer {
     pub(crate) vec: Vec<(InternalEvent, EventError)>,
+    contract_events_size_limit: Option<u32>,
 }
 
 impl InternalEventsBuffer {
@@ -186,12 +195,19 @@ impl InternalEventsBuffer {
 
         match &e {
             InternalEvent::Contract(_) => metered_internal_event_push(e)?,
-            InternalEvent::Diagnostic(_) => host.with_debug_mode(|| metered_internal_event_push(e)),
+            InternalEvent::Diagnostic(_) => host
+                .with_diagnostic_level(DiagnosticLevel::Errors, || metered_internal_event_push(e)),
         }
 
         Ok(())
     }
 
+    /// Sets the limit on the total encoded size of the contract events, which
+    /// is checked in [InternalEventsBuffer::externalize].
+    pub(crate) fn set_contract_events_size_limit(&mut self, limit: u32) {
+        self.contract_events_size_limit = Some(limit);
+    }
+
     /// "Rolls back" the event buffer starting at `events` by marking all
     /// subsequent events as failed calls.
     pub(crate) fn rollback(&mut self, events: usize) -> Result<(), HostError> {
@@ -207,6 +223,10 @@ impl InternalEventsBuffer {
     /// Converts the internal events into their external representation. This
     /// should only be called either when the host is finished (via
     /// `try_finish`), or when an error occurs.
+    ///
+    /// Fails with the `(Events, ExceededLimit)` error if the contract events
+    /// size limit is set and the contract events of the successful calls
+    /// exceed it.
     pub(crate) fn externalize(&self, host: &Host) -> Result<Events, HostError> {
         // This line is intentionally unmetered. We want to separate out
         // charging the main budget for `Contract` events (with "observable"
@@ -233,13 +253,34 @@ impl InternalEventsBuffer {
                 Ok(())
             };
 
+        let mut contract_events_size: u64 = 0;
         for (event, status) in self.vec.iter() {
             match event {
                 InternalEvent::Contract(c) => {
-                    metered_external_event_push(c.to_xdr(host)?, status)?;
+                    let event = c.to_xdr(host)?;
+                    if let Some(limit) = self.contract_events_size_limit {
+                        if *status == EventError::FromSuccessfulCall {
+                            // This is the same size as the one used for the
+                            // contract events fee.
+                            let mut buf = vec![];
+                            metered_write_xdr(host.budget_ref(), &event, &mut buf)?;
+                            contract_events_size =
+                                contract_events_size.saturating_add(buf.len() as u64);
+                            if contract_events_size > limit as u64 {
+                                return Err(Error::from_type_and_code(
+                                    ScErrorType::Events,
+                                    ScErrorCode::ExceededLimit,
+                                )
+                                .into());
+                            }
+                        }
+                    }
+                    metered_external_event_push(event, status)?;
                 }
                 InternalEvent::Diagnostic(d) => {
-                    host.with_debug_mode(|| metered_external_event_push(d.to_xdr(host)?, status));
+                    host.with_diagnostic_level(DiagnosticLevel::Errors, || {
+                        metered_external_event_push(d.to_xdr(host)?, status)
+                    });
                 }
             }
         }
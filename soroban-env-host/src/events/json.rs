@@ -0,0 +1,260 @@
+//! JSON encoding of the host events (requires the `serde` feature).
+//!
+//! Unlike the `serde` encoding of the XDR types, this encoding is meant to be
+//! read by humans (e.g. in the test snapshots) while still being
+//! unambiguous. A [`HostEvent`] is encoded as
+//!
+//! ```json
+//! {
+//!   "type": "contract",
+//!   "contract_id": "CA...",
+//!   "topics": ["transfer", {"address": "GA..."}],
+//!   "data": {"i128": "100"},
+//!   "failed_call": false
+//! }
+//! ```
+//!
+//! where `type` is one of `contract`, `system` and `diagnostic`, and
+//! `contract_id` is `null` for the events recorded outside of any contract.
+//!
+//! The [`ScVal`] values are encoded as follows:
+//! - `Bool`, `U32` and `I32` as the JSON booleans and numbers, `Void` as
+//!   `null`, `Symbol` as a JSON string and `Vec` as a JSON array.
+//! - All the other values as single-key objects where the key is the value
+//!   type: `{"u64": "1"}`, `{"i64": "-1"}`, `{"timepoint": "1"}`,
+//!   `{"duration": "1"}`, `{"u128": "1"}`, `{"i128": "-1"}`, `{"u256": "1"}`,
+//!   `{"i256": "-1"}` (the 64-bit and wider integers as decimal strings, as
+//!   many JSON parsers can't represent them exactly as numbers),
+//!   `{"bytes": "00ff"}` (hex), `{"string": "abc"}`,
+//!   `{"address": "G..."}` (strkey), `{"error": "Error(Value, InvalidInput)"}`,
+//!   `{"map": [[key, value], ...]}`, `{"ledger_key_nonce": "1"}`,
+//!   `{"ledger_key_contract_instance": null}` and
+//!   `{"contract_instance": {"executable": {"wasm": "<hash hex>"}, "storage": [[key, value], ...]}}`
+//!   (with `"executable": "stellar_asset"` for the Stellar Asset Contract and
+//!   `"storage": null` when the instance has no storage).
+//! - The strings and symbols that aren't valid UTF-8 as
+//!   `{"string_bytes": "<hex>"}` and `{"symbol_bytes": "<hex>"}`, so that
+//!   they can't be confused with the valid ones.
+//!
+//! [`Events`] are encoded as an array of events, and the [`CallTrace`] as an
+//! array of entries, where every entry is either `{"event": <event>}` or
+//! `{"call": <frame>}`. A [`CallFrame`] is encoded as an object with the
+//! `caller` (`null` for the calls made by the host), `contract`, `function`,
+//! `args`, `rolled_back` and `entries` fields, and either the `returned`
+//! value or the `failed` error (`null` if unknown).
+
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+use crate::{
+    num::{i256_from_pieces, u256_from_pieces},
+    xdr::{
+        ContractEventBody, ContractEventType, ContractExecutable, Hash, PublicKey, ScAddress,
+        ScContractInstance, ScMap, ScSymbol, ScVal,
+    },
+    Error,
+};
+
+use super::{CallFrame, CallOutcome, CallTrace, CallTraceEntry, Events, HostEvent};
+
+/// Wrapper that encodes the [`ScVal`] in the JSON format described in the
+/// [module documentation](self).
+pub struct JsonScVal<'a>(pub &'a ScVal);
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn contract_strkey(hash: &Hash) -> String {
+    stellar_strkey::Strkey::Contract(stellar_strkey::Contract(hash.0)).to_string()
+}
+
+fn address_strkey(address: &ScAddress) -> String {
+    match address {
+        ScAddress::Account(account_id) => {
+            let PublicKey::PublicKeyTypeEd25519(key) = &account_id.0;
+            stellar_strkey::Strkey::PublicKeyEd25519(stellar_strkey::ed25519::PublicKey(key.0))
+                .to_string()
+        }
+        ScAddress::Contract(hash) => contract_strkey(hash),
+    }
+}
+
+// A single-key object `{tag: value}`.
+struct Tagged<'a, T: ?Sized>(&'a str, &'a T);
+
+impl<T: Serialize + ?Sized> Serialize for Tagged<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(self.0, self.1)?;
+        map.end()
+    }
+}
+
+fn map_entries(map: &ScMap) -> Vec<(JsonScVal<'_>, JsonScVal<'_>)> {
+    map.iter()
+        .map(|e| (JsonScVal(&e.key), JsonScVal(&e.val)))
+        .collect()
+}
+
+// The contract instance as `{"executable": ..., "storage": ...}`.
+struct JsonContractInstance<'a>(&'a ScContractInstance);
+
+impl Serialize for JsonContractInstance<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        match &self.0.executable {
+            ContractExecutable::Wasm(hash) => {
+                map.serialize_entry("executable", &Tagged("wasm", &to_hex(&hash.0)))?
+            }
+            ContractExecutable::StellarAsset => {
+                map.serialize_entry("executable", "stellar_asset")?
+            }
+        }
+        map.serialize_entry("storage", &self.0.storage.as_ref().map(map_entries))?;
+        map.end()
+    }
+}
+
+// Serializes a `Symbol` (or a function name) as a JSON string, falling back
+// to the hex of its bytes when they aren't valid UTF-8.
+struct JsonSymbol<'a>(&'a ScSymbol);
+
+impl Serialize for JsonSymbol<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(self.0.as_slice()) {
+            Ok(s) => serializer.serialize_str(s),
+            Err(_) => serialize_tagged(serializer, "symbol_bytes", &to_hex(self.0.as_slice())),
+        }
+    }
+}
+
+fn serialize_tagged<S: Serializer, T: Serialize + ?Sized>(
+    serializer: S,
+    tag: &str,
+    value: &T,
+) -> Result<S::Ok, S::Error> {
+    Tagged(tag, value).serialize(serializer)
+}
+
+impl Serialize for JsonScVal<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            ScVal::Bool(v) => serializer.serialize_bool(*v),
+            ScVal::Void => serializer.serialize_unit(),
+            ScVal::Error(e) => serialize_tagged(
+                serializer,
+                "error",
+                &format!("{:?}", Error::from_scerror(e.clone())),
+            ),
+            ScVal::U32(v) => serializer.serialize_u32(*v),
+            ScVal::I32(v) => serializer.serialize_i32(*v),
+            ScVal::U64(v) => serialize_tagged(serializer, "u64", &v.to_string()),
+            ScVal::I64(v) => serialize_tagged(serializer, "i64", &v.to_string()),
+            ScVal::Timepoint(v) => serialize_tagged(serializer, "timepoint", &v.0.to_string()),
+            ScVal::Duration(v) => serialize_tagged(serializer, "duration", &v.0.to_string()),
+            ScVal::U128(v) => serialize_tagged(serializer, "u128", &u128::from(v).to_string()),
+            ScVal::I128(v) => serialize_tagged(serializer, "i128", &i128::from(v).to_string()),
+            ScVal::U256(v) => serialize_tagged(
+                serializer,
+                "u256",
+                &u256_from_pieces(v.hi_hi, v.hi_lo, v.lo_hi, v.lo_lo).to_string(),
+            ),
+            ScVal::I256(v) => serialize_tagged(
+                serializer,
+                "i256",
+                &i256_from_pieces(v.hi_hi, v.hi_lo, v.lo_hi, v.lo_lo).to_string(),
+            ),
+            ScVal::Bytes(v) => serialize_tagged(serializer, "bytes", &to_hex(v.as_slice())),
+            ScVal::String(v) => match std::str::from_utf8(v.as_slice()) {
+                Ok(s) => serialize_tagged(serializer, "string", s),
+                Err(_) => serialize_tagged(serializer, "string_bytes", &to_hex(v.as_slice())),
+            },
+            ScVal::Symbol(v) => JsonSymbol(v).serialize(serializer),
+            ScVal::Vec(None) => serializer.serialize_seq(Some(0))?.end(),
+            ScVal::Vec(Some(vec)) => {
+                let mut seq = serializer.serialize_seq(Some(vec.len()))?;
+                for v in vec.iter() {
+                    seq.serialize_element(&JsonScVal(v))?;
+                }
+                seq.end()
+            }
+            ScVal::Map(None) => serialize_tagged(serializer, "map", &[(); 0]),
+            ScVal::Map(Some(map)) => serialize_tagged(serializer, "map", &map_entries(map)),
+            ScVal::Address(addr) => serialize_tagged(serializer, "address", &address_strkey(addr)),
+            ScVal::LedgerKeyContractInstance => {
+                serialize_tagged(serializer, "ledger_key_contract_instance", &())
+            }
+            ScVal::LedgerKeyNonce(n) => {
+                serialize_tagged(serializer, "ledger_key_nonce", &n.nonce.to_string())
+            }
+            ScVal::ContractInstance(instance) => serialize_tagged(
+                serializer,
+                "contract_instance",
+                &JsonContractInstance(instance),
+            ),
+        }
+    }
+}
+
+impl Serialize for HostEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let type_ = match self.event.type_ {
+            ContractEventType::Contract => "contract",
+            ContractEventType::System => "system",
+            ContractEventType::Diagnostic => "diagnostic",
+        };
+        let ContractEventBody::V0(body) = &self.event.body;
+        let topics: Vec<JsonScVal> = body.topics.iter().map(JsonScVal).collect();
+        let mut map = serializer.serialize_map(Some(5))?;
+        map.serialize_entry("type", type_)?;
+        map.serialize_entry(
+            "contract_id",
+            &self.event.contract_id.as_ref().map(contract_strkey),
+        )?;
+        map.serialize_entry("topics", &topics)?;
+        map.serialize_entry("data", &JsonScVal(&body.data))?;
+        map.serialize_entry("failed_call", &self.failed_call)?;
+        map.end()
+    }
+}
+
+impl Serialize for Events {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl Serialize for CallTraceEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            CallTraceEntry::Event(event) => serialize_tagged(serializer, "event", event),
+            CallTraceEntry::Call(frame) => serialize_tagged(serializer, "call", frame),
+        }
+    }
+}
+
+impl Serialize for CallFrame {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let args: Vec<JsonScVal> = self.args.iter().map(JsonScVal).collect();
+        let mut map = serializer.serialize_map(Some(7))?;
+        map.serialize_entry("caller", &self.caller.as_ref().map(contract_strkey))?;
+        map.serialize_entry("contract", &contract_strkey(&self.contract))?;
+        map.serialize_entry("function", &JsonSymbol(&self.function))?;
+        map.serialize_entry("args", &args)?;
+        match &self.outcome {
+            CallOutcome::Returned(val) => map.serialize_entry("returned", &JsonScVal(val))?,
+            CallOutcome::Failed(err) => {
+                map.serialize_entry("failed", &err.map(|err| format!("{:?}", err)))?
+            }
+        }
+        map.serialize_entry("rolled_back", &self.rolled_back)?;
+        map.serialize_entry("entries", &self.entries)?;
+        map.end()
+    }
+}
+
+impl Serialize for CallTrace {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
@@ -1,9 +1,15 @@
+mod call_trace;
 pub(crate) mod diagnostic;
 mod internal;
+#[cfg(feature = "serde")]
+mod json;
 pub(crate) mod system_events;
+pub use call_trace::{CallFrame, CallOutcome, CallTrace, CallTraceEntry};
 pub(crate) use internal::{
     EventError, InternalDiagnosticArg, InternalDiagnosticEvent, InternalEventsBuffer,
 };
+#[cfg(feature = "serde")]
+pub use json::JsonScVal;
 // expose them as pub use for benches
 use crate::{
     num::{i256_from_pieces, u256_from_pieces},
@@ -143,6 +149,23 @@ impl Host {
         self.try_borrow_events()?.externalize(self)
     }
 
+    /// Limits the total size of the contract (non-diagnostic) events emitted
+    /// by the invocation, such as the per-transaction
+    /// `tx_max_contract_events_size_bytes` network setting. The size of an
+    /// event is the size of its encoded `ContractEvent` XDR, and the events
+    /// rolled back by the failed calls don't count towards the limit.
+    ///
+    /// There is no limit by default. When it's set, the size is measured
+    /// when the events are externalized (i.e. by [Host::get_events] and
+    /// [Host::try_finish]), which fail with the `(Events, ExceededLimit)`
+    /// error if the limit is exceeded. Note, that the network limit also
+    /// covers the return value of the invocation, which is not included here.
+    pub fn set_contract_events_size_limit(&self, limit: u32) -> Result<(), HostError> {
+        self.try_borrow_events_mut()?
+            .set_contract_events_size_limit(limit);
+        Ok(())
+    }
+
     // Records a contract event.
     pub(crate) fn record_contract_event(
         &self,
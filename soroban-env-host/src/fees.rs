@@ -122,6 +122,45 @@ pub struct RentFeeConfiguration {
     pub temporary_rent_rate_denominator: i64,
 }
 
+/// Per-component breakdown of the resource fee of a transaction, as computed
+/// by `compute_transaction_resource_fee_breakdown`.
+pub struct TransactionResourceFeeBreakdown {
+    /// Fee for the CPU instructions.
+    pub compute_fee: i64,
+    /// Fee for the ledger entries read (including the written ones).
+    pub ledger_read_entry_fee: i64,
+    /// Fee for the ledger entries written.
+    pub ledger_write_entry_fee: i64,
+    /// Fee for the bytes read from ledger.
+    pub ledger_read_bytes_fee: i64,
+    /// Fee for the bytes written to ledger.
+    pub ledger_write_bytes_fee: i64,
+    /// Fee for writing the transaction and its result to history.
+    pub historical_fee: i64,
+    /// Fee for the contract events.
+    pub events_fee: i64,
+    /// Fee for the transaction size.
+    pub bandwidth_fee: i64,
+}
+
+impl TransactionResourceFeeBreakdown {
+    /// Returns the sum of the non-refundable fee components.
+    pub fn non_refundable_fee(&self) -> i64 {
+        self.compute_fee
+            .saturating_add(self.ledger_read_entry_fee)
+            .saturating_add(self.ledger_write_entry_fee)
+            .saturating_add(self.ledger_read_bytes_fee)
+            .saturating_add(self.ledger_write_bytes_fee)
+            .saturating_add(self.historical_fee)
+            .saturating_add(self.bandwidth_fee)
+    }
+
+    /// Returns the sum of the refundable fee components.
+    pub fn refundable_fee(&self) -> i64 {
+        self.events_fee
+    }
+}
+
 /// Computes the resource fee for a transaction based on the resource
 /// consumption and the fee-related network configuration.
 ///
@@ -134,6 +173,16 @@ pub fn compute_transaction_resource_fee(
     tx_resources: &TransactionResources,
     fee_config: &FeeConfiguration,
 ) -> (i64, i64) {
+    let breakdown = compute_transaction_resource_fee_breakdown(tx_resources, fee_config);
+    (breakdown.non_refundable_fee(), breakdown.refundable_fee())
+}
+
+/// Same as `compute_transaction_resource_fee`, but returns the fee for every
+/// resource separately.
+pub fn compute_transaction_resource_fee_breakdown(
+    tx_resources: &TransactionResources,
+    fee_config: &FeeConfiguration,
+) -> TransactionResourceFeeBreakdown {
     let compute_fee = compute_fee_per_increment(
         tx_resources.instructions,
         fee_config.fee_per_instruction_increment,
@@ -179,16 +228,16 @@ pub fn compute_transaction_resource_fee(
         DATA_SIZE_1KB_INCREMENT,
     );
 
-    let refundable_fee = events_fee;
-    let non_refundable_fee = compute_fee
-        .saturating_add(ledger_read_entry_fee)
-        .saturating_add(ledger_write_entry_fee)
-        .saturating_add(ledger_read_bytes_fee)
-        .saturating_add(ledger_write_bytes_fee)
-        .saturating_add(historical_fee)
-        .saturating_add(bandwidth_fee);
-
-    (non_refundable_fee, refundable_fee)
+    TransactionResourceFeeBreakdown {
+        compute_fee,
+        ledger_read_entry_fee,
+        ledger_write_entry_fee,
+        ledger_read_bytes_fee,
+        ledger_write_bytes_fee,
+        historical_fee,
+        events_fee,
+        bandwidth_fee,
+    }
 }
 
 // Helper for clamping values to the range of positive i64, with
@@ -305,6 +354,160 @@ pub fn compute_rent_fee(
     fee
 }
 
+/// A ledger entry whose TTL is to be extended, with parameters relevant for
+/// rent fee computations.
+pub struct RentExtensionEntry {
+    /// Whether this is persistent or temporary entry.
+    pub is_persistent: bool,
+    /// Size of the entry in bytes, including the key.
+    pub size_bytes: u32,
+    /// Current live until ledger of the entry. The entry must be live, i.e.
+    /// this should be greater than or equal to the current ledger.
+    pub live_until_ledger: u32,
+}
+
+/// TTL extension of a set of entries and its rent fee.
+pub struct RentExtension {
+    /// Ledger until which all the entries live after the extension.
+    pub live_until_ledger: u32,
+    /// Rent fee for the extension, as computed by `compute_rent_fee`.
+    pub fee: i64,
+}
+
+/// Computes the rent fee for extending the TTL of the provided entries until
+/// (at least) `extend_to_ledger`.
+///
+/// Entries that already live until `extend_to_ledger` are not extended and
+/// don't contribute to the fee. The entries don't change their size.
+pub fn compute_rent_fee_for_extension(
+    entries: &[RentExtensionEntry],
+    extend_to_ledger: u32,
+    fee_config: &RentFeeConfiguration,
+    current_ledger_seq: u32,
+) -> i64 {
+    let changes = entries
+        .iter()
+        .map(|e| LedgerEntryRentChange {
+            is_persistent: e.is_persistent,
+            old_size_bytes: e.size_bytes,
+            new_size_bytes: e.size_bytes,
+            old_live_until_ledger: e.live_until_ledger,
+            new_live_until_ledger: e.live_until_ledger.max(extend_to_ledger),
+        })
+        .collect();
+    compute_rent_fee(&changes, fee_config, current_ledger_seq)
+}
+
+/// Computes the longest TTL extension of the provided entries that fits into
+/// `fee_budget`, up to `max_live_until_ledger` (normally the current ledger
+/// plus the maximum entry TTL of the network, minus 1).
+///
+/// This is the inverse of `compute_rent_fee_for_extension`: the returned
+/// extension is the latest `live_until_ledger` that the entries can be
+/// extended to for at most `fee_budget`, along with its exact fee. See
+/// `compute_optimal_rent_extension` for the extension with the lowest fee per
+/// ledger.
+///
+/// When the budget doesn't suffice to extend any entry by a single ledger,
+/// the returned extension is the earliest live until ledger of the entries
+/// (i.e. no extension) with a zero fee.
+pub fn compute_max_rent_extension(
+    entries: &[RentExtensionEntry],
+    fee_budget: i64,
+    max_live_until_ledger: u32,
+    fee_config: &RentFeeConfiguration,
+    current_ledger_seq: u32,
+) -> RentExtension {
+    let fee_for = |live_until_ledger| {
+        compute_rent_fee_for_extension(entries, live_until_ledger, fee_config, current_ledger_seq)
+    };
+    let mut lo = entries
+        .iter()
+        .map(|e| e.live_until_ledger)
+        .min()
+        .unwrap_or(max_live_until_ledger)
+        .min(max_live_until_ledger);
+    let mut hi = max_live_until_ledger;
+    // The fee doesn't decrease with the extension, so binary search the
+    // latest ledger that fits into the budget. `lo` always fits, as nothing
+    // is extended to it.
+    while lo < hi {
+        let mid = lo + num_integer::div_ceil(hi - lo, 2);
+        if fee_for(mid) <= fee_budget {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    RentExtension {
+        live_until_ledger: lo,
+        fee: fee_for(lo),
+    }
+}
+
+/// Computes the TTL extension of the provided entries with the lowest rent fee
+/// per ledger of extension, up to `max_live_until_ledger` (normally the current
+/// ledger plus the maximum entry TTL of the network, minus 1).
+///
+/// The length of an extension to `live_until_ledger` is the number of ledgers
+/// it adds to the lifetime of the whole set of entries, i.e.
+/// `live_until_ledger` minus the earliest live until ledger of the entries.
+/// Every extended entry is charged a constant fee for writing its TTL entry
+/// plus rent that is proportional to the number of ledgers it's extended by,
+/// so (up to the rounding of the fees to whole stroops) the fee per ledger is
+/// monotonic between the ledgers where the set of the extended entries
+/// changes. Hence only these ledgers (just before and just after every entry
+/// starts being extended) and `max_live_until_ledger` are considered, and the
+/// latest of them is returned on ties.
+///
+/// When no entry can be extended, the returned extension is the earliest live
+/// until ledger of the entries (i.e. no extension) with a zero fee.
+pub fn compute_optimal_rent_extension(
+    entries: &[RentExtensionEntry],
+    max_live_until_ledger: u32,
+    fee_config: &RentFeeConfiguration,
+    current_ledger_seq: u32,
+) -> RentExtension {
+    let earliest_live_until_ledger = entries
+        .iter()
+        .map(|e| e.live_until_ledger)
+        .min()
+        .unwrap_or(max_live_until_ledger)
+        .min(max_live_until_ledger);
+    let mut best = RentExtension {
+        live_until_ledger: earliest_live_until_ledger,
+        fee: 0,
+    };
+    let candidates = entries
+        .iter()
+        .flat_map(|e| [e.live_until_ledger, e.live_until_ledger.saturating_add(1)])
+        .chain(std::iter::once(max_live_until_ledger))
+        .filter(|l| *l > earliest_live_until_ledger && *l <= max_live_until_ledger);
+    for live_until_ledger in candidates {
+        let fee = compute_rent_fee_for_extension(
+            entries,
+            live_until_ledger,
+            fee_config,
+            current_ledger_seq,
+        );
+        let ledgers = (live_until_ledger - earliest_live_until_ledger) as i128;
+        let best_ledgers = (best.live_until_ledger - earliest_live_until_ledger) as i128;
+        // Compare `fee / ledgers` with `best.fee / best_ledgers` without
+        // rounding; the initial no-op extension loses to any actual one.
+        let is_better = best_ledgers == 0
+            || (fee as i128) * best_ledgers < (best.fee as i128) * ledgers
+            || ((fee as i128) * best_ledgers == (best.fee as i128) * ledgers
+                && live_until_ledger > best.live_until_ledger);
+        if is_better {
+            best = RentExtension {
+                live_until_ledger,
+                fee,
+            };
+        }
+    }
+    best
+}
+
 // Size of half-open range (lo, hi], or None if lo>hi
 fn exclusive_ledger_diff(lo: u32, hi: u32) -> Option<u32> {
     hi.checked_sub(lo)
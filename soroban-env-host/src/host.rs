@@ -1,5 +1,5 @@
 use core::{cell::RefCell, cmp::Ordering, fmt::Debug};
-use std::rc::Rc;
+use std::{collections::BTreeSet, rc::Rc};
 
 use crate::{
     auth::AuthorizationManager,
@@ -10,6 +10,7 @@ use crate::{
     impl_wrapping_obj_to_num,
     num::*,
     storage::Storage,
+    vm::ModuleCache,
     xdr::{
         int128_helpers, AccountId, Asset, ContractCostType, ContractEventType, ContractExecutable,
         ContractIdPreimage, ContractIdPreimageFromAddress, CreateContractArgs, Duration, Hash,
@@ -111,9 +112,21 @@ struct HostImpl {
     // way of observing the diagnostic level (which may vary between different
     // replicas of the host, thus causing divergence) there are no borrow
     // helpers for it and the only method to use it is inside the
-    // `with_debug_mode` callback that switches to the shadow budget.
+    // `with_debug_mode` and `with_diagnostic_level` callbacks that switch to
+    // the shadow budget.
     diagnostic_level: RefCell<DiagnosticLevel>,
     base_prng: RefCell<Option<Prng>>,
+    // Parsed wasm modules that may be shared with other hosts, see
+    // `set_module_cache`. A private cache is created on the first contract
+    // instantiation if none has been attached.
+    module_cache: RefCell<Option<Rc<ModuleCache>>>,
+    // Hashes of the contract code that has been instantiated by this host,
+    // i.e. whose full instantiation cost has been charged, see
+    // `Vm::new_with_cache`.
+    instantiated_code_hashes: RefCell<BTreeSet<Hash>>,
+    // Whether to check contract invocation arguments against the contract's
+    // `contractspecv0` section, see `set_contract_spec_validation`.
+    contract_spec_validation: RefCell<bool>,
     // Auth-recording mode generates pseudorandom nonces to populate its output.
     // We'd like these to be deterministic from one run to the next, but also
     // completely isolated from any use of the user-accessible PRNGs (either
@@ -228,6 +241,25 @@ impl_checked_borrow_helpers!(
     try_borrow_authorization_manager_mut
 );
 
+impl_checked_borrow_helpers!(
+    module_cache,
+    Option<Rc<ModuleCache>>,
+    try_borrow_module_cache,
+    try_borrow_module_cache_mut
+);
+impl_checked_borrow_helpers!(
+    instantiated_code_hashes,
+    BTreeSet<Hash>,
+    try_borrow_instantiated_code_hashes,
+    try_borrow_instantiated_code_hashes_mut
+);
+impl_checked_borrow_helpers!(
+    contract_spec_validation,
+    bool,
+    try_borrow_contract_spec_validation,
+    try_borrow_contract_spec_validation_mut
+);
+
 // Note: diagnostic_mode borrow helpers are _not_ defined here to reduce the
 // risk of future maintainers accidentally revealing any way of observing the
 // diagnostic level in user code (which may vary between different replicas of
@@ -315,6 +347,9 @@ impl Host {
             ),
             diagnostic_level: Default::default(),
             base_prng: RefCell::new(None),
+            module_cache: RefCell::new(None),
+            instantiated_code_hashes: Default::default(),
+            contract_spec_validation: RefCell::new(false),
             #[cfg(any(test, feature = "recording_auth"))]
             recording_auth_nonce_prng: RefCell::new(None),
             #[cfg(any(test, feature = "testutils"))]
@@ -399,6 +434,31 @@ impl Host {
         Ok(())
     }
 
+    /// Switches to the recording authorization mode that still enforces
+    /// the provided `auth_entries`.
+    ///
+    /// Authorization of every address that has at least one entry in
+    /// `auth_entries` is enforced exactly like in the enforcing mode (i.e.
+    /// the signatures are verified and the nonces are consumed), while the
+    /// authorization requirements of all the other addresses are recorded and
+    /// can be retrieved via `get_recorded_auth_payloads`. This allows
+    /// simulating an invocation after some of the parties have already signed
+    /// their authorization entries.
+    #[cfg(any(test, feature = "recording_auth"))]
+    pub fn switch_to_recording_auth_with_entries(
+        &self,
+        disable_non_root_auth: bool,
+        auth_entries: Vec<soroban_env_common::xdr::SorobanAuthorizationEntry>,
+    ) -> Result<(), HostError> {
+        let new_auth_manager = AuthorizationManager::new_recording_with_entries(
+            self,
+            disable_non_root_auth,
+            auth_entries,
+        )?;
+        *self.try_borrow_authorization_manager_mut()? = new_auth_manager;
+        Ok(())
+    }
+
     pub fn set_authorization_entries(
         &self,
         auth_entries: Vec<soroban_env_common::xdr::SorobanAuthorizationEntry>,
@@ -408,6 +468,51 @@ impl Host {
         Ok(())
     }
 
+    /// Attaches a [ModuleCache] to the host, which [Vm](crate::Vm)s for
+    /// contracts are instantiated from. Modules are parsed and added to the
+    /// cache when they are first instantiated. Attaching a cache only saves
+    /// the work of parsing the modules that are already cached and doesn't
+    /// affect metering: without one, the host uses a private cache, and in
+    /// either case the first instantiation of every contract code is charged
+    /// in full and the later ones as
+    /// [ContractCostType::VmCachedInstantiation].
+    ///
+    /// The cache must have been built for a host with the same wasmi fuel
+    /// costs as this one.
+    pub fn set_module_cache(&self, cache: Rc<ModuleCache>) -> Result<(), HostError> {
+        if !cache.is_compatible_with(self)? {
+            return Err(self.err(
+                ScErrorType::Context,
+                ScErrorCode::InternalError,
+                "module cache was built with different wasmi fuel costs",
+                &[],
+            ));
+        }
+        *self.try_borrow_module_cache_mut()? = Some(cache);
+        Ok(())
+    }
+
+    // Returns the attached module cache, attaching a private one first if
+    // there is none.
+    pub(crate) fn module_cache(&self) -> Result<Rc<ModuleCache>, HostError> {
+        if let Some(cache) = self.try_borrow_module_cache()?.as_ref() {
+            return Ok(Rc::clone(cache));
+        }
+        let cache = Rc::new(ModuleCache::new(self)?);
+        *self.try_borrow_module_cache_mut()? = Some(Rc::clone(&cache));
+        Ok(cache)
+    }
+
+    /// Enables or disables checking the arguments of every call into a WASM
+    /// contract against the function signatures declared in the contract's
+    /// `contractspecv0` section, before the call enters the VM. A mismatch
+    /// fails the call with an error (and diagnostic event) naming the
+    /// offending argument. Disabled by default.
+    pub fn set_contract_spec_validation(&self, enabled: bool) -> Result<(), HostError> {
+        *self.try_borrow_contract_spec_validation_mut()? = enabled;
+        Ok(())
+    }
+
     #[allow(unused_variables)]
     pub fn set_base_prng_seed(&self, seed: prng::Seed) -> Result<(), HostError> {
         let mut base_prng = Prng::new_from_seed(seed, self.budget_ref())?;
@@ -497,10 +602,13 @@ impl Host {
         self.set_diagnostic_level(DiagnosticLevel::Debug)
     }
 
-    /// Wraps a `budget.with_shadow_mode` call with a check against the
-    /// diagnostic level. This wrapper should be used for any work that is part
-    /// of the production workflow but in debug mode, i.e. diagnostic related
-    /// work (logging, or any operations on diagnostic events).
+    /// Wraps a `budget.with_shadow_mode` call with a check that diagnostics
+    /// are enabled at [DiagnosticLevel::Debug] (or above). This wrapper
+    /// should be used for any work that is part of the production workflow
+    /// but in debug mode, i.e. diagnostic related work (logging, or any
+    /// operations on diagnostic events). Work that must also happen at the
+    /// lower levels, like recording the error events, should use
+    /// [Host::with_diagnostic_level] instead.
     ///
     /// Note: to help minimize the risk of divergence based on accidental
     /// observation of the diagnostic level in any context _other_ than this
@@ -517,12 +625,21 @@ impl Host {
     /// debug-mode-guarded and/or only write results into debug state (eg.
     /// diagnostic events).
     pub(crate) fn with_debug_mode<F>(&self, f: F)
+    where
+        F: FnOnce() -> Result<(), HostError>,
+    {
+        self.with_diagnostic_level(DiagnosticLevel::Debug, f)
+    }
+
+    /// Like [Host::with_debug_mode], but runs `f` when the diagnostic level
+    /// is at least `level` instead. The same caveats apply.
+    pub(crate) fn with_diagnostic_level<F>(&self, level: DiagnosticLevel, f: F)
     where
         F: FnOnce() -> Result<(), HostError>,
     {
         use crate::host::error::TryBorrowOrErr;
         if let Ok(cell) = self.0.diagnostic_level.try_borrow_or_err() {
-            if matches!(*cell, DiagnosticLevel::Debug) {
+            if *cell != DiagnosticLevel::None && *cell >= level {
                 return self.budget_ref().with_shadow_mode(f);
             }
         }
@@ -934,7 +1051,7 @@ impl VmCallerEnv for Host {
         vals_pos: U32Val,
         vals_len: U32Val,
     ) -> Result<Void, HostError> {
-        self.with_debug_mode(|| {
+        self.with_diagnostic_level(DiagnosticLevel::Logs, || {
             let MemFnArgs { vm, pos, len } = self.get_mem_fn_args(msg_pos, msg_len)?;
             Vec::<u8>::charge_bulk_init_cpy(len as u64, self)?;
             let mut msg: Vec<u8> = vec![0u8; len as usize];
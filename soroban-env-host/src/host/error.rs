@@ -2,7 +2,7 @@ use crate::{
     budget::AsBudget,
     events::Events,
     xdr::{self, Hash, LedgerKey, ScAddress, ScError, ScErrorCode, ScErrorType},
-    ConversionError, EnvBase, Error, Host, TryFromVal, U32Val, Val,
+    ConversionError, DiagnosticLevel, EnvBase, Error, Host, TryFromVal, U32Val, Val,
 };
 
 #[cfg(any(test, feature = "testutils"))]
@@ -246,7 +246,7 @@ impl Host {
     /// [Backtrace] and snapshot of the [Events] buffer.
     pub(crate) fn error(&self, error: Error, msg: &str, args: &[Val]) -> HostError {
         let mut he = HostError::from(error);
-        self.with_debug_mode(|| {
+        self.with_diagnostic_level(DiagnosticLevel::Errors, || {
             // We _try_ to take a mutable borrow of the events buffer refcell
             // while building up the event we're going to emit into the events
             // log, failing gracefully (just emitting a no-debug-info
@@ -345,7 +345,7 @@ impl Host {
             // This observes the debug state, but it only causes a different
             // (richer) string to be logged as a diagnostic event, which
             // is itself not observable outside the debug state.
-            self.with_debug_mode(|| {
+            self.with_diagnostic_level(DiagnosticLevel::Errors, || {
                 msg = Cow::Owned(format!("{:?}", e));
                 Ok(())
             });
@@ -475,7 +475,7 @@ pub(crate) trait DebugArg {
         // _while_ forming a debug argument.
         let mut val: Option<Val> = None;
         if let Ok(_guard) = host.0.events.try_borrow_mut() {
-            host.with_debug_mode(|| {
+            host.with_diagnostic_level(DiagnosticLevel::Errors, || {
                 if let Ok(v) = Self::debug_arg_maybe_expensive_or_fallible(host, arg) {
                     val = Some(v);
                 }
@@ -544,7 +544,7 @@ macro_rules! err {
             // expression that corresponds to the number of arguments.
             let mut buf = [$(voidarg(stringify!($args))),*];
             let mut i = 0;
-            $host.with_debug_mode(||{
+            $host.with_diagnostic_level($crate::DiagnosticLevel::Errors, ||{
                 $(
                     // Args actually get used here, where we fill in array cells..
                     buf[i] = <_ as $crate::host::error::DebugArg>::debug_arg($host, &$args);
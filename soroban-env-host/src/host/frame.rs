@@ -176,6 +176,72 @@ impl Host {
         Ok(rp)
     }
 
+    /// Computes the label of a frame in the budget's call tree, or `None` if
+    /// the call tree is disabled. Naming the frame runs in shadow mode, so
+    /// that enabling the call tree does not affect metering, and the frame is
+    /// left unnamed if naming fails.
+    fn call_tree_label(
+        &self,
+        name: impl FnOnce() -> Result<(Option<Hash>, String), HostError>,
+    ) -> Result<Option<(Option<Hash>, String)>, HostError> {
+        if !self.as_budget().is_tracking_call_tree()? {
+            return Ok(None);
+        }
+        let mut label = None;
+        self.as_budget().with_shadow_mode(|| {
+            label = Some(name()?);
+            Ok(())
+        });
+        Ok(Some(label.unwrap_or_default()))
+    }
+
+    fn contract_call_tree_label(
+        &self,
+        id: &Hash,
+        func: &Symbol,
+    ) -> Result<Option<(Option<Hash>, String)>, HostError> {
+        self.call_tree_label(|| {
+            Ok((
+                Some(id.metered_clone(self)?),
+                SymbolStr::try_from_val(self, func)?.to_string(),
+            ))
+        })
+    }
+
+    fn frame_call_tree_label(
+        &self,
+        frame: &Frame,
+    ) -> Result<Option<(Option<Hash>, String)>, HostError> {
+        let (id, func) = match frame {
+            Frame::HostFunction(hf) => {
+                return self.call_tree_label(|| Ok((None, hf.name().to_string())))
+            }
+            Frame::ContractVM { vm, fn_name, .. } => (&vm.contract_id, fn_name),
+            Frame::StellarAssetContract(id, fn_name, ..) => (id, fn_name),
+            #[cfg(any(test, feature = "testutils"))]
+            Frame::TestContract(tc) => (&tc.id, &tc.func),
+        };
+        self.contract_call_tree_label(id, func)
+    }
+
+    /// Runs `f` within a new frame of the budget's call tree labeled with
+    /// `label` (see [`Host::call_tree_label`]), or just runs `f` if `label`
+    /// is `None`. The frame is closed whether or not `f` succeeds, so pushes
+    /// and pops always stay balanced.
+    fn with_call_tree_frame<T>(
+        &self,
+        label: Option<(Option<Hash>, String)>,
+        f: impl FnOnce() -> Result<T, HostError>,
+    ) -> Result<T, HostError> {
+        let Some((contract_id, function)) = label else {
+            return f();
+        };
+        self.as_budget().push_call_frame(contract_id, function)?;
+        let res = f();
+        self.as_budget().pop_call_frame()?;
+        res
+    }
+
     /// Helper function for [`Host::with_frame`] below. Pops a [`Context`] off
     /// the current context stack and optionally rolls back the [`Host`]'s objects
     /// and storage map to the state in the provided [`RollbackPoint`].
@@ -373,7 +439,23 @@ impl Host {
     /// Pushes a [`Frame`], runs a closure, and then pops the frame, rolling back
     /// if the closure returned an error. Returns the result that the closure
     /// returned (or any error caused during the frame push/pop).
+    ///
+    /// The frame is also tracked in the budget's call tree (if enabled),
+    /// except for the [`Frame::ContractVM`] frames, which are tracked by
+    /// [`Host::call_contract_fn`] so that the VM instantiation is attributed
+    /// to the called contract.
     pub(crate) fn with_frame<F>(&self, frame: Frame, f: F) -> Result<Val, HostError>
+    where
+        F: FnOnce() -> Result<Val, HostError>,
+    {
+        let label = match &frame {
+            Frame::ContractVM { .. } => None,
+            _ => self.frame_call_tree_label(&frame)?,
+        };
+        self.with_call_tree_frame(label, || self.with_context_frame(frame, f))
+    }
+
+    fn with_context_frame<F>(&self, frame: Frame, f: F) -> Result<Val, HostError>
     where
         F: FnOnce() -> Result<Val, HostError>,
     {
@@ -623,24 +705,50 @@ impl Host {
             .map_err(|e| self.decorate_contract_instance_storage_error(e, &id))?;
         Vec::<Val>::charge_bulk_init_cpy(args.len() as u64, self.as_budget())?;
         let args_vec = args.to_vec();
-        match &instance.executable {
+        let ScContractInstance {
+            executable,
+            storage,
+        } = instance;
+        match executable {
             ContractExecutable::Wasm(wasm_hash) => {
-                let code_entry = self.retrieve_wasm_from_storage(&wasm_hash)?;
-                let vm = Vm::new(self, id.metered_clone(self)?, code_entry.as_slice())?;
-                let relative_objects = Vec::new();
-                self.with_frame(
-                    Frame::ContractVM {
-                        vm: Rc::clone(&vm),
-                        fn_name: *func,
-                        args: args_vec,
-                        instance,
-                        relative_objects,
-                    },
-                    || vm.invoke_function_raw(self, func, args),
-                )
+                // The call tree frame is opened before instantiating the VM,
+                // which is usually the largest cost of the call.
+                let label = self.contract_call_tree_label(id, func)?;
+                self.with_call_tree_frame(label, || {
+                    let code_entry = self.retrieve_wasm_from_storage(&wasm_hash)?;
+                    let vm = Vm::new_with_cache(
+                        self,
+                        id.metered_clone(self)?,
+                        &wasm_hash,
+                        code_entry.as_slice(),
+                    )?;
+                    self.check_args_against_contract_spec(&vm, &wasm_hash, func, args)?;
+                    let relative_objects = Vec::new();
+                    self.with_frame(
+                        Frame::ContractVM {
+                            vm: Rc::clone(&vm),
+                            fn_name: *func,
+                            args: args_vec,
+                            instance: ScContractInstance {
+                                executable: ContractExecutable::Wasm(wasm_hash),
+                                storage,
+                            },
+                            relative_objects,
+                        },
+                        || vm.invoke_function_raw(self, func, args),
+                    )
+                })
             }
             ContractExecutable::StellarAsset => self.with_frame(
-                Frame::StellarAssetContract(id.metered_clone(self)?, *func, args_vec, instance),
+                Frame::StellarAssetContract(
+                    id.metered_clone(self)?,
+                    *func,
+                    args_vec,
+                    ScContractInstance {
+                        executable: ContractExecutable::StellarAsset,
+                        storage,
+                    },
+                ),
                 || {
                     use crate::builtin_contracts::{BuiltinContract, StellarAssetContract};
                     StellarAssetContract.call(func, self, args)
@@ -800,7 +908,7 @@ impl Host {
                             // code path will get hit when contracts do
                             // `panic!("some string")` in native testing mode.
                             if !recovered_error_from_panic_refcell {
-                                self.with_debug_mode(|| {
+                                self.with_diagnostic_level(crate::DiagnosticLevel::Logs, || {
                                     if let Some(str) = panic_payload.downcast_ref::<&str>() {
                                         let msg: String = format!(
                                             "caught panic '{}' from contract function '{:?}'",
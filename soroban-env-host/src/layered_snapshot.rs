@@ -0,0 +1,164 @@
+//! This module provides [LayeredSnapshotSource], a [SnapshotSource] that
+//! stacks the ledger changes of a sequence of invocations on top of a base
+//! snapshot, so that every invocation observes the writes, deletions and TTL
+//! extensions of the ones before it (e.g. when simulating a deployment, then
+//! an initialization, then a call of the same contract).
+//!
+//! Every [LayeredSnapshotSource::push_ledger_changes] call adds a layer
+//! holding the changes of one invocation. The top layer can then be dropped
+//! (e.g. when the transaction it belongs to is not going to be submitted)
+//! with [LayeredSnapshotSource::discard_top_layer], or merged into the layer
+//! below with [LayeredSnapshotSource::commit_top_layer]. The base snapshot is
+//! never modified.
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use crate::{
+    e2e_invoke::LedgerEntryChange,
+    host::error::TryBorrowOrErr,
+    storage::{EntryWithLiveUntil, SnapshotSource},
+    xdr::{LedgerEntry, LedgerKey, ReadXdr, ScErrorCode, ScErrorType},
+    Error, HostError, DEFAULT_XDR_RW_LIMITS,
+};
+
+/// The state of an entry changed by a layer: `None` for removed entries.
+pub type LayeredEntryState = Option<EntryWithLiveUntil>;
+
+type Layer = BTreeMap<Rc<LedgerKey>, LayeredEntryState>;
+
+/// A [SnapshotSource] made of a base snapshot and a stack of layers of ledger
+/// changes on top of it, the most recent one last.
+pub struct LayeredSnapshotSource {
+    base: Rc<dyn SnapshotSource>,
+    // Layers are pushed while the source is shared with hosts (it is passed
+    // to them as `Rc<dyn SnapshotSource>`), hence the `RefCell`.
+    layers: RefCell<Vec<Layer>>,
+}
+
+impl LayeredSnapshotSource {
+    pub fn new(base: Rc<dyn SnapshotSource>) -> Self {
+        Self {
+            base,
+            layers: RefCell::new(vec![]),
+        }
+    }
+
+    /// Returns the number of layers on top of the base snapshot.
+    pub fn depth(&self) -> Result<usize, HostError> {
+        Ok(self.layers.try_borrow_or_err()?.len())
+    }
+
+    // Looks the entry up in the layers, from the top one down. `None` means
+    // that the layers don't have the entry and the base snapshot must be
+    // consulted.
+    fn get_from_layers(&self, key: &Rc<LedgerKey>) -> Result<Option<LayeredEntryState>, HostError> {
+        Ok(self
+            .layers
+            .try_borrow_or_err()?
+            .iter()
+            .rev()
+            .find_map(|layer| layer.get(key).cloned()))
+    }
+
+    fn get_current(&self, key: &Rc<LedgerKey>) -> Result<LayeredEntryState, HostError> {
+        match self.get_from_layers(key)? {
+            Some(state) => Ok(state),
+            None if self.base.has(key)? => Ok(Some(self.base.get(key)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Pushes a new layer holding the ledger changes of an invocation (as
+    /// returned by [crate::e2e_invoke::get_ledger_changes]) that ran against
+    /// this source: read-write entries are updated or removed, and the live
+    /// until ledgers of all the entries are updated.
+    pub fn push_ledger_changes(&self, changes: &[LedgerEntryChange]) -> Result<(), HostError> {
+        let mut layer = Layer::new();
+        for change in changes {
+            let key = Rc::new(LedgerKey::from_xdr(
+                &change.encoded_key,
+                DEFAULT_XDR_RW_LIMITS,
+            )?);
+            let new_live_until_ledger = change
+                .ttl_change
+                .as_ref()
+                .map(|ttl_change| ttl_change.new_live_until_ledger);
+            if change.read_only {
+                // Read-only entries can only have their TTL extended.
+                let Some(new_live_until_ledger) = new_live_until_ledger else {
+                    continue;
+                };
+                if let Some((entry, live_until_ledger)) = self.get_current(&key)? {
+                    if live_until_ledger != Some(new_live_until_ledger) {
+                        layer.insert(key, Some((entry, Some(new_live_until_ledger))));
+                    }
+                }
+            } else if let Some(encoded_new_value) = &change.encoded_new_value {
+                let entry = LedgerEntry::from_xdr(encoded_new_value, DEFAULT_XDR_RW_LIMITS)?;
+                layer.insert(key, Some((Rc::new(entry), new_live_until_ledger)));
+            } else if self.get_current(&key)?.is_some() {
+                layer.insert(key, None);
+            }
+        }
+        self.layers.try_borrow_mut_or_err()?.push(layer);
+        Ok(())
+    }
+
+    /// Drops the top layer, returning whether there was one.
+    pub fn discard_top_layer(&self) -> Result<bool, HostError> {
+        Ok(self.layers.try_borrow_mut_or_err()?.pop().is_some())
+    }
+
+    /// Merges the top layer into the layer below it, so that both can only
+    /// be discarded together. Does nothing when there are fewer than two
+    /// layers.
+    pub fn commit_top_layer(&self) -> Result<(), HostError> {
+        let mut layers = self.layers.try_borrow_mut_or_err()?;
+        if layers.len() >= 2 {
+            if let Some(top) = layers.pop() {
+                if let Some(below) = layers.last_mut() {
+                    below.extend(top);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the state of every entry changed by the layers relative to the
+    /// base snapshot, in the order of their keys. The entries that were both
+    /// created and removed by the layers are not changes, so they are skipped.
+    pub fn changes(&self) -> Result<Vec<(Rc<LedgerKey>, LayeredEntryState)>, HostError> {
+        let mut merged = Layer::new();
+        for layer in self.layers.try_borrow_or_err()?.iter() {
+            merged.extend(layer.iter().map(|(k, v)| (Rc::clone(k), v.clone())));
+        }
+        let mut changes = vec![];
+        for (key, state) in merged {
+            if state.is_some() || self.base.has(&key)? {
+                changes.push((key, state));
+            }
+        }
+        Ok(changes)
+    }
+}
+
+impl SnapshotSource for LayeredSnapshotSource {
+    fn get(&self, key: &Rc<LedgerKey>) -> Result<EntryWithLiveUntil, HostError> {
+        match self.get_from_layers(key)? {
+            Some(Some(entry)) => Ok(entry),
+            Some(None) => Err(Error::from_type_and_code(
+                ScErrorType::Storage,
+                ScErrorCode::MissingValue,
+            )
+            .into()),
+            None => self.base.get(key),
+        }
+    }
+
+    fn has(&self, key: &Rc<LedgerKey>) -> Result<bool, HostError> {
+        match self.get_from_layers(key)? {
+            Some(state) => Ok(state.is_some()),
+            None => self.base.has(key),
+        }
+    }
+}
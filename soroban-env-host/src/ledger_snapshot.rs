@@ -0,0 +1,336 @@
+//! This module provides [LedgerSnapshot], a [SnapshotSource] holding a set of
+//! ledger entries (and their live until ledgers) that can be read from and
+//! written to a snapshot file, so that contracts can be run offline against
+//! ledger state exported from a network. The snapshots are read from any
+//! [Read] and written to any [Write]; opening the files is left to the caller
+//! (e.g. the `soroban-env-host` command-line driver).
+//!
+//! Two file formats are supported:
+//!
+//!   - The XDR format is a stream of `LedgerEntry` XDR records, each one
+//!     preceded by a 4-byte big-endian record mark as in RFC 5531 (the high
+//!     bit set to mark the last fragment of the record and the low 31 bits
+//!     holding the record length). The live until ledgers are stored as
+//!     `LedgerEntry` records of type `Ttl`, which refer to the entry they
+//!     belong to by the SHA-256 hash of its `LedgerKey` XDR, i.e. the same way
+//!     as on the ledger itself. Every contract data and contract code entry
+//!     must have a TTL record, and every TTL record must belong to an entry in
+//!     the stream. Records may come in any order, and can't be longer than
+//!     the default XDR length limit.
+//!
+//!   - The JSON format (requires the `serde` feature) is an object with a
+//!     single `entries` array; every element holds the base64-encoded
+//!     `LedgerEntry` XDR in `entry` and its live until ledger (or `null`) in
+//!     `live_until_ledger`:
+//!
+//!     ```json
+//!     { "entries": [ { "entry": "AAAAAA...", "live_until_ledger": 1000 } ] }
+//!     ```
+//!
+//! After running an invocation against a snapshot, the resulting
+//! [LedgerEntryChange]s can be applied to it with
+//! [LedgerSnapshot::apply_ledger_changes] and the post-invocation state saved.
+//!
+//! Loading and saving happen outside of any host, so they are not metered.
+
+use std::{
+    collections::BTreeMap,
+    io::{ErrorKind, Read, Write},
+    rc::Rc,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    budget::Budget,
+    e2e_invoke::{ledger_entry_to_ledger_key, LedgerEntryChange},
+    storage::{EntryWithLiveUntil, SnapshotSource},
+    xdr::{
+        Hash, LedgerEntry, LedgerEntryData, LedgerEntryExt, LedgerKey, Limits, ReadXdr,
+        ScErrorCode, ScErrorType, TtlEntry, WriteXdr,
+    },
+    Error, HostError, DEFAULT_XDR_RW_LIMITS,
+};
+
+// The high bit of an RFC 5531 record mark, set on the last fragment of a
+// record. Records in snapshot files always consist of a single fragment.
+const LAST_FRAGMENT_BIT: u32 = 0x8000_0000;
+
+/// A set of ledger entries with their live until ledgers, which can be read
+/// from and written to a snapshot file and used as the [SnapshotSource] for a
+/// host.
+#[derive(Clone, Debug, Default)]
+pub struct LedgerSnapshot {
+    entries: BTreeMap<Rc<LedgerKey>, EntryWithLiveUntil>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonSnapshot {
+    entries: Vec<JsonSnapshotEntry>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonSnapshotEntry {
+    entry: String,
+    live_until_ledger: Option<u32>,
+}
+
+fn invalid_input() -> HostError {
+    Error::from_type_and_code(ScErrorType::Storage, ScErrorCode::InvalidInput).into()
+}
+
+fn io_error(e: std::io::Error) -> HostError {
+    crate::xdr::Error::Io(e).into()
+}
+
+fn ledger_key_hash(key: &LedgerKey) -> Result<Hash, HostError> {
+    let key_xdr = key.to_xdr(DEFAULT_XDR_RW_LIMITS)?;
+    Ok(Hash(Sha256::digest(key_xdr).into()))
+}
+
+impl LedgerSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a snapshot from ledger entries and their live until ledgers.
+    pub fn from_entries<I>(entries: I) -> Result<Self, HostError>
+    where
+        I: IntoIterator<Item = (LedgerEntry, Option<u32>)>,
+    {
+        let budget = Budget::try_new_unlimited()?;
+        let mut snapshot = Self::new();
+        for (entry, live_until_ledger) in entries {
+            snapshot.insert_with_budget(entry, live_until_ledger, &budget)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Adds an entry to the snapshot, replacing any entry with the same key.
+    /// `Ttl` entries are rejected: the live until ledger is stored alongside
+    /// the entry it belongs to.
+    pub fn insert(
+        &mut self,
+        entry: LedgerEntry,
+        live_until_ledger: Option<u32>,
+    ) -> Result<(), HostError> {
+        self.insert_with_budget(entry, live_until_ledger, &Budget::try_new_unlimited()?)
+    }
+
+    fn insert_with_budget(
+        &mut self,
+        entry: LedgerEntry,
+        live_until_ledger: Option<u32>,
+        budget: &Budget,
+    ) -> Result<(), HostError> {
+        if matches!(entry.data, LedgerEntryData::Ttl(_)) {
+            return Err(invalid_input());
+        }
+        let key = ledger_entry_to_ledger_key(&entry, budget)?;
+        self.entries
+            .insert(Rc::new(key), (Rc::new(entry), live_until_ledger));
+        Ok(())
+    }
+
+    /// Removes the entry with the given key from the snapshot, returning it if
+    /// it was present.
+    pub fn remove(&mut self, key: &LedgerKey) -> Option<EntryWithLiveUntil> {
+        self.entries.remove(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in the snapshot, in the order of their keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&Rc<LedgerKey>, &EntryWithLiveUntil)> {
+        self.entries.iter()
+    }
+
+    /// Reads a snapshot in the XDR format.
+    pub fn read_xdr<R: Read>(mut r: R) -> Result<Self, HostError> {
+        let mut entries = Vec::new();
+        let mut ttls = BTreeMap::<Hash, u32>::new();
+        loop {
+            // The stream may only end at a record boundary.
+            let mut mark = [0u8; 4];
+            match r.read(&mut mark[..1]) {
+                Ok(0) => break,
+                Ok(_) => r.read_exact(&mut mark[1..]).map_err(io_error)?,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(io_error(e)),
+            }
+            let mark = u32::from_be_bytes(mark);
+            let len = (mark & !LAST_FRAGMENT_BIT) as usize;
+            if mark & LAST_FRAGMENT_BIT == 0 || len > DEFAULT_XDR_RW_LIMITS.len {
+                return Err(invalid_input());
+            }
+            // The record mark is untrusted, so don't allocate the whole record
+            // upfront: the buffer only grows as the data actually arrives.
+            let mut record = Vec::new();
+            (&mut r)
+                .take(len as u64)
+                .read_to_end(&mut record)
+                .map_err(io_error)?;
+            if record.len() != len {
+                return Err(io_error(ErrorKind::UnexpectedEof.into()));
+            }
+            let limits = Limits {
+                len: record.len(),
+                ..DEFAULT_XDR_RW_LIMITS
+            };
+            match LedgerEntry::from_xdr(record, limits)? {
+                LedgerEntry {
+                    data: LedgerEntryData::Ttl(ttl),
+                    ..
+                } => {
+                    if ttls
+                        .insert(ttl.key_hash, ttl.live_until_ledger_seq)
+                        .is_some()
+                    {
+                        return Err(invalid_input());
+                    }
+                }
+                entry => entries.push(entry),
+            }
+        }
+
+        let budget = Budget::try_new_unlimited()?;
+        let mut snapshot = Self::new();
+        for entry in entries {
+            let key = ledger_entry_to_ledger_key(&entry, &budget)?;
+            let live_until_ledger = ttls.remove(&ledger_key_hash(&key)?);
+            let needs_ttl = matches!(
+                entry.data,
+                LedgerEntryData::ContractData(_) | LedgerEntryData::ContractCode(_)
+            );
+            if needs_ttl != live_until_ledger.is_some() {
+                return Err(invalid_input());
+            }
+            if snapshot
+                .entries
+                .insert(Rc::new(key), (Rc::new(entry), live_until_ledger))
+                .is_some()
+            {
+                return Err(invalid_input());
+            }
+        }
+        // Every TTL record must belong to an entry.
+        if !ttls.is_empty() {
+            return Err(invalid_input());
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot in the XDR format. Every entry with a live until
+    /// ledger is followed by its `Ttl` record.
+    pub fn write_xdr<W: Write>(&self, mut w: W) -> Result<(), HostError> {
+        let mut write_record = |entry: &LedgerEntry| -> Result<(), HostError> {
+            let record = entry.to_xdr(DEFAULT_XDR_RW_LIMITS)?;
+            let len = u32::try_from(record.len())
+                .ok()
+                .filter(|len| len & LAST_FRAGMENT_BIT == 0)
+                .ok_or_else(invalid_input)?;
+            w.write_all(&(len | LAST_FRAGMENT_BIT).to_be_bytes())
+                .map_err(io_error)?;
+            w.write_all(&record).map_err(io_error)
+        };
+        for (key, (entry, live_until_ledger)) in self.entries.iter() {
+            write_record(entry)?;
+            if let Some(live_until_ledger) = live_until_ledger {
+                write_record(&LedgerEntry {
+                    last_modified_ledger_seq: entry.last_modified_ledger_seq,
+                    data: LedgerEntryData::Ttl(TtlEntry {
+                        key_hash: ledger_key_hash(key)?,
+                        live_until_ledger_seq: *live_until_ledger,
+                    }),
+                    ext: LedgerEntryExt::V0,
+                })?;
+            }
+        }
+        w.flush().map_err(io_error)
+    }
+
+    /// Reads a snapshot in the JSON format.
+    #[cfg(feature = "serde")]
+    pub fn read_json<R: Read>(r: R) -> Result<Self, HostError> {
+        let json: JsonSnapshot =
+            serde_json::from_reader(r).map_err(|e| io_error(std::io::Error::from(e)))?;
+        let mut snapshot = Self::new();
+        for JsonSnapshotEntry {
+            entry,
+            live_until_ledger,
+        } in json.entries
+        {
+            let entry = LedgerEntry::from_xdr_base64(entry, DEFAULT_XDR_RW_LIMITS)?;
+            snapshot.insert(entry, live_until_ledger)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot in the JSON format.
+    #[cfg(feature = "serde")]
+    pub fn write_json<W: Write>(&self, mut w: W) -> Result<(), HostError> {
+        let entries = self
+            .entries
+            .values()
+            .map(|(entry, live_until_ledger)| {
+                Ok(JsonSnapshotEntry {
+                    entry: entry.to_xdr_base64(DEFAULT_XDR_RW_LIMITS)?,
+                    live_until_ledger: *live_until_ledger,
+                })
+            })
+            .collect::<Result<Vec<_>, HostError>>()?;
+        serde_json::to_writer_pretty(&mut w, &JsonSnapshot { entries })
+            .map_err(|e| io_error(std::io::Error::from(e)))?;
+        w.flush().map_err(io_error)
+    }
+
+    /// Applies the ledger changes of an invocation (as returned by
+    /// [crate::e2e_invoke::get_ledger_changes]) to the snapshot: read-write
+    /// entries are updated or removed, and the live until ledgers of all the
+    /// entries are updated.
+    pub fn apply_ledger_changes(&mut self, changes: &[LedgerEntryChange]) -> Result<(), HostError> {
+        for change in changes {
+            let key = LedgerKey::from_xdr(&change.encoded_key, DEFAULT_XDR_RW_LIMITS)?;
+            let new_live_until_ledger = change
+                .ttl_change
+                .as_ref()
+                .map(|ttl_change| ttl_change.new_live_until_ledger);
+            if change.read_only {
+                if let (Some(new_live_until_ledger), Some((_, live_until_ledger))) =
+                    (new_live_until_ledger, self.entries.get_mut(&key))
+                {
+                    *live_until_ledger = Some(new_live_until_ledger);
+                }
+            } else if let Some(encoded_new_value) = &change.encoded_new_value {
+                let entry = LedgerEntry::from_xdr(encoded_new_value, DEFAULT_XDR_RW_LIMITS)?;
+                self.entries
+                    .insert(Rc::new(key), (Rc::new(entry), new_live_until_ledger));
+            } else {
+                self.entries.remove(&key);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SnapshotSource for LedgerSnapshot {
+    fn get(&self, key: &Rc<LedgerKey>) -> Result<EntryWithLiveUntil, HostError> {
+        if let Some((entry, live_until_ledger)) = self.entries.get(key) {
+            Ok((Rc::clone(entry), *live_until_ledger))
+        } else {
+            Err(Error::from_type_and_code(ScErrorType::Storage, ScErrorCode::MissingValue).into())
+        }
+    }
+
+    fn has(&self, key: &Rc<LedgerKey>) -> Result<bool, HostError> {
+        Ok(self.entries.contains_key(key))
+    }
+}
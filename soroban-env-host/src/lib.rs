@@ -32,6 +32,7 @@ pub(crate) mod host_object;
 mod builtin_contracts;
 
 pub mod auth;
+pub mod auth_signing;
 pub mod vm;
 pub use vm::Vm;
 pub mod storage;
@@ -42,8 +43,12 @@ pub use host::{
 };
 pub use soroban_env_common::*;
 
+pub mod contract_spec;
 pub mod e2e_invoke;
 pub mod fees;
+pub mod layered_snapshot;
+pub mod ledger_snapshot;
+pub mod network_config;
 
 #[cfg(feature = "bench")]
 #[doc(hidden)]
@@ -0,0 +1,309 @@
+//! This module builds the host configuration (fee configurations, budget and
+//! entry TTL bounds) from the network configuration settings, as stored in
+//! the ledger in `ConfigSettingEntry` entries.
+//!
+//! Every setting is validated before use: negative fee rates and limits,
+//! per-transaction limits exceeding the respective per-ledger limits and
+//! inconsistent TTL bounds are all rejected with `InvalidInput` errors.
+
+use crate::{
+    budget::Budget,
+    fees::{
+        compute_write_fee_per_1kb, FeeConfiguration, RentFeeConfiguration, WriteFeeConfiguration,
+    },
+    xdr::{
+        ConfigSettingContractBandwidthV0, ConfigSettingContractComputeV0,
+        ConfigSettingContractEventsV0, ConfigSettingContractHistoricalDataV0,
+        ConfigSettingContractLedgerCostV0, ConfigSettingEntry, ContractCostParams, ScErrorCode,
+        ScErrorType, StateArchivalSettings,
+    },
+    HostError,
+};
+
+fn invalid_setting() -> HostError {
+    (ScErrorType::Context, ScErrorCode::InvalidInput).into()
+}
+
+fn ensure(condition: bool) -> Result<(), HostError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(invalid_setting())
+    }
+}
+
+fn ensure_non_negative(values: &[i64]) -> Result<(), HostError> {
+    ensure(values.iter().all(|v| *v >= 0))
+}
+
+fn validate_compute(compute: &ConfigSettingContractComputeV0) -> Result<(), HostError> {
+    ensure_non_negative(&[
+        compute.ledger_max_instructions,
+        compute.tx_max_instructions,
+        compute.fee_rate_per_instructions_increment,
+    ])?;
+    ensure(compute.tx_max_instructions <= compute.ledger_max_instructions)
+}
+
+fn validate_ledger_cost(ledger_cost: &ConfigSettingContractLedgerCostV0) -> Result<(), HostError> {
+    ensure_non_negative(&[
+        ledger_cost.fee_read_ledger_entry,
+        ledger_cost.fee_write_ledger_entry,
+        ledger_cost.fee_read1_kb,
+        ledger_cost.write_fee1_kb_bucket_list_low,
+        ledger_cost.write_fee1_kb_bucket_list_high,
+    ])?;
+    ensure(ledger_cost.bucket_list_target_size_bytes > 0)?;
+    ensure(
+        ledger_cost.write_fee1_kb_bucket_list_low <= ledger_cost.write_fee1_kb_bucket_list_high,
+    )?;
+    ensure(
+        ledger_cost.tx_max_read_ledger_entries <= ledger_cost.ledger_max_read_ledger_entries
+            && ledger_cost.tx_max_read_bytes <= ledger_cost.ledger_max_read_bytes
+            && ledger_cost.tx_max_write_ledger_entries
+                <= ledger_cost.ledger_max_write_ledger_entries
+            && ledger_cost.tx_max_write_bytes <= ledger_cost.ledger_max_write_bytes,
+    )?;
+    // Written entries are read as well.
+    ensure(ledger_cost.tx_max_write_ledger_entries <= ledger_cost.tx_max_read_ledger_entries)
+}
+
+fn validate_state_archival(state_archival: &StateArchivalSettings) -> Result<(), HostError> {
+    ensure(
+        state_archival.persistent_rent_rate_denominator > 0
+            && state_archival.temp_rent_rate_denominator > 0,
+    )?;
+    ensure(state_archival.min_temporary_ttl > 0 && state_archival.min_persistent_ttl > 0)?;
+    ensure(
+        state_archival.min_temporary_ttl <= state_archival.max_entry_ttl
+            && state_archival.min_persistent_ttl <= state_archival.max_entry_ttl,
+    )
+}
+
+impl WriteFeeConfiguration {
+    /// Builds the write fee configuration from the ledger cost settings.
+    pub fn try_from_config_setting(
+        ledger_cost: &ConfigSettingContractLedgerCostV0,
+    ) -> Result<Self, HostError> {
+        validate_ledger_cost(ledger_cost)?;
+        Ok(Self {
+            bucket_list_target_size_bytes: ledger_cost.bucket_list_target_size_bytes,
+            write_fee_1kb_bucket_list_low: ledger_cost.write_fee1_kb_bucket_list_low,
+            write_fee_1kb_bucket_list_high: ledger_cost.write_fee1_kb_bucket_list_high,
+            bucket_list_write_fee_growth_factor: ledger_cost.bucket_list_write_fee_growth_factor,
+        })
+    }
+}
+
+impl FeeConfiguration {
+    /// Builds the fee configuration from the respective settings.
+    ///
+    /// `fee_per_write_1kb` has to be computed via `compute_write_fee_per_1kb`
+    /// (see `NetworkConfig` that does that based on the bucket list size
+    /// window setting).
+    pub fn try_from_config_settings(
+        compute: &ConfigSettingContractComputeV0,
+        ledger_cost: &ConfigSettingContractLedgerCostV0,
+        historical_data: &ConfigSettingContractHistoricalDataV0,
+        events: &ConfigSettingContractEventsV0,
+        bandwidth: &ConfigSettingContractBandwidthV0,
+        fee_per_write_1kb: i64,
+    ) -> Result<Self, HostError> {
+        validate_compute(compute)?;
+        validate_ledger_cost(ledger_cost)?;
+        ensure_non_negative(&[
+            historical_data.fee_historical1_kb,
+            events.fee_contract_events1_kb,
+            bandwidth.fee_tx_size1_kb,
+            fee_per_write_1kb,
+        ])?;
+        ensure(bandwidth.tx_max_size_bytes <= bandwidth.ledger_max_txs_size_bytes)?;
+        Ok(Self {
+            fee_per_instruction_increment: compute.fee_rate_per_instructions_increment,
+            fee_per_read_entry: ledger_cost.fee_read_ledger_entry,
+            fee_per_write_entry: ledger_cost.fee_write_ledger_entry,
+            fee_per_read_1kb: ledger_cost.fee_read1_kb,
+            fee_per_write_1kb,
+            fee_per_historical_1kb: historical_data.fee_historical1_kb,
+            fee_per_contract_event_1kb: events.fee_contract_events1_kb,
+            fee_per_transaction_size_1kb: bandwidth.fee_tx_size1_kb,
+        })
+    }
+}
+
+impl RentFeeConfiguration {
+    /// Builds the rent fee configuration from the respective settings.
+    ///
+    /// `fee_per_write_1kb` is the same value as in `FeeConfiguration`.
+    pub fn try_from_config_settings(
+        ledger_cost: &ConfigSettingContractLedgerCostV0,
+        state_archival: &StateArchivalSettings,
+        fee_per_write_1kb: i64,
+    ) -> Result<Self, HostError> {
+        validate_ledger_cost(ledger_cost)?;
+        validate_state_archival(state_archival)?;
+        ensure_non_negative(&[fee_per_write_1kb])?;
+        Ok(Self {
+            fee_per_write_1kb,
+            fee_per_write_entry: ledger_cost.fee_write_ledger_entry,
+            persistent_rent_rate_denominator: state_archival.persistent_rent_rate_denominator,
+            temporary_rent_rate_denominator: state_archival.temp_rent_rate_denominator,
+        })
+    }
+}
+
+impl Budget {
+    /// Initializes the budget with the per-transaction limits of the compute
+    /// settings and the cost parameters settings.
+    pub fn try_from_config_settings(
+        compute: &ConfigSettingContractComputeV0,
+        cpu_cost_params: ContractCostParams,
+        mem_cost_params: ContractCostParams,
+    ) -> Result<Self, HostError> {
+        validate_compute(compute)?;
+        Budget::try_from_configs(
+            compute.tx_max_instructions as u64,
+            compute.tx_memory_limit as u64,
+            cpu_cost_params,
+            mem_cost_params,
+        )
+    }
+}
+
+/// Host configuration derived from the network configuration settings.
+pub struct NetworkConfig {
+    /// Configuration for `compute_transaction_resource_fee`.
+    pub fee_configuration: FeeConfiguration,
+    /// Configuration for `compute_rent_fee`.
+    pub rent_fee_configuration: RentFeeConfiguration,
+    /// Configuration for `compute_write_fee_per_1kb`.
+    pub write_fee_configuration: WriteFeeConfiguration,
+    /// Average bucket list size over the bucket list size window, used to
+    /// compute the write fee.
+    pub average_bucket_list_size: u64,
+    /// Per-transaction CPU instructions limit.
+    pub tx_max_instructions: u64,
+    /// Per-transaction memory limit.
+    pub tx_memory_limit: u64,
+    /// Per-transaction limit on the total size of the contract events, to be
+    /// used in `Host::set_contract_events_size_limit`.
+    pub tx_max_contract_events_size_bytes: u32,
+    /// CPU instructions cost parameters of the budget.
+    pub cpu_cost_params: ContractCostParams,
+    /// Memory cost parameters of the budget.
+    pub mem_cost_params: ContractCostParams,
+    /// Minimum TTL of temporary entries, to be used in `LedgerInfo`.
+    pub min_temp_entry_ttl: u32,
+    /// Minimum TTL of persistent entries, to be used in `LedgerInfo`.
+    pub min_persistent_entry_ttl: u32,
+    /// Maximum TTL of any entry, to be used in `LedgerInfo`.
+    pub max_entry_ttl: u32,
+}
+
+impl NetworkConfig {
+    /// Builds the host configuration from the network configuration settings.
+    ///
+    /// `settings` must contain exactly one entry for each of the compute,
+    /// ledger cost, historical data, events, bandwidth, CPU and memory cost
+    /// params, state archival and bucket list size window settings; other
+    /// settings are ignored. A missing setting is a `MissingValue` error and
+    /// a duplicate one is an `ExistingValue` error.
+    pub fn try_from_config_settings(settings: &[ConfigSettingEntry]) -> Result<Self, HostError> {
+        fn set<T: Clone>(slot: &mut Option<T>, value: &T) -> Result<(), HostError> {
+            if slot.is_some() {
+                return Err((ScErrorType::Context, ScErrorCode::ExistingValue).into());
+            }
+            *slot = Some(value.clone());
+            Ok(())
+        }
+        fn get<T>(slot: Option<T>) -> Result<T, HostError> {
+            slot.ok_or_else(|| (ScErrorType::Context, ScErrorCode::MissingValue).into())
+        }
+
+        let mut compute = None;
+        let mut ledger_cost = None;
+        let mut historical_data = None;
+        let mut events = None;
+        let mut bandwidth = None;
+        let mut cpu_cost_params = None;
+        let mut mem_cost_params = None;
+        let mut state_archival = None;
+        let mut bucket_list_size_window = None;
+        for setting in settings {
+            match setting {
+                ConfigSettingEntry::ContractComputeV0(s) => set(&mut compute, s)?,
+                ConfigSettingEntry::ContractLedgerCostV0(s) => set(&mut ledger_cost, s)?,
+                ConfigSettingEntry::ContractHistoricalDataV0(s) => set(&mut historical_data, s)?,
+                ConfigSettingEntry::ContractEventsV0(s) => set(&mut events, s)?,
+                ConfigSettingEntry::ContractBandwidthV0(s) => set(&mut bandwidth, s)?,
+                ConfigSettingEntry::ContractCostParamsCpuInstructions(s) => {
+                    set(&mut cpu_cost_params, s)?
+                }
+                ConfigSettingEntry::ContractCostParamsMemoryBytes(s) => {
+                    set(&mut mem_cost_params, s)?
+                }
+                ConfigSettingEntry::StateArchival(s) => set(&mut state_archival, s)?,
+                ConfigSettingEntry::BucketlistSizeWindow(s) => {
+                    set(&mut bucket_list_size_window, s)?
+                }
+                _ => (),
+            }
+        }
+        let compute = get(compute)?;
+        let ledger_cost = get(ledger_cost)?;
+        let state_archival = get(state_archival)?;
+        let bucket_list_size_window = get(bucket_list_size_window)?;
+
+        ensure(!bucket_list_size_window.is_empty())?;
+        let average_bucket_list_size = bucket_list_size_window
+            .iter()
+            .fold(0_u128, |sum, size| sum.saturating_add(*size as u128))
+            / bucket_list_size_window.len() as u128;
+        let average_bucket_list_size = average_bucket_list_size as u64;
+
+        let write_fee_configuration = WriteFeeConfiguration::try_from_config_setting(&ledger_cost)?;
+        let fee_per_write_1kb = compute_write_fee_per_1kb(
+            i64::try_from(average_bucket_list_size).map_err(|_| invalid_setting())?,
+            &write_fee_configuration,
+        );
+        let events = get(events)?;
+        let fee_configuration = FeeConfiguration::try_from_config_settings(
+            &compute,
+            &ledger_cost,
+            &get(historical_data)?,
+            &events,
+            &get(bandwidth)?,
+            fee_per_write_1kb,
+        )?;
+        let rent_fee_configuration = RentFeeConfiguration::try_from_config_settings(
+            &ledger_cost,
+            &state_archival,
+            fee_per_write_1kb,
+        )?;
+        Ok(Self {
+            fee_configuration,
+            rent_fee_configuration,
+            write_fee_configuration,
+            average_bucket_list_size,
+            tx_max_instructions: compute.tx_max_instructions as u64,
+            tx_memory_limit: compute.tx_memory_limit as u64,
+            tx_max_contract_events_size_bytes: events.tx_max_contract_events_size_bytes,
+            cpu_cost_params: get(cpu_cost_params)?,
+            mem_cost_params: get(mem_cost_params)?,
+            min_temp_entry_ttl: state_archival.min_temporary_ttl,
+            min_persistent_entry_ttl: state_archival.min_persistent_ttl,
+            max_entry_ttl: state_archival.max_entry_ttl,
+        })
+    }
+
+    /// Creates a fresh budget with the per-transaction limits and the cost
+    /// parameters of this configuration.
+    pub fn create_budget(&self) -> Result<Budget, HostError> {
+        Budget::try_from_configs(
+            self.tx_max_instructions,
+            self.tx_memory_limit,
+            self.cpu_cost_params.clone(),
+            self.mem_cost_params.clone(),
+        )
+    }
+}
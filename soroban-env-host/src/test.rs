@@ -2,13 +2,16 @@ pub(crate) mod observe;
 
 mod address;
 mod auth;
+mod auth_signing;
 mod basic;
 mod budget_metering;
 mod bytes;
 mod complex;
+mod contract_spec;
 mod crypto;
 mod depth_limit;
 mod dispatch;
+mod e2e_tests;
 mod event;
 mod finish;
 mod frame;
@@ -17,12 +20,16 @@ mod hostile;
 #[cfg(opt_build)]
 mod hostile_opt;
 mod invocation;
+mod layered_snapshot;
 mod ledger;
+mod ledger_snapshot;
 mod lifecycle;
 mod linear_memory;
 mod map;
 #[cfg(feature = "testutils")]
 mod metering_benchmark;
+mod module_cache;
+mod network_config;
 mod num;
 mod post_mvp;
 mod prng;
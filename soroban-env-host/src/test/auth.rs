@@ -2,10 +2,11 @@ use ed25519_dalek::SigningKey;
 use rand::Rng;
 use soroban_builtin_sdk_macros::contracttype;
 use soroban_env_common::xdr::{
-    AccountId, ContractDataDurability, HashIdPreimage, HashIdPreimageSorobanAuthorization,
-    InvokeContractArgs, PublicKey, ScAddress, ScBytes, ScErrorCode, ScErrorType, ScNonceKey,
-    ScSymbol, ScVal, SorobanAddressCredentials, SorobanAuthorizationEntry,
-    SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials, Uint256, VecM,
+    AccountId, ContractDataDurability, ContractEventBody, HashIdPreimage,
+    HashIdPreimageSorobanAuthorization, InvokeContractArgs, PublicKey, ScAddress, ScBytes,
+    ScErrorCode, ScErrorType, ScMap, ScNonceKey, ScSymbol, ScVal, SorobanAddressCredentials,
+    SorobanAuthorizationEntry, SorobanAuthorizedFunction, SorobanAuthorizedInvocation,
+    SorobanCredentials, Uint256, VecM,
 };
 use soroban_test_wasms::{
     AUTH_TEST_CONTRACT, CONDITIONAL_ACCOUNT_TEST_CONTRACT, DELEGATED_ACCOUNT_TEST_CONTRACT,
@@ -17,7 +18,7 @@ use crate::builtin_contracts::base_types::Address;
 use crate::builtin_contracts::testutils::{
     create_account, generate_signing_key, sign_payload_for_account, signing_key_to_account_id,
 };
-use crate::{Host, LedgerInfo};
+use crate::{Host, HostError, LedgerInfo};
 use soroban_env_common::{AddressObject, Env, Symbol, SymbolStr, TryFromVal, TryIntoVal};
 
 use crate::builtin_contracts::base_types::Vec as HostVec;
@@ -230,6 +231,26 @@ impl AuthTest {
         sign_payloads: Vec<Vec<SignNode>>,
         success: bool,
     ) {
+        let contract_auth = self.sign_auth_entries(sign_payloads);
+        self.host.set_authorization_entries(contract_auth).unwrap();
+        let res = self
+            .host
+            .call(contract_address.into(), fn_name, args.into());
+        if res.is_err() && success {
+            eprintln!(
+                "invocation failed while success expected, error: {:?}",
+                res.clone().err().unwrap()
+            );
+        }
+        assert_eq!(res.is_ok(), success);
+    }
+
+    // Builds signed authorization entries for the `SignNode`s of every signer
+    // in the setup and remembers their nonces in `last_nonces`.
+    fn sign_auth_entries(
+        &mut self,
+        sign_payloads: Vec<Vec<SignNode>>,
+    ) -> Vec<SorobanAuthorizationEntry> {
         let mut contract_auth = vec![];
         self.last_nonces.clear();
         for address_id in 0..self.keys.len() {
@@ -276,18 +297,7 @@ impl AuthTest {
             }
             self.last_nonces.push(curr_nonces);
         }
-
-        self.host.set_authorization_entries(contract_auth).unwrap();
-        let res = self
-            .host
-            .call(contract_address.into(), fn_name, args.into());
-        if res.is_err() && success {
-            eprintln!(
-                "invocation failed while success expected, error: {:?}",
-                res.clone().err().unwrap()
-            );
-        }
-        assert_eq!(res.is_ok(), success);
+        contract_auth
     }
 
     fn read_nonce_live_until(&self, address: &Address, nonce: i64) -> Option<u32> {
@@ -376,6 +386,30 @@ impl AuthTest {
         self.host.get_recorded_auth_payloads().unwrap()
     }
 
+    // Runs the tree test in recording mode, while enforcing the signed
+    // payloads provided in `sign_payloads`.
+    fn tree_run_recording_with_signed_entries(
+        &mut self,
+        root: &SetupNode,
+        sign_payloads: Vec<Vec<SignNode>>,
+    ) -> Result<Vec<RecordedAuthPayload>, HostError> {
+        let auth_entries = self.sign_auth_entries(sign_payloads);
+        self.host
+            .switch_to_recording_auth_with_entries(true, auth_entries)
+            .unwrap();
+        self.host.call(
+            root.contract_address.clone().into(),
+            Symbol::try_from_small_str("tree_fn").unwrap(),
+            test_vec![
+                &self.host,
+                self.get_addresses(),
+                self.convert_setup_tree(&root)
+            ]
+            .into(),
+        )?;
+        self.host.get_recorded_auth_payloads()
+    }
+
     fn test_recording_and_enforcing_no_auth(
         &mut self,
         contract_address: Address,
@@ -3210,3 +3244,366 @@ fn test_rollback_with_conditional_custom_account_auth() {
     // Third call still can't succeed and won't consume nonce.
     assert_eq!(test.read_nonce_live_until(&account, 666), None);
 }
+
+fn diagnostic_map(entries: Vec<(&str, ScVal)>) -> ScVal {
+    ScVal::Map(Some(
+        ScMap::sorted_from(
+            entries
+                .into_iter()
+                .map(|(k, v)| (ScVal::Symbol(ScSymbol(k.try_into().unwrap())), v)),
+        )
+        .unwrap(),
+    ))
+}
+
+fn diagnostic_sym(s: &str) -> ScVal {
+    ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+}
+
+fn diagnostic_vec(v: Vec<ScVal>) -> ScVal {
+    ScVal::Vec(Some(v.try_into().unwrap()))
+}
+
+fn tree_fn_diagnostic(contract: &Address, args: Vec<ScVal>) -> ScVal {
+    diagnostic_map(vec![
+        ("args", diagnostic_vec(args)),
+        (
+            "contract",
+            ScVal::Address(contract.to_sc_address().unwrap()),
+        ),
+        ("fn_name", diagnostic_sym("tree_fn")),
+    ])
+}
+
+fn last_auth_failure_diagnostic(host: &Host) -> ScVal {
+    host.get_events()
+        .unwrap()
+        .0
+        .iter()
+        .rev()
+        .find_map(|e| match &e.event.body {
+            ContractEventBody::V0(v0)
+                if v0.topics.first() == Some(&diagnostic_sym("auth_failure")) =>
+            {
+                Some(v0.data.clone())
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_auth_failure_diagnostics() {
+    let mut test = AuthTest::setup(1, 2);
+    let setup = SetupNode::new(&test.contracts[0], vec![true], vec![]);
+
+    // Root mismatches: wrong contract and wrong arguments.
+    test.tree_test_enforcing(
+        &setup,
+        vec![vec![
+            SignNode::tree_fn(&test.contracts[1], vec![]),
+            SignNode::new(
+                &test.contracts[0],
+                Symbol::try_from_small_str("tree_fn").unwrap(),
+                vec![ScVal::U32(1)].try_into().unwrap(),
+                vec![],
+            ),
+        ]],
+        false,
+    );
+    assert_eq!(
+        last_auth_failure_diagnostic(&test.host),
+        diagnostic_vec(vec![
+            tree_fn_diagnostic(&test.contracts[0], vec![]),
+            diagnostic_map(vec![
+                (
+                    "mismatches",
+                    diagnostic_vec(vec![diagnostic_map(vec![
+                        ("node", diagnostic_vec(vec![])),
+                        ("reason", diagnostic_sym("contract")),
+                    ])])
+                ),
+                (
+                    "tree",
+                    diagnostic_map(vec![
+                        ("exhausted", ScVal::Bool(false)),
+                        ("function", tree_fn_diagnostic(&test.contracts[1], vec![])),
+                        ("sub_invocations", diagnostic_vec(vec![])),
+                    ])
+                ),
+            ]),
+            diagnostic_map(vec![
+                (
+                    "mismatches",
+                    diagnostic_vec(vec![diagnostic_map(vec![
+                        ("node", diagnostic_vec(vec![])),
+                        ("reason", diagnostic_sym("args_len")),
+                    ])])
+                ),
+                (
+                    "tree",
+                    diagnostic_map(vec![
+                        ("exhausted", ScVal::Bool(false)),
+                        (
+                            "function",
+                            tree_fn_diagnostic(&test.contracts[0], vec![ScVal::U32(1)])
+                        ),
+                        ("sub_invocations", diagnostic_vec(vec![])),
+                    ])
+                ),
+            ]),
+        ])
+    );
+
+    // Sub-invocation mismatch: the root is matched, but the authorized
+    // sub-invocation is for the wrong contract.
+    let setup = SetupNode::new(
+        &test.contracts[0],
+        vec![true],
+        vec![SetupNode::new(&test.contracts[1], vec![true], vec![])],
+    );
+    test.tree_test_enforcing(
+        &setup,
+        vec![vec![SignNode::tree_fn(
+            &test.contracts[0],
+            vec![SignNode::tree_fn(&test.contracts[0], vec![])],
+        )]],
+        false,
+    );
+    assert_eq!(
+        last_auth_failure_diagnostic(&test.host),
+        diagnostic_vec(vec![
+            tree_fn_diagnostic(&test.contracts[1], vec![]),
+            diagnostic_map(vec![
+                (
+                    "mismatches",
+                    diagnostic_vec(vec![diagnostic_map(vec![
+                        ("node", diagnostic_vec(vec![ScVal::U32(0)])),
+                        ("reason", diagnostic_sym("contract")),
+                    ])])
+                ),
+                (
+                    "tree",
+                    diagnostic_map(vec![
+                        ("exhausted", ScVal::Bool(true)),
+                        ("function", tree_fn_diagnostic(&test.contracts[0], vec![])),
+                        (
+                            "sub_invocations",
+                            diagnostic_vec(vec![diagnostic_map(vec![
+                                ("exhausted", ScVal::Bool(false)),
+                                ("function", tree_fn_diagnostic(&test.contracts[0], vec![])),
+                                ("sub_invocations", diagnostic_vec(vec![])),
+                            ])])
+                        ),
+                    ])
+                ),
+            ]),
+        ])
+    );
+}
+
+#[test]
+fn test_recording_auth_with_signed_entries() {
+    let mut test = AuthTest::setup(2, 2);
+    let setup = SetupNode::new(
+        &test.contracts[0],
+        vec![true, true],
+        vec![SetupNode::new(
+            &test.contracts[1],
+            vec![false, true],
+            vec![],
+        )],
+    );
+
+    // The first address has signed its payload, so only the second address
+    // has its authorization recorded.
+    assert_eq!(
+        test.tree_run_recording_with_signed_entries(
+            &setup,
+            vec![vec![SignNode::tree_fn(&test.contracts[0], vec![])], vec![]],
+        )
+        .unwrap(),
+        vec![RecordedAuthPayload {
+            address: Some(test.key_to_sc_address(&test.keys[1])),
+            nonce: Some(0),
+            invocation: test.convert_sign_node(&SignNode::tree_fn(
+                &test.contracts[0],
+                vec![SignNode::tree_fn(&test.contracts[1], vec![])]
+            )),
+        }]
+    );
+    test.verify_nonces_consumed(vec![1, 0]);
+
+    // Both addresses have signed their payloads, nothing is recorded.
+    assert_eq!(
+        test.tree_run_recording_with_signed_entries(
+            &setup,
+            vec![
+                vec![SignNode::tree_fn(&test.contracts[0], vec![])],
+                vec![SignNode::tree_fn(
+                    &test.contracts[0],
+                    vec![SignNode::tree_fn(&test.contracts[1], vec![])]
+                )],
+            ],
+        )
+        .unwrap(),
+        vec![]
+    );
+    test.verify_nonces_consumed(vec![1, 1]);
+
+    // Signed payload doesn't cover the invocation: authorization of the
+    // first address is enforced and fails instead of being recorded.
+    let err = test
+        .tree_run_recording_with_signed_entries(
+            &setup,
+            vec![vec![SignNode::tree_fn(&test.contracts[1], vec![])], vec![]],
+        )
+        .err()
+        .unwrap();
+    assert!(err.error.is_type(ScErrorType::Auth));
+    test.verify_nonces_consumed(vec![0, 0]);
+
+    // The second address has signed only the root invocation, so the
+    // sub-invocation fails even though the first address is recorded.
+    let err = test
+        .tree_run_recording_with_signed_entries(
+            &setup,
+            vec![vec![], vec![SignNode::tree_fn(&test.contracts[0], vec![])]],
+        )
+        .err()
+        .unwrap();
+    assert!(err.error.is_type(ScErrorType::Auth));
+
+    // The signed entry of a custom account is enforced by calling its
+    // `__check_auth`, which in turn requires the authorization of the
+    // account's owner (the first classic account).
+    let account: Address = test
+        .host
+        .register_test_contract_wasm(DELEGATED_ACCOUNT_TEST_CONTRACT)
+        .try_into_val(&test.host)
+        .unwrap();
+    test.host
+        .call(
+            account.as_object(),
+            Symbol::try_from_small_str("init").unwrap(),
+            test_vec![&test.host, test.key_to_address(&test.keys[0])].as_object(),
+        )
+        .unwrap();
+    let network_id: crate::xdr::Hash = test
+        .host
+        .with_ledger_info(|li: &LedgerInfo| Ok(li.network_id))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let payload_hash = |invocation: &SorobanAuthorizedInvocation, nonce| {
+        test.host
+            .metered_hash_xdr(&HashIdPreimage::SorobanAuthorization(
+                HashIdPreimageSorobanAuthorization {
+                    network_id: network_id.clone(),
+                    invocation: invocation.clone(),
+                    nonce,
+                    signature_expiration_ledger: 1000,
+                },
+            ))
+            .unwrap()
+    };
+    let account_invocation = SorobanAuthorizedInvocation {
+        function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+            contract_address: test.contracts[0].to_sc_address().unwrap(),
+            function_name: "do_auth".try_into().unwrap(),
+            args: vec![
+                ScVal::Address(account.to_sc_address().unwrap()),
+                ScVal::U32(123),
+            ]
+            .try_into()
+            .unwrap(),
+        }),
+        sub_invocations: VecM::default(),
+    };
+    let account_entry = SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+            address: account.to_sc_address().unwrap(),
+            nonce: 1111,
+            signature: ScVal::Void,
+            signature_expiration_ledger: 1000,
+        }),
+        root_invocation: account_invocation.clone(),
+    };
+    let owner_invocation = SorobanAuthorizedInvocation {
+        function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+            contract_address: account.to_sc_address().unwrap(),
+            function_name: "__check_auth".try_into().unwrap(),
+            args: vec![ScVal::Bytes(ScBytes(
+                payload_hash(&account_invocation, 1111).try_into().unwrap(),
+            ))]
+            .try_into()
+            .unwrap(),
+        }),
+        sub_invocations: VecM::default(),
+    };
+    // Signs the owner's entry, either for its actual payload or for a wrong
+    // one.
+    let owner_entry = |nonce, sign_correct_payload: bool| {
+        let mut payload = payload_hash(&owner_invocation, nonce);
+        if !sign_correct_payload {
+            payload[0] ^= 1;
+        }
+        let signature_args = test_vec![
+            &test.host,
+            sign_payload_for_account(&test.host, &test.keys[0], &payload)
+        ];
+        SorobanAuthorizationEntry {
+            credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+                address: test.key_to_sc_address(&test.keys[0]),
+                nonce,
+                signature: ScVal::Vec(Some(
+                    test.host
+                        .vecobject_to_scval_vec(signature_args.into())
+                        .unwrap()
+                        .into(),
+                )),
+                signature_expiration_ledger: 1000,
+            }),
+            root_invocation: owner_invocation.clone(),
+        }
+    };
+    let do_auth = |auth_entries: Vec<SorobanAuthorizationEntry>| {
+        test.host
+            .switch_to_recording_auth_with_entries(true, auth_entries)
+            .unwrap();
+        test.host.call(
+            test.contracts[0].as_object(),
+            Symbol::try_from_small_str("do_auth").unwrap(),
+            test_vec![&test.host, account, 123_u32].as_object(),
+        )
+    };
+
+    // The owner's signature is checked for real.
+    let err = do_auth(vec![account_entry.clone(), owner_entry(2222, false)])
+        .err()
+        .unwrap();
+    assert!(err.error.is_type(ScErrorType::Auth));
+
+    do_auth(vec![account_entry, owner_entry(3333, true)]).unwrap();
+    assert_eq!(test.host.get_recorded_auth_payloads().unwrap(), vec![]);
+    // The enforced authorizations of both the account and its owner are
+    // reported as authenticated.
+    assert_eq!(
+        test.host.get_authenticated_authorizations().unwrap(),
+        vec![
+            (account.to_sc_address().unwrap(), account_invocation),
+            (test.key_to_sc_address(&test.keys[0]), owner_invocation),
+        ]
+    );
+    assert_eq!(test.read_nonce_live_until(&account, 1111), Some(1000));
+    assert_eq!(
+        test.read_nonce_live_until(
+            &test
+                .key_to_address(&test.keys[0])
+                .try_into_val(&test.host)
+                .unwrap(),
+            3333,
+        ),
+        Some(1000)
+    );
+}
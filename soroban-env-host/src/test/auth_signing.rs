@@ -0,0 +1,209 @@
+use ed25519_dalek::SigningKey;
+use rand::Rng;
+use soroban_env_common::{Env, Symbol, TryIntoVal};
+use soroban_test_wasms::AUTH_TEST_CONTRACT;
+
+use crate::{
+    auth::RecordedAuthPayload,
+    auth_signing::{
+        account_signature, network_id_from_passphrase, sign_account_authorization_entry,
+        sign_recorded_auth_payloads,
+    },
+    budget::AsBudget,
+    builtin_contracts::testutils::{
+        create_account, generate_signing_key, signing_key_to_account_id,
+    },
+    xdr::{
+        Hash, ScAddress, ScErrorCode, ScVal, SorobanAddressCredentials, SorobanAuthorizationEntry,
+        SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials,
+    },
+    AddressObject, Host, HostError, LedgerInfo,
+};
+
+fn setup_multisig_account(host: &Host) -> (AddressObject, Vec<SigningKey>) {
+    let keys: Vec<SigningKey> = (0..3).map(|_| generate_signing_key(host)).collect();
+    let account_id = signing_key_to_account_id(&keys[0]);
+    // All the three signers are needed to reach the medium threshold.
+    create_account(
+        host,
+        &account_id,
+        vec![(&keys[1], 1), (&keys[2], 1)],
+        100_000_000,
+        2,
+        [1, 0, 3, 0],
+        None,
+        None,
+        0,
+    );
+    let address = host
+        .add_host_object(ScAddress::Account(account_id))
+        .unwrap();
+    (address, keys)
+}
+
+fn call_do_auth(
+    host: &Host,
+    contract: AddressObject,
+    address: AddressObject,
+) -> Result<(), HostError> {
+    host.call(
+        contract,
+        Symbol::try_from_small_str("do_auth").unwrap(),
+        test_vec![host, address, 10_u32].into(),
+    )?;
+    Ok(())
+}
+
+// Records the auth payloads for `do_auth` call. The recording run writes the
+// recorded nonces to the storage of this host, so these are replaced with
+// fresh nonces (a separate simulation host would not need this).
+fn record_auth_payloads(
+    host: &Host,
+    contract: AddressObject,
+    address: AddressObject,
+) -> Vec<RecordedAuthPayload> {
+    host.switch_to_recording_auth(true).unwrap();
+    call_do_auth(host, contract, address).unwrap();
+    let mut payloads = host.get_recorded_auth_payloads().unwrap();
+    for payload in payloads.iter_mut() {
+        payload.nonce = Some(
+            host.with_test_prng(|chacha| Ok(chacha.gen_range(0..=i64::MAX)))
+                .unwrap(),
+        );
+    }
+    payloads
+}
+
+#[test]
+fn test_sign_recorded_auth_payloads() {
+    let host = Host::test_host_with_recording_footprint();
+    host.as_budget().reset_unlimited().unwrap();
+    host.with_mut_ledger_info(|li| {
+        li.sequence_number = 100;
+        li.max_entry_ttl = 10000;
+    })
+    .unwrap();
+    let contract = host.register_test_contract_wasm(AUTH_TEST_CONTRACT);
+    let (address, keys) = setup_multisig_account(&host);
+    let sc_address = host.scaddress_from_address(address).unwrap();
+    let network_id = host
+        .with_ledger_info(|li: &LedgerInfo| Ok(li.network_id))
+        .unwrap();
+
+    let payloads = record_auth_payloads(&host, contract, address);
+
+    // Signers may be provided in any order and with duplicates.
+    let signed = sign_recorded_auth_payloads(payloads, &network_id, 1000, |signer_address| {
+        assert_eq!(signer_address, &sc_address);
+        vec![
+            keys[2].clone(),
+            keys[0].clone(),
+            keys[1].clone(),
+            keys[0].clone(),
+        ]
+    })
+    .unwrap();
+    assert_eq!(signed.len(), 1);
+    let SorobanCredentials::Address(credentials) = &signed[0].credentials else {
+        panic!("unexpected credentials");
+    };
+    assert_eq!(credentials.signature_expiration_ledger, 1000);
+
+    host.set_authorization_entries(signed.clone()).unwrap();
+    call_do_auth(&host, contract, address).unwrap();
+
+    // Nonce has been consumed, so the same entries can't be reused.
+    host.set_authorization_entries(signed).unwrap();
+    assert!(call_do_auth(&host, contract, address).is_err());
+
+    // Signers don't reach the medium threshold.
+    let payloads = record_auth_payloads(&host, contract, address);
+    let signed = sign_recorded_auth_payloads(payloads, &network_id, 1000, |_| {
+        vec![keys[0].clone(), keys[1].clone()]
+    })
+    .unwrap();
+    host.set_authorization_entries(signed).unwrap();
+    assert!(call_do_auth(&host, contract, address).is_err());
+
+    // Signing for a different network fails the authentication.
+    let payloads = record_auth_payloads(&host, contract, address);
+    let signed = sign_recorded_auth_payloads(
+        payloads,
+        &network_id_from_passphrase("Some other network"),
+        1000,
+        |_| keys.clone(),
+    )
+    .unwrap();
+    host.set_authorization_entries(signed).unwrap();
+    assert!(call_do_auth(&host, contract, address).is_err());
+}
+
+#[test]
+fn test_account_signature_is_sorted_and_deduplicated() {
+    let host = Host::test_host();
+    let keys: Vec<SigningKey> = (0..4).map(|_| generate_signing_key(&host)).collect();
+    let signers = vec![
+        keys[3].clone(),
+        keys[1].clone(),
+        keys[3].clone(),
+        keys[0].clone(),
+        keys[2].clone(),
+    ];
+    let signature = account_signature(&[7; 32], &signers).unwrap();
+
+    let mut expected_keys: Vec<[u8; 32]> =
+        keys.iter().map(|k| k.verifying_key().to_bytes()).collect();
+    expected_keys.sort();
+    let ScVal::Vec(Some(signatures)) = signature else {
+        panic!("unexpected signature");
+    };
+    let public_keys: Vec<[u8; 32]> = signatures
+        .iter()
+        .map(|s| {
+            let ScVal::Map(Some(m)) = s else {
+                panic!("unexpected signature entry");
+            };
+            let ScVal::Bytes(b) = &m[0].val else {
+                panic!("unexpected public key");
+            };
+            b.as_slice().try_into().unwrap()
+        })
+        .collect();
+    assert_eq!(public_keys, expected_keys);
+}
+
+#[test]
+fn test_sign_authorization_entry_requires_account_address() {
+    let host = Host::test_host();
+    let key = generate_signing_key(&host);
+    let entry = SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+            address: ScAddress::Contract(Hash([1; 32])),
+            nonce: 123,
+            signature_expiration_ledger: 0,
+            signature: ScVal::Void,
+        }),
+        root_invocation: SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(crate::xdr::InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash([1; 32])),
+                function_name: "foo".try_into().unwrap(),
+                args: Default::default(),
+            }),
+            sub_invocations: Default::default(),
+        },
+    };
+    let err = sign_account_authorization_entry(&entry, &[0; 32], 1000, std::slice::from_ref(&key))
+        .err()
+        .unwrap();
+    assert!(err.error.is_code(ScErrorCode::InvalidInput));
+
+    // Source account entries don't need signatures.
+    let source_account_entry = SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::SourceAccount,
+        root_invocation: entry.root_invocation.clone(),
+    };
+    assert_eq!(
+        sign_account_authorization_entry(&source_account_entry, &[0; 32], 1000, &[key]).unwrap(),
+        source_account_entry
+    );
+}
@@ -81,3 +81,44 @@ fn f32_does_not_work() -> Result<(), HostError> {
     assert!(crate::vm::Vm::new(&host, hash, soroban_test_wasms::ADD_F32).is_err());
     Ok(())
 }
+
+#[test]
+fn host_function_imports_are_gated_by_protocol() -> Result<(), HostError> {
+    use crate::vm::{
+        func_info::{HostFuncInfo, HOST_FUNCTIONS},
+        Vm,
+    };
+    use soroban_env_common::xdr::{Hash, ScErrorCode, ScErrorType};
+
+    let host = observe_host!(Host::test_host_with_recording_footprint());
+    let proto = host.get_ledger_protocol_version()?;
+    let wasm = soroban_test_wasms::CONTRACT_STORAGE;
+    let module = wasmi::Module::new(&wasmi::Engine::default(), wasm).unwrap();
+
+    // Every function in env.json is available in the current protocol.
+    Vm::check_host_function_imports(&host, &module, HOST_FUNCTIONS)?;
+    Vm::new(&host, Hash::from([0; 32]), wasm)?;
+
+    // The same imports are rejected if the functions are only available in
+    // a later protocol, or have been removed in an earlier one.
+    let with_bounds = |min_proto, max_proto| -> Vec<HostFuncInfo> {
+        HOST_FUNCTIONS
+            .iter()
+            .map(|hf| HostFuncInfo {
+                min_proto,
+                max_proto,
+                ..*hf
+            })
+            .collect()
+    };
+    for (min_proto, max_proto) in [(Some(proto + 1), None), (None, Some(proto - 1))] {
+        let err =
+            Vm::check_host_function_imports(&host, &module, &with_bounds(min_proto, max_proto))
+                .err()
+                .unwrap();
+        assert!(err.error.is_type(ScErrorType::WasmVm));
+        assert!(err.error.is_code(ScErrorCode::InvalidAction));
+    }
+    Vm::check_host_function_imports(&host, &module, &with_bounds(Some(proto), Some(proto)))?;
+    Ok(())
+}
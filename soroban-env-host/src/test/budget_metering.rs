@@ -433,3 +433,117 @@ fn total_amount_charged_from_random_inputs() -> Result<(), HostError> {
 
     Ok(())
 }
+
+#[test]
+fn call_tree_attributes_costs_to_frames() -> Result<(), HostError> {
+    use soroban_env_common::EnvBase;
+    use soroban_test_wasms::{ADD_I32, INVOKE_CONTRACT};
+
+    let run = |call_tree: bool| -> Result<(u64, Vec<_>), HostError> {
+        let host = Host::test_host_with_recording_footprint();
+        let id0_obj = host.register_test_contract_wasm(INVOKE_CONTRACT);
+        let id1_obj = host.register_test_contract_wasm(ADD_I32);
+        let args = host.test_vec_obj::<i32>(&[5, 6])?;
+        let args = host.vec_push_back(args, id1_obj.to_val())?;
+        let budget = host.budget_cloned();
+        budget.reset_unlimited()?;
+        if call_tree {
+            budget.enable_call_tree()?;
+        }
+        host.call(id0_obj, Symbol::try_from_small_str("add_with")?, args)?;
+        drop(host);
+        Ok((budget.get_cpu_insns_consumed()?, budget.get_call_tree()?))
+    };
+
+    let (cpu_without_tree, tree) = run(false)?;
+    assert!(tree.is_empty());
+    let (cpu_with_tree, tree) = run(true)?;
+    // Recording the call tree does not affect metering.
+    assert_eq!(cpu_without_tree, cpu_with_tree);
+
+    assert_eq!(tree.len(), 1);
+    let add_with = &tree[0];
+    assert_eq!(add_with.function, "add_with");
+    assert_eq!(add_with.children.len(), 1);
+    let add = &add_with.children[0];
+    assert_eq!(add.function, "add");
+    assert!(add.children.is_empty());
+    assert!(add_with.contract_id.is_some());
+    assert!(add.contract_id.is_some());
+    assert_ne!(add_with.contract_id, add.contract_id);
+
+    assert!(add.cpu_insns_inclusive > 0);
+    assert_eq!(add.cpu_insns_inclusive, add.cpu_insns_exclusive);
+    assert_eq!(
+        add_with.cpu_insns_inclusive,
+        add_with.cpu_insns_exclusive + add.cpu_insns_inclusive
+    );
+    assert_eq!(
+        add_with.mem_bytes_inclusive,
+        add_with.mem_bytes_exclusive + add.mem_bytes_inclusive
+    );
+    assert!(add_with.cpu_insns_inclusive <= cpu_with_tree);
+
+    let mut visited = vec![];
+    add_with.visit(&mut |depth, frame| visited.push((depth, frame.function.clone())));
+    assert_eq!(
+        visited,
+        vec![(0, "add_with".to_string()), (1, "add".to_string())]
+    );
+    Ok(())
+}
+
+#[test]
+fn call_tree_folded_stack_export() -> Result<(), HostError> {
+    use crate::budget::{write_folded_stacks, FoldedStackWeight};
+    use soroban_env_common::EnvBase;
+    use soroban_test_wasms::{ADD_I32, INVOKE_CONTRACT};
+
+    let host = Host::test_host_with_recording_footprint();
+    let id0_obj = host.register_test_contract_wasm(INVOKE_CONTRACT);
+    let id1_obj = host.register_test_contract_wasm(ADD_I32);
+    let args = host.test_vec_obj::<i32>(&[5, 6])?;
+    let args = host.vec_push_back(args, id1_obj.to_val())?;
+    host.as_budget().enable_call_tree()?;
+    host.call(id0_obj, Symbol::try_from_small_str("add_with")?, args)?;
+    let tree = host.as_budget().get_call_tree()?;
+
+    let root = &tree[0];
+    let add = &root.children[0];
+    // Each frame's exclusive cost is broken down by cost type.
+    root.visit(&mut |_, frame| {
+        assert_eq!(
+            frame.cost_types.values().map(|t| t.cpu).sum::<u64>(),
+            frame.cpu_insns_exclusive
+        );
+        assert_eq!(
+            frame.cost_types.values().map(|t| t.mem).sum::<u64>(),
+            frame.mem_bytes_exclusive
+        );
+    });
+
+    for (weight, total) in [
+        (FoldedStackWeight::CpuInsns, root.cpu_insns_inclusive),
+        (FoldedStackWeight::MemBytes, root.mem_bytes_inclusive),
+    ] {
+        let mut out = vec![];
+        write_folded_stacks(&tree, weight, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let mut sum = 0;
+        for line in out.lines() {
+            let (stack, count) = line.rsplit_once(' ').unwrap();
+            assert!(stack.starts_with(&format!("{};", root.label())));
+            let count: u64 = count.parse().unwrap();
+            assert!(count > 0);
+            sum += count;
+        }
+        assert_eq!(sum, total);
+        if weight == FoldedStackWeight::CpuInsns {
+            let prefix = format!("{};{};WasmInsnExec ", root.label(), add.label());
+            assert!(out.lines().any(|l| l.starts_with(&prefix)));
+        }
+    }
+    assert!(root.label().starts_with('C'));
+    assert!(root.label().ends_with(":add_with"));
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use std::rc::Rc;
+
+use soroban_env_common::{
+    xdr::{
+        ContractCostType, Limits, ScErrorCode, ScErrorType, ScSpecEntry, ScSpecTypeDef,
+        ScSpecTypeUdt, ScSpecUdtStructFieldV0, ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0,
+        ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0, ScSymbol, ScVal,
+        WriteXdr,
+    },
+    Env, EnvBase, TryFromVal,
+};
+use soroban_test_wasms::ADD_I32;
+
+use crate::{
+    budget::{AsBudget, Budget},
+    contract_spec::{ContractSpec, SpecMismatch, CONTRACT_SPEC_SECTION_NAME},
+    events::HostEvent,
+    xdr::ContractEventType,
+    Host, HostError, Symbol, Vm,
+};
+
+fn sym(s: &str) -> ScVal {
+    ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+}
+
+#[test]
+fn decode_spec_from_wasm() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let vm = Vm::new(&host, [0; 32].into(), ADD_I32)?;
+    let section = vm.custom_section(CONTRACT_SPEC_SECTION_NAME).unwrap();
+    let spec = ContractSpec::from_xdr_stream(&Budget::default(), section)?;
+    let add = spec.function("add").unwrap();
+    assert_eq!(add.inputs.len(), 2);
+    assert_eq!(add.inputs[0].name.to_utf8_string_lossy(), "a");
+    assert_eq!(add.inputs[0].type_, ScSpecTypeDef::I32);
+    assert_eq!(
+        spec.check_args("add", &[ScVal::I32(1), ScVal::U32(2)]),
+        Err(SpecMismatch::ArgType {
+            index: 1,
+            name: "b".to_string()
+        })
+    );
+    assert_eq!(
+        spec.check_args("add", &[ScVal::I32(1)]),
+        Err(SpecMismatch::ArgCount {
+            expected: 2,
+            actual: 1
+        })
+    );
+    assert_eq!(
+        spec.check_args("add", &[ScVal::I32(1), ScVal::I32(2)]),
+        Ok(())
+    );
+    // Functions missing from the spec are not checked.
+    assert_eq!(spec.check_args("sub", &[]), Ok(()));
+    Ok(())
+}
+
+#[test]
+fn check_udt_args() -> Result<(), HostError> {
+    let point = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: Default::default(),
+        lib: Default::default(),
+        name: "Point".try_into().unwrap(),
+        fields: vec![
+            ScSpecUdtStructFieldV0 {
+                doc: Default::default(),
+                name: "x".try_into().unwrap(),
+                type_: ScSpecTypeDef::I32,
+            },
+            ScSpecUdtStructFieldV0 {
+                doc: Default::default(),
+                name: "y".try_into().unwrap(),
+                type_: ScSpecTypeDef::I32,
+            },
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    let shape = ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+        doc: Default::default(),
+        lib: Default::default(),
+        name: "Shape".try_into().unwrap(),
+        cases: vec![
+            ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                doc: Default::default(),
+                name: "Empty".try_into().unwrap(),
+            }),
+            ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                doc: Default::default(),
+                name: "At".try_into().unwrap(),
+                type_: vec![ScSpecTypeDef::Udt(ScSpecTypeUdt {
+                    name: "Point".try_into().unwrap(),
+                })]
+                .try_into()
+                .unwrap(),
+            }),
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    let mut section = point.to_xdr(Limits::none())?;
+    section.extend(shape.to_xdr(Limits::none())?);
+    let spec = ContractSpec::from_xdr_stream(&Budget::default(), &section)?;
+    let shape_ty = ScSpecTypeDef::Udt(ScSpecTypeUdt {
+        name: "Shape".try_into().unwrap(),
+    });
+
+    let point_val = |x: ScVal| {
+        ScVal::Map(Some(
+            vec![
+                crate::xdr::ScMapEntry {
+                    key: sym("x"),
+                    val: x,
+                },
+                crate::xdr::ScMapEntry {
+                    key: sym("y"),
+                    val: ScVal::I32(2),
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        ))
+    };
+    let vec_val = |v: Vec<ScVal>| ScVal::Vec(Some(v.try_into().unwrap()));
+
+    assert!(spec.value_matches(&shape_ty, &vec_val(vec![sym("Empty")])));
+    assert!(spec.value_matches(
+        &shape_ty,
+        &vec_val(vec![sym("At"), point_val(ScVal::I32(1))])
+    ));
+    assert!(!spec.value_matches(
+        &shape_ty,
+        &vec_val(vec![sym("At"), point_val(ScVal::U32(1))])
+    ));
+    assert!(!spec.value_matches(&shape_ty, &vec_val(vec![sym("Empty"), ScVal::Void])));
+    assert!(!spec.value_matches(&shape_ty, &vec_val(vec![sym("Circle")])));
+    Ok(())
+}
+
+#[test]
+fn mistyped_argument_is_rejected_before_entering_vm() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    host.enable_debug()?;
+    host.set_contract_spec_validation(true)?;
+    let contract = host.register_test_contract_wasm(ADD_I32);
+    let add = Symbol::try_from_small_str("add")?;
+
+    let args = host.vec_new_from_slice(&[1i32.into(), 2u32.into()])?;
+    let res = host.call(contract, add, args);
+    assert!(HostError::result_matches_err(
+        res,
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    // The diagnostic event for the failure names the offending argument.
+    let events = host.get_events()?.0;
+    assert!(events.iter().any(|HostEvent { event, .. }| {
+        let debug = format!("{:?}", event);
+        event.type_ == ContractEventType::Diagnostic
+            && debug.contains("argument does not match type in contract spec")
+            && debug.contains("Symbol(ScSymbol(StringM(b)))")
+    }));
+
+    let args = host.test_vec_obj(&[1i32])?;
+    let res = host.call(contract, add, args);
+    assert!(HostError::result_matches_err(
+        res,
+        (ScErrorType::Value, ScErrorCode::UnexpectedSize)
+    ));
+
+    let res = host.call(contract, add, host.test_vec_obj(&[3i32, 4i32])?)?;
+    assert_eq!(i32::try_from_val(&host, &res)?, 7);
+    Ok(())
+}
+
+// The validation is metered by the shadow budget and the spec is only
+// decoded once per cached module.
+#[test]
+fn validation_does_not_affect_metering() -> Result<(), HostError> {
+    let run = |validate: bool| -> Result<(u64, u64), HostError> {
+        let host = Host::test_host_with_recording_footprint();
+        host.set_contract_spec_validation(validate)?;
+        let contract = host.register_test_contract_wasm(ADD_I32);
+        let add = Symbol::try_from_small_str("add")?;
+        host.as_budget().reset_unlimited()?;
+        for _ in 0..2 {
+            let res = host.call(contract, add, host.test_vec_obj(&[3i32, 4i32])?)?;
+            assert_eq!(i32::try_from_val(&host, &res)?, 7);
+        }
+        let budget = host.as_budget();
+        assert_eq!(
+            budget.get_tracker(ContractCostType::ValDeser)?.iterations,
+            0
+        );
+        Ok((
+            budget.get_cpu_insns_consumed()?,
+            budget.get_mem_bytes_consumed()?,
+        ))
+    };
+    assert_eq!(run(true)?, run(false)?);
+
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(ADD_I32);
+    host.call(
+        contract,
+        Symbol::try_from_small_str("add")?,
+        host.test_vec_obj(&[3i32, 4i32])?,
+    )?;
+    let cache = host.module_cache()?;
+    let hash = crate::test::e2e_tests::wasm_hash(ADD_I32);
+    let vm = Vm::new(&host, [0; 32].into(), ADD_I32)?;
+    let spec = cache.get_contract_spec(&host, &hash, &vm)?;
+    assert!(spec.function("add").is_some());
+    assert!(Rc::ptr_eq(
+        &spec,
+        &cache.get_contract_spec(&host, &hash, &vm)?
+    ));
+    Ok(())
+}
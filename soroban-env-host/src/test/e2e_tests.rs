@@ -0,0 +1,741 @@
+use std::rc::Rc;
+
+use sha2::{Digest, Sha256};
+use soroban_test_wasms::{ADD_I32, CONTRACT_STORAGE};
+
+use crate::{
+    budget::Budget,
+    e2e_invoke::{
+        build_transaction_meta, estimate_resource_fee, extend_footprint_ttl,
+        ledger_entry_to_ledger_key, restore_footprint, simulate_invoke_host_function,
+        LedgerEntryChange, ResourceFeeMargins,
+    },
+    fees::{compute_transaction_resource_fee, FeeConfiguration, RentFeeConfiguration},
+    layered_snapshot::LayeredSnapshotSource,
+    storage::SnapshotSource,
+    testutils::MockSnapshotSource,
+    xdr::{
+        AccountId, ContractCodeEntry, ContractDataDurability, ContractDataEntry,
+        ContractExecutable, ContractIdPreimage, ContractIdPreimageFromAddress, CreateContractArgs,
+        ExtensionPoint, Hash, HostFunction, InvokeContractArgs, LedgerEntry,
+        LedgerEntryChange as XdrLedgerEntryChange, LedgerEntryData, LedgerEntryExt,
+        LedgerFootprint, LedgerKey, Limits, PublicKey, ScAddress, ScContractInstance, ScErrorCode,
+        ScErrorType, ScSymbol, ScVal, SorobanAuthorizedFunction, SorobanCredentials,
+        SorobanResources, TransactionMeta, TransactionMetaV3, TtlEntry, Uint256, WriteXdr,
+    },
+    LedgerInfo,
+};
+
+pub(crate) fn ledger_info() -> LedgerInfo {
+    LedgerInfo {
+        protocol_version: crate::meta::get_ledger_protocol_version(crate::meta::INTERFACE_VERSION),
+        sequence_number: 100,
+        timestamp: 12345,
+        network_id: [5; 32],
+        base_reserve: 1,
+        min_temp_entry_ttl: 16,
+        min_persistent_entry_ttl: 4096,
+        max_entry_ttl: 6_312_000,
+    }
+}
+
+pub(crate) fn source_account() -> AccountId {
+    AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([1; 32])))
+}
+
+pub(crate) fn wasm_hash(wasm: &[u8]) -> Hash {
+    Hash(Sha256::digest(wasm).into())
+}
+
+pub(crate) fn wasm_entry(wasm: &[u8]) -> LedgerEntry {
+    LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::ContractCode(ContractCodeEntry {
+            ext: ExtensionPoint::V0,
+            hash: wasm_hash(wasm),
+            code: wasm.try_into().unwrap(),
+        }),
+        ext: LedgerEntryExt::V0,
+    }
+}
+
+pub(crate) fn contract_instance_entry(contract_id: &Hash, wasm: &[u8]) -> LedgerEntry {
+    LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: ScAddress::Contract(contract_id.clone()),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+            val: ScVal::ContractInstance(ScContractInstance {
+                executable: ContractExecutable::Wasm(wasm_hash(wasm)),
+                storage: None,
+            }),
+        }),
+        ext: LedgerEntryExt::V0,
+    }
+}
+
+pub(crate) fn snapshot_with_contract(contract_id: &Hash, wasm: &[u8]) -> MockSnapshotSource {
+    MockSnapshotSource::from_entries(vec![
+        (wasm_entry(wasm), Some(1000)),
+        (contract_instance_entry(contract_id, wasm), Some(1000)),
+    ])
+}
+
+pub(crate) fn invoke_contract_fn(
+    contract_id: &Hash,
+    fn_name: &str,
+    args: Vec<ScVal>,
+) -> HostFunction {
+    HostFunction::InvokeContract(InvokeContractArgs {
+        contract_address: ScAddress::Contract(contract_id.clone()),
+        function_name: ScSymbol(fn_name.try_into().unwrap()),
+        args: args.try_into().unwrap(),
+    })
+}
+
+#[test]
+fn test_simulate_upload_wasm() {
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let res = simulate_invoke_host_function(
+        Rc::new(MockSnapshotSource::new()),
+        &budget,
+        true,
+        HostFunction::UploadContractWasm(ADD_I32.try_into().unwrap()),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut diagnostic_events,
+    )
+    .unwrap();
+    assert_eq!(
+        res.invoke_result.unwrap(),
+        ScVal::Bytes(wasm_hash(ADD_I32).0.to_vec().try_into().unwrap())
+    );
+    assert!(res.auth.is_empty());
+    assert!(res.contract_events.is_empty());
+    assert!(res.resources.footprint.read_only.is_empty());
+    assert_eq!(
+        res.resources.footprint.read_write.as_slice(),
+        &[LedgerKey::ContractCode(crate::xdr::LedgerKeyContractCode {
+            hash: wasm_hash(ADD_I32)
+        })]
+    );
+    assert!(res.resources.instructions > 0);
+    assert_eq!(res.resources.read_bytes, 0);
+    assert_eq!(res.ledger_changes.len(), 1);
+    assert_eq!(
+        res.resources.write_bytes as usize,
+        res.ledger_changes[0]
+            .encoded_new_value
+            .as_ref()
+            .unwrap()
+            .len()
+    );
+}
+
+#[test]
+fn test_simulate_create_contract_records_source_account_auth() {
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let create_args = CreateContractArgs {
+        contract_id_preimage: ContractIdPreimage::Address(ContractIdPreimageFromAddress {
+            address: ScAddress::Account(source_account()),
+            salt: Uint256([0; 32]),
+        }),
+        executable: ContractExecutable::Wasm(wasm_hash(ADD_I32)),
+    };
+    let res = simulate_invoke_host_function(
+        Rc::new(MockSnapshotSource::from_entries(vec![(
+            wasm_entry(ADD_I32),
+            Some(1000),
+        )])),
+        &budget,
+        false,
+        HostFunction::CreateContract(create_args.clone()),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut diagnostic_events,
+    )
+    .unwrap();
+    assert!(matches!(res.invoke_result, Ok(ScVal::Address(_))));
+    assert!(diagnostic_events.is_empty());
+    assert_eq!(res.auth.len(), 1);
+    assert_eq!(res.auth[0].credentials, SorobanCredentials::SourceAccount);
+    assert_eq!(
+        res.auth[0].root_invocation.function,
+        SorobanAuthorizedFunction::CreateContractHostFn(create_args)
+    );
+    assert_eq!(res.resources.footprint.read_only.len(), 1);
+    assert_eq!(res.resources.footprint.read_write.len(), 1);
+    assert!(res.resources.read_bytes > 0);
+    assert!(res.resources.write_bytes > 0);
+}
+
+#[test]
+fn test_simulate_invoke_contract() {
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let contract_id = Hash([3; 32]);
+    let res = simulate_invoke_host_function(
+        Rc::new(snapshot_with_contract(&contract_id, ADD_I32)),
+        &budget,
+        true,
+        invoke_contract_fn(&contract_id, "add", vec![ScVal::I32(3), ScVal::I32(4)]),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut diagnostic_events,
+    )
+    .unwrap();
+    assert_eq!(res.invoke_result.unwrap(), ScVal::I32(7));
+    assert!(res.auth.is_empty());
+    assert_eq!(res.resources.footprint.read_only.len(), 2);
+    assert!(res.resources.footprint.read_write.is_empty());
+    assert!(res.resources.read_bytes as usize > ADD_I32.len());
+    assert_eq!(res.resources.write_bytes, 0);
+    assert!(!diagnostic_events.is_empty());
+}
+
+#[test]
+fn test_simulate_invoke_contract_with_storage_write() {
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let contract_id = Hash([3; 32]);
+    let res = simulate_invoke_host_function(
+        Rc::new(snapshot_with_contract(&contract_id, CONTRACT_STORAGE)),
+        &budget,
+        false,
+        invoke_contract_fn(
+            &contract_id,
+            "put_persistent",
+            vec![
+                ScVal::Symbol(ScSymbol("key".try_into().unwrap())),
+                ScVal::U64(1234),
+            ],
+        ),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut diagnostic_events,
+    )
+    .unwrap();
+    assert_eq!(res.invoke_result.unwrap(), ScVal::Void);
+    assert_eq!(res.resources.footprint.read_only.len(), 2);
+    assert_eq!(res.resources.footprint.read_write.len(), 1);
+    assert!(res.resources.write_bytes > 0);
+    let new_entry_changes: Vec<_> = res
+        .ledger_changes
+        .iter()
+        .filter(|c| c.encoded_new_value.is_some())
+        .collect();
+    assert_eq!(new_entry_changes.len(), 1);
+    assert_eq!(new_entry_changes[0].old_entry_size_bytes, 0);
+}
+
+#[test]
+fn test_simulate_failed_invocation_returns_empty_result() {
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let contract_id = Hash([3; 32]);
+    let res = simulate_invoke_host_function(
+        Rc::new(MockSnapshotSource::new()),
+        &budget,
+        true,
+        invoke_contract_fn(&contract_id, "add", vec![ScVal::I32(3), ScVal::I32(4)]),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut diagnostic_events,
+    )
+    .unwrap();
+    assert!(res.invoke_result.is_err());
+    assert!(res.auth.is_empty());
+    assert!(res.ledger_changes.is_empty());
+    assert!(res.resources.footprint.read_only.is_empty());
+    assert!(res.resources.footprint.read_write.is_empty());
+    assert_eq!(res.resources.instructions, 0);
+    assert!(!diagnostic_events.is_empty());
+}
+
+fn temp_data_entry(contract_id: &Hash) -> LedgerEntry {
+    LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: ScAddress::Contract(contract_id.clone()),
+            key: ScVal::U32(1),
+            durability: ContractDataDurability::Temporary,
+            val: ScVal::U32(2),
+        }),
+        ext: LedgerEntryExt::V0,
+    }
+}
+
+// Builds the encoded footprint operation inputs for the given entries and
+// their live until ledgers.
+fn footprint_op_inputs(
+    entries: &[(LedgerEntry, u32)],
+    read_only: bool,
+    read_bytes: u32,
+    write_bytes: u32,
+) -> (Vec<u8>, Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let budget = Budget::default();
+    let keys: Vec<LedgerKey> = entries
+        .iter()
+        .map(|(e, _)| ledger_entry_to_ledger_key(e, &budget).unwrap())
+        .collect();
+    let footprint = if read_only {
+        LedgerFootprint {
+            read_only: keys.clone().try_into().unwrap(),
+            read_write: Default::default(),
+        }
+    } else {
+        LedgerFootprint {
+            read_only: Default::default(),
+            read_write: keys.clone().try_into().unwrap(),
+        }
+    };
+    let resources = SorobanResources {
+        footprint,
+        instructions: 0,
+        read_bytes,
+        write_bytes,
+    };
+    let ttl_entries = entries
+        .iter()
+        .zip(keys.iter())
+        .map(|((_, live_until_ledger_seq), key)| {
+            TtlEntry {
+                key_hash: Hash(Sha256::digest(key.to_xdr(Limits::none()).unwrap()).into()),
+                live_until_ledger_seq: *live_until_ledger_seq,
+            }
+            .to_xdr(Limits::none())
+            .unwrap()
+        })
+        .collect();
+    (
+        resources.to_xdr(Limits::none()).unwrap(),
+        entries
+            .iter()
+            .map(|(e, _)| e.to_xdr(Limits::none()).unwrap())
+            .collect(),
+        ttl_entries,
+    )
+}
+
+#[test]
+fn test_extend_footprint_ttl() {
+    let contract_id = Hash([3; 32]);
+    let ledger_info = ledger_info();
+    let entries = vec![
+        // Already lives long enough.
+        (wasm_entry(ADD_I32), 1000),
+        // Archived.
+        (contract_instance_entry(&contract_id, ADD_I32), 50),
+        (temp_data_entry(&contract_id), 200),
+    ];
+    let (resources, ledger_entries, ttl_entries) = footprint_op_inputs(&entries, true, 10_000, 0);
+    let res = extend_footprint_ttl(
+        &Budget::default(),
+        &resources,
+        500,
+        &ledger_info,
+        ledger_entries.iter(),
+        ttl_entries.iter(),
+    )
+    .unwrap();
+    assert_eq!(res.ledger_changes.len(), 1);
+    let change = &res.ledger_changes[0];
+    assert!(change.read_only);
+    assert!(change.encoded_new_value.is_none());
+    assert_eq!(
+        change.old_entry_size_bytes as usize,
+        ledger_entries[2].len()
+    );
+    let ttl_change = change.ttl_change.as_ref().unwrap();
+    assert_eq!(ttl_change.durability, ContractDataDurability::Temporary);
+    assert_eq!(ttl_change.old_live_until_ledger, 200);
+    assert_eq!(ttl_change.new_live_until_ledger, 600);
+    assert_eq!(res.rent_changes.len(), 1);
+    let rent_change = &res.rent_changes[0];
+    assert!(!rent_change.is_persistent);
+    assert_eq!(rent_change.old_size_bytes, rent_change.new_size_bytes);
+    assert_eq!(rent_change.old_live_until_ledger, 200);
+    assert_eq!(rent_change.new_live_until_ledger, 600);
+
+    let extend = |extend_to, resources: &Vec<u8>| {
+        extend_footprint_ttl(
+            &Budget::default(),
+            resources,
+            extend_to,
+            &ledger_info,
+            ledger_entries.iter(),
+            ttl_entries.iter(),
+        )
+    };
+    // Extending past the maximum entry TTL is malformed.
+    assert!(extend(ledger_info.max_entry_ttl, &resources)
+        .err()
+        .unwrap()
+        .error
+        .is_code(ScErrorCode::InvalidInput));
+    // So is a non-empty read-write footprint.
+    let (rw_resources, _, _) = footprint_op_inputs(&entries, false, 10_000, 10_000);
+    assert!(extend(500, &rw_resources)
+        .err()
+        .unwrap()
+        .error
+        .is_code(ScErrorCode::InvalidInput));
+    // Only the extended entries count towards the read bytes: the large Wasm
+    // entry, which already lives long enough, doesn't.
+    let extended_size = ledger_entries[2].len() as u32;
+    let (exact_resources, _, _) = footprint_op_inputs(&entries, true, extended_size, 0);
+    assert!(extend(500, &exact_resources).is_ok());
+    let (small_resources, _, _) = footprint_op_inputs(&entries, true, extended_size - 1, 0);
+    let err = extend(500, &small_resources).err().unwrap().error;
+    assert!(err.is_type(ScErrorType::Budget) && err.is_code(ScErrorCode::ExceededLimit));
+
+    // Duplicate footprint keys are malformed.
+    let mut dup_entries = entries.clone();
+    dup_entries.push(entries[2].clone());
+    let (dup_resources, _, _) = footprint_op_inputs(&dup_entries, true, 10_000, 0);
+    assert!(extend(500, &dup_resources)
+        .err()
+        .unwrap()
+        .error
+        .is_code(ScErrorCode::InvalidInput));
+    // So is passing the same entry twice.
+    let (_, dup_ledger_entries, dup_ttl_entries) =
+        footprint_op_inputs(&dup_entries, true, 10_000, 0);
+    assert!(extend_footprint_ttl(
+        &Budget::default(),
+        &resources,
+        500,
+        &ledger_info,
+        dup_ledger_entries.iter(),
+        dup_ttl_entries.iter(),
+    )
+    .err()
+    .unwrap()
+    .error
+    .is_code(ScErrorCode::InvalidInput));
+}
+
+#[test]
+fn test_restore_footprint() {
+    let contract_id = Hash([3; 32]);
+    let ledger_info = ledger_info();
+    let entries = vec![
+        // Archived.
+        (wasm_entry(ADD_I32), 50),
+        // Still live.
+        (contract_instance_entry(&contract_id, ADD_I32), 100),
+    ];
+    let (resources, ledger_entries, ttl_entries) =
+        footprint_op_inputs(&entries, false, 10_000, 10_000);
+    let res = restore_footprint(
+        &Budget::default(),
+        &resources,
+        &ledger_info,
+        ledger_entries.iter(),
+        ttl_entries.iter(),
+    )
+    .unwrap();
+    let restored_live_until_ledger =
+        ledger_info.sequence_number + ledger_info.min_persistent_entry_ttl - 1;
+    assert_eq!(res.ledger_changes.len(), 1);
+    let change = &res.ledger_changes[0];
+    assert!(!change.read_only);
+    assert_eq!(change.encoded_new_value.as_ref(), Some(&ledger_entries[0]));
+    let ttl_change = change.ttl_change.as_ref().unwrap();
+    assert_eq!(ttl_change.old_live_until_ledger, 50);
+    assert_eq!(ttl_change.new_live_until_ledger, restored_live_until_ledger);
+    assert_eq!(res.rent_changes.len(), 1);
+    let rent_change = &res.rent_changes[0];
+    assert!(rent_change.is_persistent);
+    assert_eq!(rent_change.old_size_bytes, 0);
+    assert_eq!(rent_change.new_size_bytes as usize, ledger_entries[0].len());
+    assert_eq!(rent_change.old_live_until_ledger, 0);
+    assert_eq!(
+        rent_change.new_live_until_ledger,
+        restored_live_until_ledger
+    );
+
+    // Restored entries count towards the write bytes.
+    let (small_resources, _, _) = footprint_op_inputs(&entries, false, 10_000, 10);
+    assert!(restore_footprint(
+        &Budget::default(),
+        &small_resources,
+        &ledger_info,
+        ledger_entries.iter(),
+        ttl_entries.iter(),
+    )
+    .err()
+    .unwrap()
+    .error
+    .is_code(ScErrorCode::ExceededLimit));
+
+    // Temporary entries can't be restored.
+    let entries = vec![(temp_data_entry(&contract_id), 50)];
+    let (resources, ledger_entries, ttl_entries) =
+        footprint_op_inputs(&entries, false, 10_000, 10_000);
+    assert!(restore_footprint(
+        &Budget::default(),
+        &resources,
+        &ledger_info,
+        ledger_entries.iter(),
+        ttl_entries.iter(),
+    )
+    .err()
+    .unwrap()
+    .error
+    .is_code(ScErrorCode::InvalidInput));
+}
+
+// Simulates `put_persistent(key, val)` and builds the transaction meta of the
+// invocation at ledger 100. Also returns the ledger changes of the invocation.
+fn put_persistent_meta(
+    snapshot: Rc<dyn SnapshotSource>,
+    val: u64,
+) -> (TransactionMeta, Vec<LedgerEntryChange>) {
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let res = simulate_invoke_host_function(
+        Rc::clone(&snapshot),
+        &budget,
+        true,
+        invoke_contract_fn(
+            &Hash([3; 32]),
+            "put_persistent",
+            vec![
+                ScVal::Symbol(ScSymbol("key".try_into().unwrap())),
+                ScVal::U64(val),
+            ],
+        ),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut diagnostic_events,
+    )
+    .unwrap();
+    let result = res.into_invoke_host_function_result(&budget).unwrap();
+    let meta = build_transaction_meta(&budget, snapshot.as_ref(), &result, &diagnostic_events, 100)
+        .unwrap();
+    (meta, result.ledger_changes)
+}
+
+fn meta_changes(meta: &TransactionMeta) -> Vec<XdrLedgerEntryChange> {
+    let TransactionMeta::V3(meta) = meta else {
+        panic!("unexpected meta version");
+    };
+    assert!(meta.tx_changes_before.0.is_empty());
+    assert!(meta.tx_changes_after.0.is_empty());
+    assert_eq!(meta.operations.len(), 1);
+    meta.operations[0].changes.0.to_vec()
+}
+
+fn data_val(entry: &LedgerEntry) -> ScVal {
+    match &entry.data {
+        LedgerEntryData::ContractData(data) => data.val.clone(),
+        _ => panic!("unexpected entry type"),
+    }
+}
+
+#[test]
+fn test_build_transaction_meta() {
+    let contract_id = Hash([3; 32]);
+    let snapshot = Rc::new(LayeredSnapshotSource::new(Rc::new(snapshot_with_contract(
+        &contract_id,
+        CONTRACT_STORAGE,
+    ))));
+
+    // A new entry is created along with its TTL entry.
+    let (meta, ledger_changes) = put_persistent_meta(snapshot.clone(), 1);
+    let TransactionMeta::V3(TransactionMetaV3 {
+        soroban_meta: Some(soroban_meta),
+        ..
+    }) = &meta
+    else {
+        panic!("missing soroban meta");
+    };
+    assert_eq!(soroban_meta.return_value, ScVal::Void);
+    assert!(!soroban_meta.diagnostic_events.is_empty());
+    let changes = meta_changes(&meta);
+    assert_eq!(changes.len(), 2);
+    let XdrLedgerEntryChange::Created(entry) = &changes[0] else {
+        panic!("expected a created entry");
+    };
+    assert_eq!(entry.last_modified_ledger_seq, 100);
+    assert_eq!(data_val(entry), ScVal::U64(1));
+    let XdrLedgerEntryChange::Created(LedgerEntry {
+        data: LedgerEntryData::Ttl(ttl),
+        ..
+    }) = &changes[1]
+    else {
+        panic!("expected a created TTL entry");
+    };
+    assert_eq!(
+        ttl.live_until_ledger_seq,
+        100 + ledger_info().min_persistent_entry_ttl - 1
+    );
+
+    // Overwriting the entry produces its state before the invocation and the
+    // updated entry, while the TTL is left unchanged.
+    snapshot.push_ledger_changes(&ledger_changes).unwrap();
+    let changes = meta_changes(&put_persistent_meta(snapshot.clone(), 2).0);
+    assert_eq!(changes.len(), 2);
+    let XdrLedgerEntryChange::State(old_entry) = &changes[0] else {
+        panic!("expected an entry state");
+    };
+    assert_eq!(data_val(old_entry), ScVal::U64(1));
+    let XdrLedgerEntryChange::Updated(new_entry) = &changes[1] else {
+        panic!("expected an updated entry");
+    };
+    assert_eq!(data_val(new_entry), ScVal::U64(2));
+    assert_eq!(new_entry.last_modified_ledger_seq, 100);
+
+    // Writing the same value produces no changes.
+    assert!(meta_changes(&put_persistent_meta(snapshot.clone(), 1).0).is_empty());
+
+    // Failed invocations have neither changes nor a return value.
+    let (meta, _) = put_persistent_meta(Rc::new(MockSnapshotSource::new()), 1);
+    assert!(meta_changes(&meta).is_empty());
+    let TransactionMeta::V3(TransactionMetaV3 {
+        soroban_meta: Some(soroban_meta),
+        ..
+    }) = &meta
+    else {
+        panic!("missing soroban meta");
+    };
+    assert_eq!(soroban_meta.return_value, ScVal::Void);
+    assert!(soroban_meta.events.is_empty());
+    assert!(!soroban_meta.diagnostic_events.is_empty());
+}
+
+fn fee_configs() -> (FeeConfiguration, RentFeeConfiguration) {
+    (
+        FeeConfiguration {
+            fee_per_instruction_increment: 100,
+            fee_per_read_entry: 10,
+            fee_per_write_entry: 20,
+            fee_per_read_1kb: 30,
+            fee_per_write_1kb: 40,
+            fee_per_historical_1kb: 50,
+            fee_per_contract_event_1kb: 60,
+            fee_per_transaction_size_1kb: 70,
+        },
+        RentFeeConfiguration {
+            fee_per_write_1kb: 40,
+            fee_per_write_entry: 20,
+            persistent_rent_rate_denominator: 100,
+            temporary_rent_rate_denominator: 1000,
+        },
+    )
+}
+
+#[test]
+fn test_estimate_resource_fee() {
+    let contract_id = Hash([3; 32]);
+    let budget = Budget::default();
+    let mut diagnostic_events = vec![];
+    let res = simulate_invoke_host_function(
+        Rc::new(snapshot_with_contract(&contract_id, CONTRACT_STORAGE)),
+        &budget,
+        false,
+        invoke_contract_fn(
+            &contract_id,
+            "put_persistent",
+            vec![
+                ScVal::Symbol(ScSymbol("key".try_into().unwrap())),
+                ScVal::U64(1234),
+            ],
+        ),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut diagnostic_events,
+    )
+    .unwrap();
+    let resources = res.resources.clone();
+    let result = res.into_invoke_host_function_result(&budget).unwrap();
+    let (fee_config, rent_fee_config) = fee_configs();
+    let estimate = |margins: &ResourceFeeMargins| {
+        estimate_resource_fee(
+            &budget,
+            &resources.footprint,
+            &result,
+            500,
+            &fee_config,
+            &rent_fee_config,
+            ledger_info().sequence_number,
+            margins,
+        )
+        .unwrap()
+    };
+
+    let fee = estimate(&ResourceFeeMargins::default());
+    assert_eq!(
+        fee.resources.instructions as u64,
+        budget.get_cpu_insns_consumed().unwrap()
+    );
+    assert_eq!(fee.resources.read_entries, 2);
+    assert_eq!(fee.resources.write_entries, 1);
+    assert_eq!(fee.resources.read_bytes, resources.read_bytes);
+    assert_eq!(fee.resources.write_bytes, resources.write_bytes);
+    // The `Void` return value.
+    assert_eq!(fee.resources.contract_events_size_bytes, 4);
+    assert_eq!(fee.resources.transaction_size_bytes, 500);
+    let (non_refundable_fee, refundable_fee) =
+        compute_transaction_resource_fee(&fee.resources, &fee_config);
+    assert_eq!(fee.non_refundable_fee, non_refundable_fee);
+    assert_eq!(fee.breakdown.non_refundable_fee(), non_refundable_fee);
+    assert_eq!(fee.breakdown.refundable_fee(), refundable_fee);
+    // The new entry pays rent for its initial TTL.
+    assert!(fee.rent_fee > 0);
+    assert_eq!(fee.refundable_fee, refundable_fee + fee.rent_fee);
+    assert_eq!(
+        fee.resource_fee(),
+        non_refundable_fee + refundable_fee + fee.rent_fee
+    );
+
+    let with_margins = estimate(&ResourceFeeMargins {
+        instructions_percent: 20,
+        ledger_bytes_percent: 10,
+        refundable_fee_percent: 50,
+    });
+    assert_eq!(
+        with_margins.resources.instructions,
+        fee.resources.instructions + fee.resources.instructions / 5
+    );
+    assert_eq!(
+        with_margins.resources.read_bytes,
+        fee.resources.read_bytes + fee.resources.read_bytes / 10
+    );
+    assert_eq!(
+        with_margins.resources.write_bytes,
+        fee.resources.write_bytes + fee.resources.write_bytes / 10
+    );
+    assert!(with_margins.non_refundable_fee > fee.non_refundable_fee);
+    assert_eq!(
+        with_margins.refundable_fee,
+        fee.refundable_fee + fee.refundable_fee / 2
+    );
+}
@@ -1,19 +1,22 @@
 use crate::{
     budget::AsBudget,
     events::{
-        Events, InternalContractEvent, InternalDiagnosticArg, InternalDiagnosticEvent,
-        InternalEvent,
+        diagnostic::DiagnosticLevel, CallFrame, CallOutcome, CallTraceEntry, Events,
+        InternalContractEvent, InternalDiagnosticArg, InternalDiagnosticEvent, InternalEvent,
     },
     testutils::AsScVal,
     xdr::{
         ContractCostType, ContractEvent, ContractEventBody, ContractEventType, ContractEventV0,
         ExtensionPoint, Hash, ScAddress, ScErrorCode, ScErrorType, ScMap, ScMapEntry, ScVal,
+        WriteXdr, SCSYMBOL_LIMIT,
     },
     ContractFunctionSet, Env, Error, Host, HostError, Symbol, SymbolSmall, Val, VecObject,
+    DEFAULT_XDR_RW_LIMITS,
 };
 use expect_test::expect;
 use more_asserts::assert_le;
-use soroban_env_common::EnvBase;
+use soroban_env_common::{EnvBase, TryFromVal, TryIntoVal};
+use soroban_test_wasms::{CONTRACT_STORAGE, INVOKE_CONTRACT, VEC};
 use std::rc::Rc;
 
 pub struct ContractWithSingleEvent;
@@ -347,3 +350,386 @@ fn too_many_events_in_loop() -> Result<(), HostError> {
     }
     Ok(())
 }
+
+fn run_with_diagnostic_level(level: DiagnosticLevel) -> Result<(Vec<String>, u64, u64), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    host.set_diagnostic_level(level)?;
+    let contract = host.register_test_contract_wasm(VEC);
+    let budget = host.as_budget().clone();
+    budget.reset_default()?;
+    host.log_diagnostics("some log", &[]);
+    let res = host.call(
+        contract,
+        Symbol::try_from_small_str("vec_err")?,
+        host.test_vec_obj::<u32>(&[1])?,
+    );
+    // The backtrace and events snapshot are only attached in debug mode.
+    assert_eq!(
+        res.as_ref().err().unwrap().info.is_some(),
+        level >= DiagnosticLevel::Debug
+    );
+    assert!(HostError::result_matches_err(
+        res,
+        (ScErrorType::Object, ScErrorCode::IndexBounds)
+    ));
+    let topics = host
+        .get_events()?
+        .0
+        .into_iter()
+        .map(|e| {
+            let ContractEventBody::V0(ContractEventV0 { topics, .. }) = e.event.body;
+            let ScVal::Symbol(topic) = &topics[0] else {
+                panic!("unexpected topic");
+            };
+            topic.to_utf8_string_lossy()
+        })
+        .collect();
+    Ok((
+        topics,
+        budget.get_cpu_insns_consumed()?,
+        budget.get_mem_bytes_consumed()?,
+    ))
+}
+
+#[test]
+fn test_diagnostic_levels() -> Result<(), HostError> {
+    let count = |topics: &Vec<String>, topic: &str| topics.iter().filter(|t| *t == topic).count();
+
+    let (none, none_cpu, none_mem) = run_with_diagnostic_level(DiagnosticLevel::None)?;
+    assert!(none.is_empty());
+
+    let (errors, errors_cpu, errors_mem) = run_with_diagnostic_level(DiagnosticLevel::Errors)?;
+    assert_ne!(count(&errors, "error"), 0);
+    assert_eq!(count(&errors, "error"), errors.len());
+
+    let (logs, logs_cpu, logs_mem) = run_with_diagnostic_level(DiagnosticLevel::Logs)?;
+    // The explicit log and the host log for the trapped VM call.
+    assert_eq!(count(&logs, "log"), 2);
+    assert_eq!(logs.len(), errors.len() + count(&logs, "log"));
+
+    let (debug, debug_cpu, debug_mem) = run_with_diagnostic_level(DiagnosticLevel::Debug)?;
+    assert_eq!(count(&debug, "fn_call"), 1);
+    assert_eq!(count(&debug, "host_fn_call"), 0);
+    assert_eq!(
+        debug.len(),
+        logs.len() + count(&debug, "fn_call") + count(&debug, "fn_return")
+    );
+
+    let (trace, trace_cpu, trace_mem) = run_with_diagnostic_level(DiagnosticLevel::HostFnTrace)?;
+    assert_ne!(count(&trace, "host_fn_call"), 0);
+    // Every host function call returns, including the failing `vec_insert`.
+    assert_eq!(
+        count(&trace, "host_fn_call"),
+        count(&trace, "host_fn_return")
+    );
+    assert_eq!(
+        trace.len(),
+        debug.len() + count(&trace, "host_fn_call") + count(&trace, "host_fn_return")
+    );
+
+    // The diagnostic level doesn't affect the metered budget.
+    for (cpu, mem) in [
+        (errors_cpu, errors_mem),
+        (logs_cpu, logs_mem),
+        (debug_cpu, debug_mem),
+        (trace_cpu, trace_mem),
+    ] {
+        assert_eq!(cpu, none_cpu);
+        assert_eq!(mem, none_mem);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_call_trace_from_diagnostic_events() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    host.enable_debug()?;
+    let caller = host.register_test_contract_wasm(INVOKE_CONTRACT);
+    let callee = host.register_test_contract_wasm(INVOKE_CONTRACT);
+    let caller_id = host.contract_id_from_address(caller)?;
+    let callee_id = host.contract_id_from_address(callee)?;
+
+    // The nested `add` call overflows, but the error is ignored by the caller.
+    let args = host.test_vec_obj::<i32>(&[i32::MAX, 1])?;
+    let args = host.vec_push_back(args, callee.to_val())?;
+    host.call(caller, Symbol::try_from_val(&host, &"add_with_try")?, args)?;
+
+    let args = host.test_vec_obj::<i32>(&[1, 2])?;
+    let args = host.vec_push_back(args, callee.to_val())?;
+    host.call(caller, Symbol::try_from_small_str("add_with")?, args)?;
+
+    let trace = host.get_events()?.call_trace();
+    let calls: Vec<&CallFrame> = trace
+        .entries
+        .iter()
+        .filter_map(|e| match e {
+            CallTraceEntry::Call(frame) => Some(frame),
+            CallTraceEntry::Event(_) => None,
+        })
+        .collect();
+    assert_eq!(calls.len(), 2);
+    let nested_call = |frame: &CallFrame| -> CallFrame {
+        let nested: Vec<&CallFrame> = frame
+            .entries
+            .iter()
+            .filter_map(|e| match e {
+                CallTraceEntry::Call(frame) => Some(frame),
+                CallTraceEntry::Event(_) => None,
+            })
+            .collect();
+        assert_eq!(nested.len(), 1);
+        nested[0].clone()
+    };
+
+    assert_eq!(calls[0].caller, None);
+    assert_eq!(calls[0].contract, caller_id);
+    assert_eq!(calls[0].args.len(), 3);
+    assert_eq!(calls[0].outcome, CallOutcome::Returned(ScVal::I32(0)));
+    assert!(!calls[0].rolled_back);
+    let failed = nested_call(calls[0]);
+    assert_eq!(failed.caller, Some(caller_id.clone()));
+    assert_eq!(failed.contract, callee_id);
+    assert_eq!(failed.function.0.as_slice(), b"add");
+    assert_eq!(failed.args, vec![ScVal::I32(i32::MAX), ScVal::I32(1)]);
+    assert_eq!(
+        failed.outcome,
+        CallOutcome::Failed(Some(
+            (ScErrorType::WasmVm, ScErrorCode::InvalidAction).into()
+        ))
+    );
+    assert!(failed.rolled_back);
+    // The `try_call` failure is recorded in the caller frame.
+    assert!(
+        matches!(calls[0].entries.last(), Some(CallTraceEntry::Event(e))
+        if e.event.contract_id == Some(caller_id.clone()) && !e.failed_call)
+    );
+
+    assert_eq!(calls[1].outcome, CallOutcome::Returned(ScVal::I32(3)));
+    let succeeded = nested_call(calls[1]);
+    assert_eq!(succeeded.caller, Some(caller_id));
+    assert_eq!(succeeded.outcome, CallOutcome::Returned(ScVal::I32(3)));
+    assert!(!succeeded.rolled_back);
+    // The `add` contract event is recorded in the nested frame.
+    assert!(succeeded.entries.iter().any(|e| matches!(e,
+        CallTraceEntry::Event(e) if e.event.type_ == ContractEventType::Contract)));
+
+    let rendered = trace.to_string();
+    assert!(rendered.contains(&format!("\n  call {}:add(2147483647, 1)\n", callee_id)));
+    assert!(rendered.contains("\n  fail Error(WasmVm, InvalidAction) (rolled back)\n"));
+    assert!(rendered.ends_with("\n  return 3\nreturn 3\n"));
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_host_event_json() {
+    use crate::{
+        events::{HostEvent, JsonScVal},
+        xdr::{
+            AccountId, ContractExecutable, Duration, Int128Parts, PublicKey, ScBytes,
+            ScContractInstance, ScError, ScString, ScSymbol, TimePoint, Uint256,
+        },
+    };
+    use serde_json::json;
+
+    let json = |val: ScVal| serde_json::to_value(JsonScVal(&val)).unwrap();
+    assert_eq!(json(ScVal::Void), json!(null));
+    assert_eq!(json(ScVal::Bool(true)), json!(true));
+    assert_eq!(json(ScVal::U32(7)), json!(7));
+    assert_eq!(json(ScVal::I32(-7)), json!(-7));
+    assert_eq!(
+        json(ScVal::U64(u64::MAX)),
+        json!({ "u64": "18446744073709551615" })
+    );
+    assert_eq!(
+        json(ScVal::I64(i64::MIN)),
+        json!({ "i64": "-9223372036854775808" })
+    );
+    assert_eq!(
+        json(ScVal::Timepoint(TimePoint(u64::MAX))),
+        json!({ "timepoint": "18446744073709551615" })
+    );
+    assert_eq!(
+        json(ScVal::Duration(Duration(1))),
+        json!({ "duration": "1" })
+    );
+    assert_eq!(
+        json(ScVal::I128(Int128Parts {
+            hi: -1,
+            lo: u64::MAX - 99
+        })),
+        json!({ "i128": "-100" })
+    );
+    assert_eq!(
+        json(ScVal::Bytes(ScBytes(
+            vec![0, 0xab, 0xff].try_into().unwrap()
+        ))),
+        json!({ "bytes": "00abff" })
+    );
+    assert_eq!(
+        json(ScVal::String(ScString("abc".try_into().unwrap()))),
+        json!({ "string": "abc" })
+    );
+    // Invalid UTF-8 is encoded losslessly, as hex.
+    assert_eq!(
+        json(ScVal::String(ScString(
+            vec![b'a', 0xff].try_into().unwrap()
+        ))),
+        json!({ "string_bytes": "61ff" })
+    );
+    assert_eq!(
+        json(ScVal::Symbol(ScSymbol("abc".try_into().unwrap()))),
+        json!("abc")
+    );
+    assert_eq!(
+        json(ScVal::Symbol(ScSymbol(vec![0xff].try_into().unwrap()))),
+        json!({ "symbol_bytes": "ff" })
+    );
+    assert_eq!(
+        json(ScVal::Error(ScError::Value(ScErrorCode::InvalidInput))),
+        json!({ "error": "Error(Value, InvalidInput)" })
+    );
+    assert_eq!(
+        json(ScVal::Address(ScAddress::Account(AccountId(
+            PublicKey::PublicKeyTypeEd25519(Uint256([0; 32]))
+        )))),
+        json!({ "address": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF" })
+    );
+    assert_eq!(
+        json(ScVal::Map(Some(ScMap(
+            vec![ScMapEntry {
+                key: ScVal::Symbol(ScSymbol("key".try_into().unwrap())),
+                val: ScVal::Vec(Some(vec![ScVal::U32(1)].try_into().unwrap())),
+            }]
+            .try_into()
+            .unwrap()
+        )))),
+        json!({ "map": [["key", [1]]] })
+    );
+    assert_eq!(
+        json(ScVal::ContractInstance(ScContractInstance {
+            executable: ContractExecutable::Wasm(Hash([0xab; 32])),
+            storage: None,
+        })),
+        json!({ "contract_instance": { "executable": { "wasm": "ab".repeat(32) }, "storage": null } })
+    );
+    assert_eq!(
+        json(ScVal::ContractInstance(ScContractInstance {
+            executable: ContractExecutable::StellarAsset,
+            storage: Some(ScMap(
+                vec![ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("admin".try_into().unwrap())),
+                    val: ScVal::U64(1),
+                }]
+                .try_into()
+                .unwrap()
+            )),
+        })),
+        json!({ "contract_instance": {
+            "executable": "stellar_asset",
+            "storage": [["admin", { "u64": "1" }]],
+        } })
+    );
+
+    let event = HostEvent {
+        event: ContractEvent {
+            ext: ExtensionPoint::V0,
+            contract_id: Some(Hash([0; 32])),
+            type_: ContractEventType::Diagnostic,
+            body: ContractEventBody::V0(ContractEventV0 {
+                topics: vec![ScVal::Symbol(ScSymbol("log".try_into().unwrap()))]
+                    .try_into()
+                    .unwrap(),
+                data: ScVal::String(ScString("hello".try_into().unwrap())),
+            }),
+        },
+        failed_call: true,
+    };
+    assert_eq!(
+        serde_json::to_value(Events(vec![event])).unwrap(),
+        json!([{
+            "type": "diagnostic",
+            "contract_id": "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4",
+            "topics": ["log"],
+            "data": { "string": "hello" },
+            "failed_call": true,
+        }])
+    );
+}
+
+#[test]
+fn test_contract_events_size_limit() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(INVOKE_CONTRACT);
+    let callee = host.register_test_contract_wasm(INVOKE_CONTRACT);
+    host.call(
+        contract,
+        Symbol::try_from_small_str("add")?,
+        host.test_vec_obj::<i32>(&[1, 2])?,
+    )?;
+    // The event of the failed nested call is rolled back and doesn't count.
+    let args = host.test_vec_obj::<i32>(&[i32::MAX, 1])?;
+    let args = host.vec_push_back(args, callee.to_val())?;
+    host.call(
+        contract,
+        Symbol::try_from_val(&host, &"add_with_try")?,
+        args,
+    )?;
+    let events = host.get_events()?;
+    assert!(events.0.iter().any(|e| e.failed_call));
+    let events_size: u64 = events
+        .0
+        .iter()
+        .filter(|e| !e.failed_call && e.event.type_ != ContractEventType::Diagnostic)
+        .map(|e| e.event.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap().len() as u64)
+        .sum();
+    assert_ne!(events_size, 0);
+
+    // The limit is inclusive.
+    host.set_contract_events_size_limit(events_size as u32)?;
+    assert_eq!(host.get_events()?.0.len(), events.0.len());
+    host.set_contract_events_size_limit(events_size as u32 - 1)?;
+    assert!(HostError::result_matches_err(
+        host.get_events(),
+        (ScErrorType::Events, ScErrorCode::ExceededLimit)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_host_fn_trace_with_long_function_name() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    host.set_diagnostic_level(DiagnosticLevel::HostFnTrace)?;
+    let contract = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    host.call(
+        contract,
+        Symbol::try_from_val(&host, &"extend_instance")?,
+        test_vec![&host, 100_u32, 1000_u32].into(),
+    )?;
+    // The name is longer than a symbol can be, so it's traced as a string.
+    let func = "extend_current_contract_instance_and_code_ttl";
+    assert!(func.len() > SCSYMBOL_LIMIT as usize);
+    let traced: Vec<&str> = host
+        .get_events()?
+        .0
+        .iter()
+        .filter_map(|e| {
+            let ContractEventBody::V0(ContractEventV0 { topics, .. }) = &e.event.body;
+            match topics.as_slice() {
+                [ScVal::Symbol(topic), ScVal::String(name)]
+                    if name.as_slice() == func.as_bytes() =>
+                {
+                    topic.to_utf8_string().ok()
+                }
+                _ => None,
+            }
+        })
+        .map(|topic| match topic.as_str() {
+            "host_fn_call" => "call",
+            "host_fn_return" => "return",
+            _ => "other",
+        })
+        .collect();
+    assert_eq!(traced, vec!["call", "return"]);
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use std::rc::Rc;
+
+use soroban_test_wasms::CONTRACT_STORAGE;
+
+use crate::{
+    budget::Budget,
+    e2e_invoke::{simulate_invoke_host_function, InvokeHostFunctionSimulationResult},
+    layered_snapshot::LayeredSnapshotSource,
+    ledger_snapshot::LedgerSnapshot,
+    storage::SnapshotSource,
+    test::e2e_tests::{
+        contract_instance_entry, invoke_contract_fn, ledger_info, source_account, wasm_entry,
+    },
+    xdr::{
+        ContractDataDurability, Hash, LedgerKey, LedgerKeyContractData, ScAddress, ScSymbol, ScVal,
+    },
+};
+
+fn key_val() -> ScVal {
+    ScVal::Symbol(ScSymbol("key".try_into().unwrap()))
+}
+
+fn run(
+    source: &Rc<LayeredSnapshotSource>,
+    contract_id: &Hash,
+    fn_name: &str,
+    args: Vec<ScVal>,
+) -> InvokeHostFunctionSimulationResult {
+    let res = simulate_invoke_host_function(
+        Rc::clone(source) as Rc<dyn SnapshotSource>,
+        &Budget::default(),
+        false,
+        invoke_contract_fn(contract_id, fn_name, args),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut vec![],
+    )
+    .unwrap();
+    source.push_ledger_changes(&res.ledger_changes).unwrap();
+    res
+}
+
+fn has_persistent(source: &Rc<LayeredSnapshotSource>, contract_id: &Hash) -> bool {
+    let res = run(source, contract_id, "has_persistent", vec![key_val()]);
+    source.discard_top_layer().unwrap();
+    match res.invoke_result.unwrap() {
+        ScVal::Bool(b) => b,
+        _ => panic!("unexpected result"),
+    }
+}
+
+#[test]
+fn test_layered_snapshot_sequential_invocations() {
+    let contract_id = Hash([3; 32]);
+    let base = Rc::new(
+        LedgerSnapshot::from_entries(vec![
+            (wasm_entry(CONTRACT_STORAGE), Some(1000)),
+            (
+                contract_instance_entry(&contract_id, CONTRACT_STORAGE),
+                Some(1000),
+            ),
+        ])
+        .unwrap(),
+    );
+    let source = Rc::new(LayeredSnapshotSource::new(base.clone()));
+    let data_key = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract(contract_id.clone()),
+        key: key_val(),
+        durability: ContractDataDurability::Persistent,
+    }));
+
+    // The second invocation observes the write of the first one.
+    run(
+        &source,
+        &contract_id,
+        "put_persistent",
+        vec![key_val(), ScVal::U64(1234)],
+    );
+    assert_eq!(source.depth().unwrap(), 1);
+    let res = run(&source, &contract_id, "get_persistent", vec![key_val()]);
+    assert_eq!(res.invoke_result.unwrap(), ScVal::U64(1234));
+    assert_eq!(source.depth().unwrap(), 2);
+    // The base snapshot is never modified.
+    assert!(!base.has(&data_key).unwrap());
+    assert!(source.has(&data_key).unwrap());
+
+    // TTL extensions of read-only entries are stacked too.
+    let (_, live_until_ledger) = source.get(&data_key).unwrap();
+    let live_until_ledger = live_until_ledger.unwrap();
+    run(
+        &source,
+        &contract_id,
+        "extend_persistent",
+        vec![key_val(), ScVal::U32(10_000), ScVal::U32(10_000)],
+    );
+    let (_, new_live_until_ledger) = source.get(&data_key).unwrap();
+    assert!(new_live_until_ledger.unwrap() > live_until_ledger);
+    source.discard_top_layer().unwrap();
+    assert_eq!(source.get(&data_key).unwrap().1, Some(live_until_ledger));
+
+    // Deletions hide the entry until they are discarded.
+    run(&source, &contract_id, "del_persistent", vec![key_val()]);
+    assert!(!source.has(&data_key).unwrap());
+    assert!(source.get(&data_key).is_err());
+    assert!(!has_persistent(&source, &contract_id));
+    assert!(source.discard_top_layer().unwrap());
+    assert!(has_persistent(&source, &contract_id));
+
+    // Committed layers can only be discarded together.
+    run(&source, &contract_id, "del_persistent", vec![key_val()]);
+    assert_eq!(source.depth().unwrap(), 3);
+    source.commit_top_layer().unwrap();
+    assert_eq!(source.depth().unwrap(), 2);
+    assert!(!source.has(&data_key).unwrap());
+    // The entry was created and then removed by the layers, which is not a
+    // change relative to the base snapshot.
+    assert!(source.changes().unwrap().is_empty());
+    assert!(source.discard_top_layer().unwrap());
+    assert!(source.has(&data_key).unwrap());
+    let changes = source.changes().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].0, data_key);
+    assert!(changes[0].1.is_some());
+    assert!(source.discard_top_layer().unwrap());
+    assert!(!source.discard_top_layer().unwrap());
+    assert!(!source.has(&data_key).unwrap());
+    assert!(source.changes().unwrap().is_empty());
+}
@@ -0,0 +1,211 @@
+use std::rc::Rc;
+
+use soroban_test_wasms::CONTRACT_STORAGE;
+
+use crate::{
+    budget::Budget,
+    e2e_invoke::simulate_invoke_host_function,
+    ledger_snapshot::LedgerSnapshot,
+    storage::SnapshotSource,
+    test::e2e_tests::{
+        contract_instance_entry, invoke_contract_fn, ledger_info, source_account, wasm_entry,
+    },
+    xdr::{
+        ContractDataDurability, ContractDataEntry, ExtensionPoint, Hash, LedgerEntry,
+        LedgerEntryData, LedgerEntryExt, LedgerKey, LedgerKeyContractData, ScAddress, ScErrorCode,
+        ScErrorType, ScSymbol, ScVal, TtlEntry,
+    },
+};
+
+fn temp_entry(contract_id: &Hash, key: u32) -> LedgerEntry {
+    LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: ScAddress::Contract(contract_id.clone()),
+            key: ScVal::U32(key),
+            durability: ContractDataDurability::Temporary,
+            val: ScVal::U32(key + 1),
+        }),
+        ext: LedgerEntryExt::V0,
+    }
+}
+
+fn test_snapshot(contract_id: &Hash) -> LedgerSnapshot {
+    LedgerSnapshot::from_entries(vec![
+        (wasm_entry(CONTRACT_STORAGE), Some(1000)),
+        (
+            contract_instance_entry(contract_id, CONTRACT_STORAGE),
+            Some(2000),
+        ),
+        (temp_entry(contract_id, 1), Some(150)),
+    ])
+    .unwrap()
+}
+
+fn assert_same_entries(a: &LedgerSnapshot, b: &LedgerSnapshot) {
+    assert_eq!(a.len(), b.len());
+    for ((ka, va), (kb, vb)) in a.iter().zip(b.iter()) {
+        assert_eq!(ka, kb);
+        assert_eq!(va, vb);
+    }
+}
+
+fn write_xdr(snapshot: &LedgerSnapshot) -> Vec<u8> {
+    let mut buf = vec![];
+    snapshot.write_xdr(&mut buf).unwrap();
+    buf
+}
+
+fn is_invalid_input(res: Result<LedgerSnapshot, crate::HostError>) -> bool {
+    res.err().unwrap().error.is_code(ScErrorCode::InvalidInput)
+}
+
+#[test]
+fn test_xdr_snapshot_round_trip() {
+    let snapshot = test_snapshot(&Hash([3; 32]));
+    let buf = write_xdr(&snapshot);
+    let read = LedgerSnapshot::read_xdr(buf.as_slice()).unwrap();
+    assert_same_entries(&snapshot, &read);
+    // Every entry is followed by its TTL record, so a stream in the reverse
+    // order (TTL records first) is read the same.
+    let mut records = vec![];
+    let mut rest = buf.as_slice();
+    while !rest.is_empty() {
+        let len = (u32::from_be_bytes(rest[..4].try_into().unwrap()) & 0x7fff_ffff) as usize;
+        records.push(&rest[..4 + len]);
+        rest = &rest[4 + len..];
+    }
+    assert_eq!(records.len(), 6);
+    let reversed: Vec<u8> = records.iter().rev().flat_map(|r| r.to_vec()).collect();
+    let read = LedgerSnapshot::read_xdr(reversed.as_slice()).unwrap();
+    assert_same_entries(&snapshot, &read);
+}
+
+#[test]
+fn test_xdr_snapshot_invalid() {
+    let contract_id = Hash([3; 32]);
+    // A single contract entry is written as the entry record followed by its
+    // TTL record.
+    let buf = write_xdr(
+        &LedgerSnapshot::from_entries(vec![(temp_entry(&contract_id, 1), Some(150))]).unwrap(),
+    );
+    let entry_len = 4 + (u32::from_be_bytes(buf[..4].try_into().unwrap()) & 0x7fff_ffff) as usize;
+    let (entry_record, ttl_record) = buf.split_at(entry_len);
+
+    // Contract entries must have a TTL.
+    assert!(is_invalid_input(LedgerSnapshot::read_xdr(entry_record)));
+    // TTL records must belong to an entry.
+    assert!(is_invalid_input(LedgerSnapshot::read_xdr(ttl_record)));
+    // Duplicate entries are rejected.
+    let mut buf = write_xdr(&test_snapshot(&contract_id));
+    buf.extend_from_within(..);
+    assert!(is_invalid_input(LedgerSnapshot::read_xdr(buf.as_slice())));
+    // The stream can't end in the middle of a record.
+    let buf = write_xdr(&test_snapshot(&contract_id));
+    assert!(LedgerSnapshot::read_xdr(&buf[..buf.len() - 1]).is_err());
+    assert!(LedgerSnapshot::read_xdr(&buf[..2]).is_err());
+    // Records must have the last fragment bit set.
+    let mut buf = write_xdr(&test_snapshot(&contract_id));
+    buf[0] &= 0x7f;
+    assert!(is_invalid_input(LedgerSnapshot::read_xdr(buf.as_slice())));
+    // Records longer than the XDR length limit are rejected before reading
+    // them.
+    assert!(is_invalid_input(LedgerSnapshot::read_xdr(
+        &[0xff, 0xff, 0xff, 0xff][..]
+    )));
+    // A record mark promising more data than there is is an error.
+    assert!(LedgerSnapshot::read_xdr(&[0x80, 0x10, 0x00, 0x00, 0x00][..]).is_err());
+}
+
+#[test]
+fn test_snapshot_rejects_ttl_entries() {
+    let ttl_entry = LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::Ttl(TtlEntry {
+            key_hash: Hash([1; 32]),
+            live_until_ledger_seq: 100,
+        }),
+        ext: LedgerEntryExt::V0,
+    };
+    let mut snapshot = LedgerSnapshot::new();
+    let err = snapshot.insert(ttl_entry.clone(), None).err().unwrap();
+    assert!(err.error.is_code(ScErrorCode::InvalidInput));
+    assert!(snapshot.is_empty());
+    assert!(is_invalid_input(LedgerSnapshot::from_entries(vec![(
+        ttl_entry,
+        Some(100)
+    )])));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_snapshot_round_trip() {
+    let snapshot = test_snapshot(&Hash([3; 32]));
+    let mut buf = vec![];
+    snapshot.write_json(&mut buf).unwrap();
+    let read = LedgerSnapshot::read_json(buf.as_slice()).unwrap();
+    assert_same_entries(&snapshot, &read);
+    assert!(LedgerSnapshot::read_json(&b"{\"entries\": [{}]}"[..]).is_err());
+}
+
+#[test]
+fn test_snapshot_apply_ledger_changes() {
+    let contract_id = Hash([3; 32]);
+    let mut snapshot = test_snapshot(&contract_id);
+    let key = ScVal::Symbol(ScSymbol("key".try_into().unwrap()));
+    let res = simulate_invoke_host_function(
+        Rc::new(snapshot.clone()),
+        &Budget::default(),
+        false,
+        invoke_contract_fn(
+            &contract_id,
+            "put_persistent",
+            vec![key.clone(), ScVal::U64(1234)],
+        ),
+        source_account(),
+        ledger_info(),
+        [0; 32],
+        true,
+        vec![],
+        &mut vec![],
+    )
+    .unwrap();
+    assert_eq!(res.invoke_result.unwrap(), ScVal::Void);
+
+    snapshot.apply_ledger_changes(&res.ledger_changes).unwrap();
+    assert_eq!(snapshot.len(), 4);
+    let new_key = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract(contract_id.clone()),
+        key,
+        durability: ContractDataDurability::Persistent,
+    }));
+    let (entry, live_until_ledger) = snapshot.get(&new_key).unwrap();
+    match &entry.data {
+        LedgerEntryData::ContractData(cd) => assert_eq!(cd.val, ScVal::U64(1234)),
+        _ => panic!("unexpected entry"),
+    }
+    let ledger_info = ledger_info();
+    assert_eq!(
+        live_until_ledger,
+        Some(ledger_info.sequence_number + ledger_info.min_persistent_entry_ttl - 1)
+    );
+
+    // The post-invocation state survives a round trip.
+    let read = LedgerSnapshot::read_xdr(write_xdr(&snapshot).as_slice()).unwrap();
+    assert_same_entries(&snapshot, &read);
+
+    // Missing entries are reported as such.
+    let missing = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract(contract_id),
+        key: ScVal::U32(5),
+        durability: ContractDataDurability::Temporary,
+    }));
+    assert!(!snapshot.has(&missing).unwrap());
+    assert!(snapshot
+        .get(&missing)
+        .err()
+        .unwrap()
+        .error
+        .is_type(ScErrorType::Storage));
+}
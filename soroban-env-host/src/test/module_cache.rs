@@ -0,0 +1,125 @@
+use std::rc::Rc;
+
+use soroban_env_common::{xdr::ContractCostType, Env, TryFromVal};
+use soroban_test_wasms::{ADD_I32, INVOKE_CONTRACT, VEC};
+
+use crate::{
+    budget::AsBudget, test::e2e_tests::wasm_hash, vm::ModuleCache, Host, HostError, Symbol,
+};
+
+fn call_add(host: &Host, contract: crate::AddressObject) -> Result<i32, HostError> {
+    let res = host.call(
+        contract,
+        Symbol::try_from_small_str("add")?,
+        host.test_vec_obj(&[4i32, 7i32])?,
+    )?;
+    Ok(i32::try_from_val(host, &res)?)
+}
+
+fn vm_instantiations(host: &Host) -> Result<(u64, u64), HostError> {
+    let budget = host.as_budget();
+    Ok((
+        budget
+            .get_tracker(ContractCostType::VmInstantiation)?
+            .iterations,
+        budget
+            .get_tracker(ContractCostType::VmCachedInstantiation)?
+            .iterations,
+    ))
+}
+
+#[test]
+fn cached_module_is_instantiated_without_parsing() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(ADD_I32);
+    let cache = Rc::new(ModuleCache::new(&host)?);
+    host.as_budget().reset_unlimited()?;
+    // Attaching the cache doesn't parse or charge anything.
+    host.set_module_cache(cache.clone())?;
+    assert!(!cache.contains_module(&wasm_hash(ADD_I32)));
+    assert_eq!(vm_instantiations(&host)?, (0, 0));
+
+    // Only the first instantiation is charged in full.
+    assert_eq!(call_add(&host, contract)?, 11);
+    assert!(cache.contains_module(&wasm_hash(ADD_I32)));
+    assert_eq!(vm_instantiations(&host)?, (1, 0));
+    assert_eq!(call_add(&host, contract)?, 11);
+    assert_eq!(vm_instantiations(&host)?, (1, 1));
+    Ok(())
+}
+
+// The same invocations are metered identically without a cache, with a fresh
+// cache and with a cache warmed by a previous host.
+#[test]
+fn metering_does_not_depend_on_the_cache() -> Result<(), HostError> {
+    let run = |cache: Option<Rc<ModuleCache>>| -> Result<(u64, u64), HostError> {
+        let host = Host::test_host_with_recording_footprint();
+        if let Some(cache) = cache {
+            host.set_module_cache(cache)?;
+        }
+        let contract = host.register_test_contract_wasm(ADD_I32);
+        host.as_budget().reset_unlimited()?;
+        assert_eq!(call_add(&host, contract)?, 11);
+        assert_eq!(call_add(&host, contract)?, 11);
+        assert_eq!(vm_instantiations(&host)?, (1, 1));
+        let budget = host.as_budget();
+        Ok((
+            budget.get_cpu_insns_consumed()?,
+            budget.get_mem_bytes_consumed()?,
+        ))
+    };
+    let uncached = run(None)?;
+    let cache = Rc::new(ModuleCache::new(&Host::test_host())?);
+    assert_eq!(run(Some(cache.clone()))?, uncached);
+    assert!(cache.contains_module(&wasm_hash(ADD_I32)));
+    assert_eq!(run(Some(cache))?, uncached);
+    Ok(())
+}
+
+// A running contract may call a contract whose module isn't cached yet.
+#[test]
+fn module_is_parsed_during_a_call() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let cache = Rc::new(ModuleCache::new(&host)?);
+    host.set_module_cache(cache.clone())?;
+    let caller = host.register_test_contract_wasm(INVOKE_CONTRACT);
+    let callee = host.register_test_contract_wasm(ADD_I32);
+    let args = host.test_vec_obj::<i32>(&[4, 7])?;
+    let args = host.vec_push_back(args, callee.to_val())?;
+    let res = host.call(caller, Symbol::try_from_small_str("add_with")?, args)?;
+    assert_eq!(i32::try_from_val(&host, &res)?, 11);
+    assert!(cache.contains_module(&wasm_hash(INVOKE_CONTRACT)));
+    assert!(cache.contains_module(&wasm_hash(ADD_I32)));
+    Ok(())
+}
+
+#[test]
+fn invalid_module_is_not_cached() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let cache = ModuleCache::new(&host)?;
+    let hash = crate::xdr::Hash([0; 32]);
+    assert!(cache
+        .get_or_parse_module(&host, &hash, &[0, 1, 2, 3])
+        .is_err());
+    assert!(!cache.contains_module(&hash));
+    Ok(())
+}
+
+#[test]
+fn cache_is_bounded_by_code_size() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let cache = ModuleCache::with_max_code_size(&host, ADD_I32.len().max(VEC.len()))?;
+    let (add_hash, vec_hash) = (wasm_hash(ADD_I32), wasm_hash(VEC));
+    cache.get_or_parse_module(&host, &add_hash, ADD_I32)?;
+    assert!(cache.contains_module(&add_hash));
+    // Both modules don't fit, so the first one is evicted.
+    cache.get_or_parse_module(&host, &vec_hash, VEC)?;
+    assert!(cache.contains_module(&vec_hash));
+    assert!(!cache.contains_module(&add_hash));
+
+    // Modules larger than the bound are parsed, but not cached.
+    let cache = ModuleCache::with_max_code_size(&host, ADD_I32.len() - 1)?;
+    cache.get_or_parse_module(&host, &add_hash, ADD_I32)?;
+    assert!(!cache.contains_module(&add_hash));
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::{
+    fees::compute_write_fee_per_1kb,
+    network_config::NetworkConfig,
+    xdr::{
+        ConfigSettingContractBandwidthV0, ConfigSettingContractComputeV0,
+        ConfigSettingContractEventsV0, ConfigSettingContractHistoricalDataV0,
+        ConfigSettingContractLedgerCostV0, ConfigSettingEntry, ContractCostParamEntry,
+        ContractCostParams, ContractCostType, ExtensionPoint, ScErrorCode, ScErrorType,
+        StateArchivalSettings,
+    },
+};
+
+fn cost_params() -> ContractCostParams {
+    ContractCostParams(
+        ContractCostType::variants()
+            .iter()
+            .map(|_| ContractCostParamEntry {
+                ext: ExtensionPoint::V0,
+                const_term: 10,
+                linear_term: 0,
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn compute() -> ConfigSettingContractComputeV0 {
+    ConfigSettingContractComputeV0 {
+        ledger_max_instructions: 500_000_000,
+        tx_max_instructions: 100_000_000,
+        fee_rate_per_instructions_increment: 25,
+        tx_memory_limit: 40_000_000,
+    }
+}
+
+fn ledger_cost() -> ConfigSettingContractLedgerCostV0 {
+    ConfigSettingContractLedgerCostV0 {
+        ledger_max_read_ledger_entries: 200,
+        ledger_max_read_bytes: 500_000,
+        ledger_max_write_ledger_entries: 100,
+        ledger_max_write_bytes: 200_000,
+        tx_max_read_ledger_entries: 40,
+        tx_max_read_bytes: 200_000,
+        tx_max_write_ledger_entries: 25,
+        tx_max_write_bytes: 65_536,
+        fee_read_ledger_entry: 6250,
+        fee_write_ledger_entry: 10_000,
+        fee_read1_kb: 1786,
+        bucket_list_target_size_bytes: 1_000_000,
+        write_fee1_kb_bucket_list_low: 1000,
+        write_fee1_kb_bucket_list_high: 10_000,
+        bucket_list_write_fee_growth_factor: 1000,
+    }
+}
+
+fn state_archival() -> StateArchivalSettings {
+    StateArchivalSettings {
+        max_entry_ttl: 3_110_400,
+        min_temporary_ttl: 17_280,
+        min_persistent_ttl: 2_073_600,
+        persistent_rent_rate_denominator: 2103,
+        temp_rent_rate_denominator: 4206,
+        max_entries_to_archive: 1000,
+        bucket_list_size_window_sample_size: 30,
+        bucket_list_window_sample_period: 64,
+        eviction_scan_size: 100_000,
+        starting_eviction_scan_level: 6,
+    }
+}
+
+fn config_settings() -> Vec<ConfigSettingEntry> {
+    vec![
+        ConfigSettingEntry::ContractMaxSizeBytes(65_536),
+        ConfigSettingEntry::ContractComputeV0(compute()),
+        ConfigSettingEntry::ContractLedgerCostV0(ledger_cost()),
+        ConfigSettingEntry::ContractHistoricalDataV0(ConfigSettingContractHistoricalDataV0 {
+            fee_historical1_kb: 16_235,
+        }),
+        ConfigSettingEntry::ContractEventsV0(ConfigSettingContractEventsV0 {
+            tx_max_contract_events_size_bytes: 8198,
+            fee_contract_events1_kb: 10_000,
+        }),
+        ConfigSettingEntry::ContractBandwidthV0(ConfigSettingContractBandwidthV0 {
+            ledger_max_txs_size_bytes: 200_000,
+            tx_max_size_bytes: 70_000,
+            fee_tx_size1_kb: 1624,
+        }),
+        ConfigSettingEntry::ContractCostParamsCpuInstructions(cost_params()),
+        ConfigSettingEntry::ContractCostParamsMemoryBytes(cost_params()),
+        ConfigSettingEntry::StateArchival(state_archival()),
+        ConfigSettingEntry::BucketlistSizeWindow(
+            vec![400_000, 500_000, 600_000].try_into().unwrap(),
+        ),
+    ]
+}
+
+fn config_error(settings: &[ConfigSettingEntry]) -> crate::Error {
+    NetworkConfig::try_from_config_settings(settings)
+        .err()
+        .unwrap()
+        .error
+}
+
+#[test]
+fn network_config_from_config_settings() {
+    let config = NetworkConfig::try_from_config_settings(&config_settings()).unwrap();
+    assert_eq!(config.average_bucket_list_size, 500_000);
+    let fee_per_write_1kb = compute_write_fee_per_1kb(500_000, &config.write_fee_configuration);
+    assert_eq!(fee_per_write_1kb, 5500);
+    assert_eq!(
+        config.fee_configuration.fee_per_write_1kb,
+        fee_per_write_1kb
+    );
+    assert_eq!(
+        config.rent_fee_configuration.fee_per_write_1kb,
+        fee_per_write_1kb
+    );
+    assert_eq!(config.fee_configuration.fee_per_instruction_increment, 25);
+    assert_eq!(config.fee_configuration.fee_per_read_entry, 6250);
+    assert_eq!(config.fee_configuration.fee_per_historical_1kb, 16_235);
+    assert_eq!(config.fee_configuration.fee_per_contract_event_1kb, 10_000);
+    assert_eq!(config.fee_configuration.fee_per_transaction_size_1kb, 1624);
+    assert_eq!(
+        config
+            .rent_fee_configuration
+            .persistent_rent_rate_denominator,
+        2103
+    );
+    assert_eq!(config.min_temp_entry_ttl, 17_280);
+    assert_eq!(config.min_persistent_entry_ttl, 2_073_600);
+    assert_eq!(config.max_entry_ttl, 3_110_400);
+    assert_eq!(config.tx_max_contract_events_size_bytes, 8198);
+
+    let budget = config.create_budget().unwrap();
+    assert_eq!(budget.get_cpu_insns_remaining().unwrap(), 100_000_000);
+    assert_eq!(budget.get_mem_bytes_remaining().unwrap(), 40_000_000);
+}
+
+#[test]
+fn network_config_rejects_missing_and_duplicate_settings() {
+    let mut settings = config_settings();
+    settings.retain(|s| !matches!(s, ConfigSettingEntry::StateArchival(_)));
+    let err = config_error(&settings);
+    assert!(err.is_type(ScErrorType::Context) && err.is_code(ScErrorCode::MissingValue));
+
+    let mut settings = config_settings();
+    settings.push(ConfigSettingEntry::ContractComputeV0(compute()));
+    assert!(config_error(&settings).is_code(ScErrorCode::ExistingValue));
+}
+
+#[test]
+fn network_config_rejects_inconsistent_settings() {
+    let with_setting = |setting: ConfigSettingEntry| {
+        let mut settings = config_settings();
+        for s in settings.iter_mut() {
+            if s.discriminant() == setting.discriminant() {
+                *s = setting.clone();
+            }
+        }
+        config_error(&settings)
+    };
+
+    // Transaction limit above the ledger limit.
+    let mut c = compute();
+    c.tx_max_instructions = c.ledger_max_instructions + 1;
+    assert!(
+        with_setting(ConfigSettingEntry::ContractComputeV0(c)).is_code(ScErrorCode::InvalidInput)
+    );
+    // Negative fee rate.
+    let mut l = ledger_cost();
+    l.fee_read1_kb = -1;
+    assert!(with_setting(ConfigSettingEntry::ContractLedgerCostV0(l))
+        .is_code(ScErrorCode::InvalidInput));
+    // Write fee decreasing with the bucket list size.
+    let mut l = ledger_cost();
+    l.write_fee1_kb_bucket_list_low = l.write_fee1_kb_bucket_list_high + 1;
+    assert!(with_setting(ConfigSettingEntry::ContractLedgerCostV0(l))
+        .is_code(ScErrorCode::InvalidInput));
+    // Minimum TTL above the maximum TTL.
+    let mut a = state_archival();
+    a.min_persistent_ttl = a.max_entry_ttl + 1;
+    assert!(with_setting(ConfigSettingEntry::StateArchival(a)).is_code(ScErrorCode::InvalidInput));
+    // Zero rent rate denominator.
+    let mut a = state_archival();
+    a.temp_rent_rate_denominator = 0;
+    assert!(with_setting(ConfigSettingEntry::StateArchival(a)).is_code(ScErrorCode::InvalidInput));
+    // Empty bucket list size window.
+    assert!(
+        with_setting(ConfigSettingEntry::BucketlistSizeWindow(Default::default()))
+            .is_code(ScErrorCode::InvalidInput)
+    );
+}
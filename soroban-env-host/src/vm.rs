@@ -10,7 +10,8 @@
 
 mod dispatch;
 mod fuel_refillable;
-mod func_info;
+pub(crate) mod func_info;
+mod module_cache;
 
 #[cfg(feature = "bench")]
 pub(crate) use dispatch::dummy0;
@@ -21,13 +22,14 @@ use crate::{
     host::{error::TryBorrowOrErr, metered_clone::MeteredContainer},
     meta::{self, get_ledger_protocol_version},
     xdr::{ContractCostType, Hash, Limited, ReadXdr, ScEnvMetaEntry, ScErrorCode, ScErrorType},
-    ConversionError, Host, HostError, Symbol, SymbolStr, TryIntoVal, Val, WasmiMarshal,
-    DEFAULT_XDR_RW_LIMITS,
+    ConversionError, DiagnosticLevel, Host, HostError, Symbol, SymbolStr, TryIntoVal, Val,
+    WasmiMarshal, DEFAULT_XDR_RW_LIMITS,
 };
 use std::{cell::RefCell, io::Cursor, rc::Rc, time::Instant};
 
 use fuel_refillable::FuelRefillable;
-use func_info::HOST_FUNCTIONS;
+use func_info::{HostFuncInfo, HOST_FUNCTIONS};
+pub use module_cache::ModuleCache;
 
 use wasmi::{Engine, FuelConsumptionMode, Instance, Linker, Memory, Module, Store, Value};
 
@@ -52,10 +54,8 @@ const MAX_VM_ARGS: usize = 32;
 /// will fail.
 pub struct Vm {
     pub(crate) contract_id: Hash,
-    // TODO: consider moving store and possibly module to Host so they can be
-    // recycled across calls. Or possibly beyond, to be recycled across txs.
-    // https://github.com/stellar/rs-soroban-env/issues/827
-    module: Module,
+    // The module may be shared with a [ModuleCache] attached to the [Host].
+    module: Rc<Module>,
     store: RefCell<Store<Host>>,
     instance: Instance,
     pub(crate) memory: Option<Memory>,
@@ -177,25 +177,75 @@ impl Vm {
         }
     }
 
-    fn check_max_args(host: &Host, m: &Module) -> Result<(), HostError> {
-        for e in m.exports() {
-            match e.ty() {
-                wasmi::ExternType::Func(f) => {
-                    if f.params().len() > MAX_VM_ARGS || f.results().len() > MAX_VM_ARGS {
-                        return Err(host.err(
-                            ScErrorType::WasmVm,
-                            ScErrorCode::InvalidInput,
-                            "Too many arguments or results in wasm export",
-                            &[],
-                        ));
-                    }
-                }
-                _ => (),
+    // Checks that every host function imported by the module is available in
+    // the current ledger protocol. Imports that don't name a host function at
+    // all are left for the linker to reject.
+    pub(crate) fn check_host_function_imports(
+        host: &Host,
+        m: &Module,
+        host_functions: &[HostFuncInfo],
+    ) -> Result<(), HostError> {
+        let ledger_proto = host.get_ledger_protocol_version()?;
+        for import in m.imports() {
+            let Some(hf) = host_functions
+                .iter()
+                .find(|hf| hf.mod_str == import.module() && hf.fn_str == import.name())
+            else {
+                continue;
+            };
+            if !hf.is_supported_in(ledger_proto) {
+                return Err(err!(
+                    host,
+                    (ScErrorType::WasmVm, ScErrorCode::InvalidAction),
+                    "contract imports a host function not supported in the current protocol",
+                    ledger_proto,
+                    hf.min_proto.unwrap_or(0),
+                    hf.max_proto.unwrap_or(u32::MAX)
+                ));
             }
         }
         Ok(())
     }
 
+    fn has_too_many_args(m: &Module) -> bool {
+        m.exports().any(|e| match e.ty() {
+            wasmi::ExternType::Func(f) => {
+                f.params().len() > MAX_VM_ARGS || f.results().len() > MAX_VM_ARGS
+            }
+            _ => false,
+        })
+    }
+
+    fn check_max_args(host: &Host, m: &Module) -> Result<(), HostError> {
+        if Self::has_too_many_args(m) {
+            return Err(host.err(
+                ScErrorType::WasmVm,
+                ScErrorCode::InvalidInput,
+                "Too many arguments or results in wasm export",
+                &[],
+            ));
+        }
+        Ok(())
+    }
+
+    fn wasmi_config(host: &Host) -> Result<wasmi::Config, HostError> {
+        let mut config = wasmi::Config::default();
+        let fuel_costs = host.as_budget().wasmi_fuel_costs()?;
+
+        // Turn off most optional wasm features, leaving on some
+        // post-MVP features commonly enabled by Rust and Clang.
+        config
+            .wasm_multi_value(false)
+            .wasm_mutable_global(true)
+            .wasm_saturating_float_to_int(false)
+            .wasm_sign_extension(true)
+            .floats(false)
+            .consume_fuel(true)
+            .fuel_consumption_mode(FuelConsumptionMode::Eager)
+            .set_fuel_costs(fuel_costs);
+        Ok(config)
+    }
+
     /// Constructs a new instance of a [Vm] within the provided [Host],
     /// establishing a new execution context for a contract identified by
     /// `contract_id` with WASM bytecode provided in `module_wasm_code`.
@@ -205,6 +255,8 @@ impl Vm {
     ///   - Parses and performs WASM validation on the module.
     ///   - Checks that the module contains an [meta::INTERFACE_VERSION] that
     ///     matches the host.
+    ///   - Checks that every host function the module imports is available in
+    ///     the current ledger protocol.
     ///   - Checks that the module has no floating point code or `start`
     ///     function, or post-MVP wasm extensions.
     ///   - Instantiates the module, leaving it ready to accept function
@@ -227,22 +279,7 @@ impl Vm {
             Some(module_wasm_code.len() as u64),
         )?;
 
-        let mut config = wasmi::Config::default();
-        let fuel_costs = host.as_budget().wasmi_fuel_costs()?;
-
-        // Turn off most optional wasm features, leaving on some
-        // post-MVP features commonly enabled by Rust and Clang.
-        config
-            .wasm_multi_value(false)
-            .wasm_mutable_global(true)
-            .wasm_saturating_float_to_int(false)
-            .wasm_sign_extension(true)
-            .floats(false)
-            .consume_fuel(true)
-            .fuel_consumption_mode(FuelConsumptionMode::Eager)
-            .set_fuel_costs(fuel_costs);
-
-        let engine = Engine::new(&config);
+        let engine = Engine::new(&Self::wasmi_config(host)?);
         let module = {
             let _span0 = tracy_span!("parse module");
             host.map_err(Module::new(&engine, module_wasm_code))?
@@ -250,6 +287,7 @@ impl Vm {
 
         Self::check_max_args(host, &module)?;
         Self::check_meta_section(host, &module)?;
+        Self::check_host_function_imports(host, &module, HOST_FUNCTIONS)?;
 
         let mut store = Store::new(&engine, host.clone());
         store.limiter(|host| host);
@@ -268,6 +306,80 @@ impl Vm {
             }
         }
 
+        let vm = Self::instantiate(host, contract_id, Rc::new(module), store, &linker)?;
+
+        host.as_budget().track_time(
+            ContractCostType::VmInstantiation,
+            now.elapsed().as_nanos() as u64,
+        )?;
+        Ok(vm)
+    }
+
+    /// Constructs a new instance of a [Vm] for the contract code with hash
+    /// `code_hash`, reusing the parsed module from the [ModuleCache] attached
+    /// to the [Host] (or from the host's private cache if none is attached).
+    ///
+    /// The first instantiation of `code_hash` within the host is charged as
+    /// [ContractCostType::VmInstantiation], like [Vm::new], and every later
+    /// one as [ContractCostType::VmCachedInstantiation]. The charges only
+    /// depend on the instantiations done by this host, so metering is the same
+    /// whether the module was already cached (e.g. by some earlier host) or
+    /// not, and whether an embedder-supplied cache is attached or not.
+    pub(crate) fn new_with_cache(
+        host: &Host,
+        contract_id: Hash,
+        code_hash: &Hash,
+        module_wasm_code: &[u8],
+    ) -> Result<Rc<Self>, HostError> {
+        let _span = tracy_span!("Vm::new_with_cache");
+        let now = Instant::now();
+
+        let cost_type = if host
+            .try_borrow_instantiated_code_hashes()?
+            .contains(code_hash)
+        {
+            ContractCostType::VmCachedInstantiation
+        } else {
+            ContractCostType::VmInstantiation
+        };
+        host.charge_budget(cost_type, Some(module_wasm_code.len() as u64))?;
+
+        let cache = host.module_cache()?;
+        let cached = cache.get_or_parse_module(host, code_hash, module_wasm_code)?;
+
+        // The interface version and host function imports checks depend on
+        // the ledger protocol, so they are repeated for every instantiation
+        // rather than once per module.
+        Self::check_meta_section(host, &cached.module)?;
+        Self::check_host_function_imports(host, &cached.module, HOST_FUNCTIONS)?;
+
+        let mut store = Store::new(&cached.engine, host.clone());
+        store.limiter(|host| host);
+
+        let vm = Self::instantiate(
+            host,
+            contract_id,
+            Rc::clone(&cached.module),
+            store,
+            &cached.linker,
+        )?;
+
+        // Only the successfully instantiated code is charged as cached later
+        // on, so failing to parse the code is charged in full every time.
+        host.try_borrow_instantiated_code_hashes_mut()?
+            .insert(code_hash.clone());
+        host.as_budget()
+            .track_time(cost_type, now.elapsed().as_nanos() as u64)?;
+        Ok(vm)
+    }
+
+    fn instantiate(
+        host: &Host,
+        contract_id: Hash,
+        module: Rc<Module>,
+        mut store: Store<Host>,
+        linker: &Linker<Host>,
+    ) -> Result<Rc<Self>, HostError> {
         let not_started_instance = {
             let _span0 = tracy_span!("instantiate module");
             host.map_err(linker.instantiate(&mut store, &module))?
@@ -288,19 +400,13 @@ impl Vm {
         // Here we do _not_ supply the store with any fuel. Fuel is supplied
         // right before the VM is being run, i.e., before crossing the host->VM
         // boundary.
-        let vm = Rc::new(Self {
+        Ok(Rc::new(Self {
             contract_id,
             module,
             store: RefCell::new(store),
             instance,
             memory,
-        });
-
-        host.as_budget().track_time(
-            ContractCostType::VmInstantiation,
-            now.elapsed().as_nanos() as u64,
-        )?;
-        Ok(vm)
+        }))
     }
 
     pub(crate) fn get_memory(&self, host: &Host) -> Result<Memory, HostError> {
@@ -395,7 +501,7 @@ impl Vm {
                     if let Some(code) = trap.trap_code() {
                         let err = code.into();
                         let mut msg = Cow::Borrowed("VM call trapped");
-                        host.with_debug_mode(|| {
+                        host.with_diagnostic_level(DiagnosticLevel::Errors, || {
                             msg = Cow::Owned(format!("VM call trapped: {:?}", &code));
                             Ok(())
                         });
@@ -417,7 +523,7 @@ impl Vm {
                 }
                 e => {
                     let mut msg = Cow::Borrowed("VM call failed");
-                    host.with_debug_mode(|| {
+                    host.with_diagnostic_level(DiagnosticLevel::Errors, || {
                         msg = Cow::Owned(format!("VM call failed: {:?}", &e));
                         Ok(())
                     });
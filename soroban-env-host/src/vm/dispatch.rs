@@ -1,5 +1,6 @@
 use super::FuelRefillable;
 use crate::{
+    events::diagnostic::DiagnosticLevel,
     xdr::{ContractCostType, ScErrorCode, ScErrorType},
     CheckedEnvArg, EnvBase, Host, HostError, VmCaller, VmCallerEnv,
 };
@@ -112,7 +113,7 @@ macro_rules! generate_dispatch_functions {
                     // pattern-repetition matcher so that it will match all such
                     // descriptions.
                     $(#[$fn_attr:meta])*
-                    { $fn_str:literal, fn $fn_id:ident ($($arg:ident:$type:ty),*) -> $ret:ty }
+                    { $fn_str:literal, $($min_proto:literal)?, $($max_proto:literal)?, fn $fn_id:ident ($($arg:ident:$type:ty),*) -> $ret:ty }
                 )*
             }
         )*
@@ -180,6 +181,18 @@ macro_rules! generate_dispatch_functions {
                     // the host maintains control of the budget.
                     FuelRefillable::return_fuel_to_host(&mut caller, &host).map_err(|he| Trap::from(he))?;
 
+                    // Host function tracing is only done at the most verbose
+                    // diagnostic level; the arguments are formatted the same
+                    // way as for `env_call_hook` above.
+                    host.with_diagnostic_level(DiagnosticLevel::HostFnTrace, || {
+                        host.host_fn_call_diagnostics(&core::stringify!($fn_id), &[$(
+                            match <$type>::try_marshal_from_relative_value(Value::I64($arg), &host) {
+                                Ok(val) => format!("{:?}", val),
+                                Err(_) => format!("bad:{:?}", $arg),
+                            }
+                        ),*])
+                    });
+
                     // Charge for the host function dispatching: conversion between VM fuel and
                     // host budget, marshalling values. This does not account for the actual work
                     // being done in those functions, which are metered individually by the implementation.
@@ -205,6 +218,14 @@ macro_rules! generate_dispatch_functions {
                         host.env_ret_hook(&core::stringify!($fn_id), &res_str)?;
                     }
 
+                    host.with_diagnostic_level(DiagnosticLevel::HostFnTrace, || {
+                        let res_str = match &res {
+                            Ok(ok) => format!("{:?}", ok),
+                            Err(err) => format!("error:{:?}", err.error),
+                        };
+                        host.host_fn_return_diagnostics(&core::stringify!($fn_id), &res_str)
+                    });
+
                     // On the off chance we got an error with no context, we can
                     // at least attach some here "at each host function call",
                     // fairly systematically. This will cause the context to
@@ -1,7 +1,7 @@
 use super::dispatch;
 use crate::Host;
 use soroban_env_common::call_macro_with_all_host_functions;
-use wasmi::{Func, Store};
+use wasmi::{errors::LinkerError, Func, Linker, Store};
 
 pub(crate) struct HostFuncInfo {
     /// String name of the WASM module this host function is importable from.
@@ -11,10 +11,42 @@ pub(crate) struct HostFuncInfo {
     /// as.
     pub(crate) fn_str: &'static str,
 
+    /// First ledger protocol in which the host function is available, or
+    /// `None` if it has been available since the first protocol.
+    pub(crate) min_proto: Option<u32>,
+
+    /// Last ledger protocol in which the host function is available, or
+    /// `None` if it is available in all the later protocols.
+    pub(crate) max_proto: Option<u32>,
+
     /// Function that takes a wasmi::Store and _wraps_ a dispatch function
     /// for this host function, with the specific type of the dispatch function,
     /// into a Func in the Store.
     pub(crate) wrap: fn(&mut Store<Host>) -> Func,
+
+    /// Function that defines the dispatch function for this host function
+    /// in a wasmi::Linker, independent of any Store, so that the Linker can be
+    /// shared between many Stores (see [ModuleCache](super::ModuleCache)).
+    pub(crate) link: fn(&mut Linker<Host>) -> Result<(), LinkerError>,
+}
+
+impl HostFuncInfo {
+    /// Returns whether the host function is available in the ledger protocol
+    /// `proto`.
+    pub(crate) fn is_supported_in(&self, proto: u32) -> bool {
+        self.min_proto.map_or(true, |min| proto >= min)
+            && self.max_proto.map_or(true, |max| proto <= max)
+    }
+}
+
+// Turns the optional protocol bound passed by the x-macro into an `Option`.
+macro_rules! optional_proto {
+    () => {
+        None
+    };
+    ($proto:literal) => {
+        Some($proto)
+    };
 }
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -41,7 +73,7 @@ macro_rules! generate_host_function_infos {
                     // pattern-repetition matcher so that it will match all such
                     // descriptions.
                     $(#[$fn_attr:meta])*
-                    { $fn_id:literal, fn $func_id:ident $args:tt -> $ret:ty }
+                    { $fn_id:literal, $($min_proto:literal)?, $($max_proto:literal)?, fn $func_id:ident $args:tt -> $ret:ty }
                 )*
             }
         )*
@@ -77,7 +109,10 @@ macro_rules! generate_host_function_infos {
                     HostFuncInfo {
                         mod_str: $mod_str,
                         fn_str: $fn_id,
+                        min_proto: optional_proto!($($min_proto)?),
+                        max_proto: optional_proto!($($max_proto)?),
                         wrap: |store| Func::wrap(store, dispatch::$func_id),
+                        link: |linker| linker.func_wrap($mod_str, $fn_id, dispatch::$func_id).map(|_| ()),
                     },
                 )*
             )*
@@ -0,0 +1,188 @@
+use super::{func_info::HOST_FUNCTIONS, Vm};
+use crate::{
+    budget::{AsBudget, FuelConfig},
+    contract_spec::{ContractSpec, CONTRACT_SPEC_SECTION_NAME},
+    host::error::TryBorrowOrErr,
+    xdr::Hash,
+    Host, HostError,
+};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+use wasmi::{Engine, Linker, Module};
+
+/// A [ModuleCache] holds parsed and validated [wasmi::Module]s, keyed by the
+/// hash of the contract code they were parsed from. Instantiating a [Vm] from
+/// a cached module skips both parsing the module and populating a fresh
+/// linker.
+///
+/// A [ModuleCache] is attached to a [Host] with [Host::set_module_cache] and
+/// may be shared between many hosts (for example, all the hosts executing
+/// transactions in a single ledger), as long as they are all configured with
+/// the same wasmi fuel costs. A [Host] without an attached cache uses a
+/// private one, so the cache never affects metering.
+///
+/// Every module is parsed by a [wasmi::Engine] of its own: an engine can't
+/// parse new code while it's executing some, and a running contract may call
+/// a contract that hasn't been cached yet. This also means that evicting a
+/// module frees its code. The cache is bounded by the total size of the wasm
+/// code of the cached modules: once adding a module would exceed the bound,
+/// all the cached modules are evicted. The [Vm]s instantiated from the
+/// evicted modules are unaffected.
+pub struct ModuleCache {
+    fuel_config: FuelConfig,
+    max_code_size: usize,
+    modules: RefCell<BTreeMap<Hash, Rc<CachedModule>>>,
+    // Total size of the wasm code of `modules`.
+    code_size: RefCell<usize>,
+}
+
+/// A parsed module along with the engine it was parsed by and a linker that
+/// has all the host functions defined in it.
+pub(crate) struct CachedModule {
+    pub(crate) engine: Engine,
+    pub(crate) linker: Linker<Host>,
+    pub(crate) module: Rc<Module>,
+    // The decoded `contractspecv0` section, decoded on first use.
+    contract_spec: RefCell<Option<Rc<ContractSpec>>>,
+}
+
+impl CachedModule {
+    fn parse(host: &Host, wasm: &[u8]) -> Result<Self, HostError> {
+        let engine = Engine::new(&Vm::wasmi_config(host)?);
+        let mut linker = <Linker<Host>>::new(&engine);
+        {
+            let _span0 = tracy_span!("define host functions");
+            for hf in HOST_FUNCTIONS {
+                host.map_err((hf.link)(&mut linker).map_err(wasmi::Error::Linker))?;
+            }
+        }
+        let module = {
+            let _span0 = tracy_span!("parse module");
+            host.map_err(Module::new(&engine, wasm))?
+        };
+        Vm::check_max_args(host, &module)?;
+        Ok(Self {
+            engine,
+            linker,
+            module: Rc::new(module),
+            contract_spec: RefCell::new(None),
+        })
+    }
+
+    /// Returns the decoded `contractspecv0` section of the module (empty if
+    /// the module has none), decoding it on first use.
+    pub(crate) fn contract_spec(&self, host: &Host) -> Result<Rc<ContractSpec>, HostError> {
+        if let Some(spec) = self
+            .contract_spec
+            .try_borrow_or_err_with(host, "contract spec borrow failed")?
+            .as_ref()
+        {
+            return Ok(Rc::clone(spec));
+        }
+        let spec = Rc::new(ContractSpec::from_custom_section(
+            host.budget_ref(),
+            Vm::module_custom_section(&self.module, CONTRACT_SPEC_SECTION_NAME),
+        )?);
+        *self
+            .contract_spec
+            .try_borrow_mut_or_err_with(host, "contract spec borrow_mut failed")? =
+            Some(Rc::clone(&spec));
+        Ok(spec)
+    }
+}
+
+impl ModuleCache {
+    /// The default bound on the total size of the wasm code parsed by a
+    /// [ModuleCache], see [ModuleCache::with_max_code_size].
+    pub const DEFAULT_MAX_CODE_SIZE: usize = 64 * 1024 * 1024;
+
+    /// Constructs a new, empty [ModuleCache] whose engine is configured with
+    /// the fuel costs of the provided [Host]'s budget, bounded by
+    /// [ModuleCache::DEFAULT_MAX_CODE_SIZE].
+    pub fn new(host: &Host) -> Result<Self, HostError> {
+        Self::with_max_code_size(host, Self::DEFAULT_MAX_CODE_SIZE)
+    }
+
+    /// Like [ModuleCache::new], but evicts all the cached modules whenever
+    /// the total size of the parsed wasm code would exceed `max_code_size`.
+    /// Modules larger than `max_code_size` are never cached.
+    pub fn with_max_code_size(host: &Host, max_code_size: usize) -> Result<Self, HostError> {
+        Ok(Self {
+            fuel_config: host.as_budget().wasmi_fuel_config()?,
+            max_code_size,
+            modules: RefCell::new(BTreeMap::new()),
+            code_size: RefCell::new(0),
+        })
+    }
+
+    /// Returns true if the cache holds a parsed module for `code_hash`.
+    pub fn contains_module(&self, code_hash: &Hash) -> bool {
+        self.modules
+            .try_borrow()
+            .is_ok_and(|modules| modules.contains_key(code_hash))
+    }
+
+    pub(crate) fn is_compatible_with(&self, host: &Host) -> Result<bool, HostError> {
+        Ok(self.fuel_config == host.as_budget().wasmi_fuel_config()?)
+    }
+
+    /// Returns the module with `code_hash`, parsing `wasm` and adding it to
+    /// the cache if it's not already present. Nothing is charged here: the
+    /// caller charges for the instantiation the same way whether or not the
+    /// module was cached, see [Vm::new_with_cache].
+    ///
+    /// Modules that fail to parse or validate are not added, and the error
+    /// is returned.
+    pub(crate) fn get_or_parse_module(
+        &self,
+        host: &Host,
+        code_hash: &Hash,
+        wasm: &[u8],
+    ) -> Result<Rc<CachedModule>, HostError> {
+        if let Some(module) = self
+            .modules
+            .try_borrow_or_err_with(host, "module cache borrow failed")?
+            .get(code_hash)
+        {
+            return Ok(Rc::clone(module));
+        }
+        let module = Rc::new(CachedModule::parse(host, wasm)?);
+        if wasm.len() <= self.max_code_size {
+            let mut modules = self
+                .modules
+                .try_borrow_mut_or_err_with(host, "module cache borrow_mut failed")?;
+            let mut code_size = self
+                .code_size
+                .try_borrow_mut_or_err_with(host, "module cache borrow_mut failed")?;
+            if code_size.saturating_add(wasm.len()) > self.max_code_size {
+                modules.clear();
+                *code_size = 0;
+            }
+            modules.insert(code_hash.clone(), Rc::clone(&module));
+            *code_size = code_size.saturating_add(wasm.len());
+        }
+        Ok(module)
+    }
+
+    /// Returns the decoded `contractspecv0` section of the contract code with
+    /// `code_hash` that `vm` has been instantiated from. The section is only
+    /// decoded once per cached module.
+    pub(crate) fn get_contract_spec(
+        &self,
+        host: &Host,
+        code_hash: &Hash,
+        vm: &Vm,
+    ) -> Result<Rc<ContractSpec>, HostError> {
+        let module = self
+            .modules
+            .try_borrow_or_err_with(host, "module cache borrow failed")?
+            .get(code_hash)
+            .cloned();
+        match module {
+            Some(module) => module.contract_spec(host),
+            None => Ok(Rc::new(ContractSpec::from_custom_section(
+                host.budget_ref(),
+                vm.custom_section(CONTRACT_SPEC_SECTION_NAME),
+            )?)),
+        }
+    }
+}
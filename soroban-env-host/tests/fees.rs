@@ -1,9 +1,10 @@
 use soroban_env_common::xdr::{Hash, LedgerEntry, LedgerEntryData, LedgerEntryExt, WriteXdr};
 use soroban_env_host::{
     fees::{
-        compute_rent_fee, compute_transaction_resource_fee, compute_write_fee_per_1kb,
-        FeeConfiguration, LedgerEntryRentChange, RentFeeConfiguration, TransactionResources,
-        WriteFeeConfiguration, TTL_ENTRY_SIZE,
+        compute_max_rent_extension, compute_optimal_rent_extension, compute_rent_fee,
+        compute_rent_fee_for_extension, compute_transaction_resource_fee,
+        compute_write_fee_per_1kb, FeeConfiguration, LedgerEntryRentChange, RentExtensionEntry,
+        RentFeeConfiguration, TransactionResources, WriteFeeConfiguration, TTL_ENTRY_SIZE,
     },
     xdr::TtlEntry,
     DEFAULT_XDR_RW_LIMITS,
@@ -904,3 +905,122 @@ fn test_compute_write_fee() {
         1_000_000_000 + 50 * (1_000_000_000 - 1_000_000) / 2
     );
 }
+
+#[test]
+fn test_rent_fee_for_extension() {
+    let fee_config = RentFeeConfiguration {
+        fee_per_write_entry: 10,
+        fee_per_write_1kb: 1000,
+        persistent_rent_rate_denominator: 10_000,
+        temporary_rent_rate_denominator: 100_000,
+    };
+    let entries = vec![
+        RentExtensionEntry {
+            is_persistent: true,
+            size_bytes: 10 * 1024,
+            live_until_ledger: 100_000,
+        },
+        RentExtensionEntry {
+            is_persistent: false,
+            size_bytes: 1024,
+            live_until_ledger: 150_000,
+        },
+    ];
+
+    assert_eq!(
+        compute_rent_fee_for_extension(&entries, 300_000, &fee_config, 50_000),
+        // Persistent rent: ceil(10 * 1024 * 1000 * 200_000 / (10_000 * 1024)) (=200_000) +
+        // Temp rent: ceil(1024 * 1000 * 150_000 / (100_000 * 1024)) (=1500) +
+        // Expiration entry write bytes: ceil(1000 * 2 * 48 / 1024) (=94) +
+        // Expiration entry writes: 2 * 10
+        200_000 + 1500 + 94 + 20
+    );
+    // Only the persistent entry is extended.
+    assert_eq!(
+        compute_rent_fee_for_extension(&entries, 100_001, &fee_config, 50_000),
+        // Rent: 1 + expiration entry write bytes: 47 + expiration entry write: 10
+        1 + 47 + 10
+    );
+    // Nothing is extended.
+    assert_eq!(
+        compute_rent_fee_for_extension(&entries, 100_000, &fee_config, 50_000),
+        0
+    );
+
+    let max_extension = |fee_budget| {
+        let extension =
+            compute_max_rent_extension(&entries, fee_budget, 1_000_000, &fee_config, 50_000);
+        (extension.live_until_ledger, extension.fee)
+    };
+    // Exact fee of the extension to 300_000.
+    assert_eq!(max_extension(201_614), (300_000, 201_614));
+    // Just not enough for 300_000.
+    assert_eq!(max_extension(201_613), (299_999, 201_613));
+    // Not enough to extend anything.
+    assert_eq!(max_extension(57), (100_000, 0));
+    assert_eq!(max_extension(58), (100_001, 58));
+    // Capped by the maximum live until ledger.
+    assert_eq!(max_extension(i64::MAX).0, 1_000_000);
+    assert_eq!(
+        max_extension(i64::MAX).1,
+        compute_rent_fee_for_extension(&entries, 1_000_000, &fee_config, 50_000)
+    );
+}
+
+#[test]
+fn test_optimal_rent_extension() {
+    let fee_config = RentFeeConfiguration {
+        fee_per_write_entry: 10_000,
+        fee_per_write_1kb: 1000,
+        persistent_rent_rate_denominator: 10_000,
+        temporary_rent_rate_denominator: 100_000,
+    };
+    let small_entry = || RentExtensionEntry {
+        is_persistent: true,
+        size_bytes: 1024,
+        live_until_ledger: 100_000,
+    };
+    let large_entry = || RentExtensionEntry {
+        is_persistent: true,
+        size_bytes: 100 * 1024,
+        live_until_ledger: 150_000,
+    };
+    let optimal = |entries: &[RentExtensionEntry], max_live_until_ledger| {
+        let extension =
+            compute_optimal_rent_extension(entries, max_live_until_ledger, &fee_config, 50_000);
+        (extension.live_until_ledger, extension.fee)
+    };
+
+    // The constant fee of writing the TTL entry makes the longest extension
+    // of a single entry the cheapest per ledger.
+    let entries = [small_entry()];
+    assert_eq!(
+        optimal(&entries, 300_000),
+        (
+            300_000,
+            compute_rent_fee_for_extension(&entries, 300_000, &fee_config, 50_000)
+        )
+    );
+
+    // Extending the large entry costs much more per ledger than the small one
+    // alone, so the set is best extended until the large entry starts being
+    // extended.
+    let entries = [small_entry(), large_entry()];
+    assert_eq!(
+        optimal(&entries, 300_000),
+        (
+            150_000,
+            compute_rent_fee_for_extension(&entries, 150_000, &fee_config, 50_000)
+        )
+    );
+    // No other extension is cheaper per ledger.
+    let (_, best_fee) = optimal(&entries, 300_000);
+    for live_until_ledger in 100_001..=300_000u32 {
+        let fee = compute_rent_fee_for_extension(&entries, live_until_ledger, &fee_config, 50_000);
+        assert!(fee as i128 * 50_000 >= best_fee as i128 * (live_until_ledger - 100_000) as i128);
+    }
+
+    // Nothing can be extended.
+    assert_eq!(optimal(&entries, 100_000), (100_000, 0));
+    assert_eq!(optimal(&[], 100_000), (100_000, 0));
+}
@@ -56,6 +56,15 @@ pub fn generate(file_lit: LitStr) -> Result<TokenStream, Error> {
                 ));
             }
 
+            if let (Some(min), Some(max)) = (f.min_supported_protocol, f.max_supported_protocol) {
+                if min > max {
+                    return Err(Error::new(
+                        file_lit.span(),
+                        format!("invalid supported protocol range in '{file_str}': {path_name} has min_supported_protocol {min} greater than max_supported_protocol {max}"),
+                    ));
+                }
+            }
+
             match export_names.entry(export_name.clone()) {
                 Entry::Occupied(existing) => {
                     let existing_name = existing.get();
@@ -91,9 +100,18 @@ pub fn generate(file_lit: LitStr) -> Result<TokenStream, Error> {
 
             let r#return = format_ident!("{}", &f.r#return);
 
+            // Protocol bounds are emitted as bare literals, or nothing when
+            // the function is not bounded.
+            let min_proto = f
+                .min_supported_protocol
+                .map(proc_macro2::Literal::u32_unsuffixed);
+            let max_proto = f
+                .max_supported_protocol
+                .map(proc_macro2::Literal::u32_unsuffixed);
+
             quote! {
                 #[doc = #docs]
-                { #export, fn #name(#(#args),*) -> #r#return }
+                { #export, #min_proto, #max_proto, fn #name(#(#args),*) -> #r#return }
             }
         });
 
@@ -120,12 +138,15 @@ pub fn generate(file_lit: LitStr) -> Result<TokenStream, Error> {
                     //
                     //  mod $mod_id:ident $mod_str:literal {
                     //     ...
-                    //     { $fn_str:literal, fn $fn_id:ident $args:tt -> $ret:ty }
+                    //     { $fn_str:literal, $($min_proto:literal)?, $($max_proto:literal)?, fn $fn_id:ident $args:tt -> $ret:ty }
                     //     ...
                     //  }
                     //
                     // Where the sub token-tree $args:tt is a normal parenthesized
-                    // argument list of comma-separated arg:type pairs
+                    // argument list of comma-separated arg:type pairs, and the
+                    // optional $min_proto and $max_proto literals are the first
+                    // and last ledger protocols in which the function is
+                    // available.
 
                     #(#modules)*
                 }
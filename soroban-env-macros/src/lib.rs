@@ -99,6 +99,12 @@ pub(crate) struct Function {
     pub(crate) args: Vec<Arg>,
     pub(crate) r#return: String,
     pub(crate) docs: Option<String>,
+    /// First protocol in which the function is available, if it hasn't been
+    /// available since the first protocol.
+    pub(crate) min_supported_protocol: Option<u32>,
+    /// Last protocol in which the function is available, if it has been
+    /// removed.
+    pub(crate) max_supported_protocol: Option<u32>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
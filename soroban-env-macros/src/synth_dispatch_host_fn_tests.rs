@@ -80,6 +80,26 @@ fn dfs(edges: &BTreeMap<String, BTreeSet<String>>, ty: &String, set: &mut BTreeS
     }
 }
 
+// Functions that are not available in every ledger protocol can only be
+// imported by the test contract if the test host runs at a protocol that
+// supports them, so their tests move the protocol of the host into the
+// supported range. If the host can't run the test contract at that protocol
+// the test fails rather than passing without exercising the function.
+fn clamp_to_supported_protocol(f: &Function) -> TokenStream {
+    let clamps: Vec<TokenStream> = f
+        .min_supported_protocol
+        .map(|min| quote! { .max(#min) })
+        .into_iter()
+        .chain(f.max_supported_protocol.map(|max| quote! { .min(#max) }))
+        .collect();
+    if clamps.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        host.with_mut_ledger_info(|li| li.protocol_version = li.protocol_version #(#clamps)*)?;
+    }
+}
+
 // This requires the input to be a valid signature
 const SPECIAL_CASES: [&str; 1] = ["recover_key_ecdsa_secp256k1"];
 
@@ -236,8 +256,16 @@ pub fn generate_hostfn_call_with_wrong_types(file_lit: LitStr) -> Result<TokenSt
         .filter(|entry| all_test_candidate_types.contains(entry.0))
         .map(|entry| {
             let target_type = entry.0;
-            // we just always pick the first function, instead of a random one
-            let (target_fn, target_arg_pos) = entry.1.get(0).unwrap();
+            // we just always pick the first function available in every
+            // protocol (if there is one), instead of a random one
+            let (target_fn, target_arg_pos) = entry
+                .1
+                .iter()
+                .find(|(f, _)| {
+                    f.min_supported_protocol.is_none() && f.max_supported_protocol.is_none()
+                })
+                .unwrap_or_else(|| entry.1.get(0).unwrap());
+            let set_protocol = clamp_to_supported_protocol(target_fn);
             let wasm_module = format_ident!("wasm_module_calling_{}", &target_fn.name);
             let test_wrong_arg_type =
                 format_ident!("dispatch_with_wrong_arg_type_{}", &target_fn.name);
@@ -295,6 +323,7 @@ pub fn generate_hostfn_call_with_wrong_types(file_lit: LitStr) -> Result<TokenSt
                 fn #test_wrong_arg_type() -> Result<(), HostError> {
                     let wasm = #wasm_module();
                     let host = observe_host!(Host::test_host_with_recording_footprint());
+                    #set_protocol
                     host.as_budget().reset_unlimited()?;
                     let contract_id_obj = host.register_test_contract_wasm(wasm.as_slice());
                     #(#calls)*
@@ -325,18 +354,21 @@ pub fn generate_hostfn_call_with_invalid_obj_handles(
         .iter()
         .flat_map(|m| m.functions.clone().into_iter())
         .flat_map(|f| {
-            f.args
-                .clone()
-                .into_iter()
-                .enumerate()
-                .map(move |(i, a)| ((f.name.clone(), f.args.clone()), (i, a)))
+            let set_protocol = clamp_to_supported_protocol(&f);
+            f.args.clone().into_iter().enumerate().map(move |(i, a)| {
+                (
+                    (f.name.clone(), f.args.clone(), set_protocol.clone()),
+                    (i, a),
+                )
+            })
         })
-        .filter(|((f_name, _), (_, arg))| {
+        .filter(|((f_name, _, _), (_, arg))| {
             !special_case_fns.contains(f_name) && arg.r#type.ends_with("Object")
         })
         .map(|(f_info, (pos, _))| {
             let wasm_module = format_ident!("wasm_module_calling_{}", f_info.0);
             let fn_ident = format_ident!("invalid_object_handle_{}_arg_{}", f_info.0, pos);
+            let set_protocol = f_info.2;
 
             let args = f_info.1.iter().enumerate().map(|(i, a)| {
                 let ty_ident = format_ident!("{}", a.r#type);
@@ -364,6 +396,7 @@ pub fn generate_hostfn_call_with_invalid_obj_handles(
                 fn #fn_ident() -> Result<(), HostError> {
                     let wasm = #wasm_module();
                     let host = observe_host!(Host::test_host_with_recording_footprint());
+                    #set_protocol
                     host.as_budget().reset_unlimited()?;
                     let contract_id_obj = host.register_test_contract_wasm(wasm.as_slice());
 